@@ -0,0 +1,7 @@
+-- Optional declarative scraping configuration, used by the generic strategy
+-- for websites that have no built-in implementation
+ALTER TABLE website ADD COLUMN base_url TEXT;
+ALTER TABLE website ADD COLUMN chapter_url_template TEXT;
+ALTER TABLE website ADD COLUMN chapter_selector TEXT;
+ALTER TABLE website ADD COLUMN chapter_attribute TEXT;
+ALTER TABLE website ADD COLUMN external_id_regex TEXT;
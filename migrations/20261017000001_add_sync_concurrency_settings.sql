@@ -0,0 +1,4 @@
+-- Add default settings for concurrent sync and per-domain politeness
+INSERT OR IGNORE INTO setting (key, value) VALUES ('SYNC_CONCURRENCY', '8');
+INSERT OR IGNORE INTO setting (key, value) VALUES ('SYNC_DOMAIN_CONCURRENCY', '1');
+INSERT OR IGNORE INTO setting (key, value) VALUES ('SYNC_DOMAIN_DELAY_MS', '1000');
@@ -0,0 +1,30 @@
+-- Create sync_run table, one row per sync job execution
+CREATE TABLE IF NOT EXISTS sync_run (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    triggered_by TEXT NOT NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP,
+    total_sources INTEGER NOT NULL DEFAULT 0,
+    success_count INTEGER NOT NULL DEFAULT 0,
+    error_count INTEGER NOT NULL DEFAULT 0,
+    new_chapters INTEGER NOT NULL DEFAULT 0
+);
+
+-- Create sync_result table, one row per source synced during a run.
+-- Results are kept when the source is deleted so the history stays readable.
+CREATE TABLE IF NOT EXISTS sync_result (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sync_run_id INTEGER NOT NULL,
+    source_id INTEGER,
+    manga_name TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    new_chapters INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (sync_run_id) REFERENCES sync_run(id) ON DELETE CASCADE,
+    FOREIGN KEY (source_id) REFERENCES source(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_result_run ON sync_result(sync_run_id);
+CREATE INDEX IF NOT EXISTS idx_sync_result_source ON sync_result(source_id);
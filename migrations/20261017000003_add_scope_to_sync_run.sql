@@ -0,0 +1,3 @@
+-- Record what a sync run covers: every source, one manga or one source
+ALTER TABLE sync_run ADD COLUMN scope TEXT NOT NULL DEFAULT 'all';
+ALTER TABLE sync_run ADD COLUMN scope_id INTEGER;
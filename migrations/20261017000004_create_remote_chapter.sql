@@ -0,0 +1,15 @@
+-- Create remote_chapter table, the chapter list scraped from each source.
+-- position is the index in the list as published by the site (0 = newest).
+CREATE TABLE IF NOT EXISTS remote_chapter (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source_id INTEGER NOT NULL,
+    href TEXT NOT NULL,
+    number REAL,
+    title TEXT,
+    position INTEGER NOT NULL,
+    first_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (source_id) REFERENCES source(id) ON DELETE CASCADE,
+    UNIQUE (source_id, href)
+);
+
+CREATE INDEX IF NOT EXISTS idx_remote_chapter_position ON remote_chapter(source_id, position);
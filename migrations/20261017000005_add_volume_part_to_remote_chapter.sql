@@ -0,0 +1,3 @@
+-- Store the full parsed chapter identifier next to its numeric part.
+ALTER TABLE remote_chapter ADD COLUMN volume INTEGER;
+ALTER TABLE remote_chapter ADD COLUMN part TEXT;
@@ -0,0 +1,15 @@
+-- Create api_key table: named keys with a scope, stored as SHA-256 hashes.
+-- scope is 'read' (GET only), 'write' (everything but /key and /setting) or 'admin'.
+-- The legacy secret/key.pub key keeps working with the admin scope.
+CREATE TABLE IF NOT EXISTS api_key (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    scope TEXT NOT NULL CHECK (scope IN ('read', 'write', 'admin')),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+-- A name can be reused once the key holding it is revoked
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_key_active_name ON api_key(name) WHERE revoked_at IS NULL;
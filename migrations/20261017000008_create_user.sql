@@ -0,0 +1,52 @@
+-- Users owning manga libraries. Existing manga and API keys are given to
+-- the default user, which the legacy key.pub key also authenticates as.
+
+CREATE TABLE user (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT UNIQUE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO user (id, name) VALUES (1, 'default');
+
+-- Manga names are now unique per user, so the table is recreated.
+-- Migrations run in a transaction where foreign keys cannot be turned off,
+-- so dropping the old table deletes the rows referencing it. They are copied
+-- beforehand and restored once the new table is in place.
+CREATE TEMP TABLE chapter_backup AS SELECT * FROM chapter;
+CREATE TEMP TABLE source_backup AS SELECT * FROM source;
+CREATE TEMP TABLE remote_chapter_backup AS SELECT * FROM remote_chapter;
+CREATE TEMP TABLE sync_result_source_backup AS SELECT id, source_id FROM sync_result WHERE source_id IS NOT NULL;
+
+CREATE TABLE manga_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL DEFAULT 1,
+    name TEXT NOT NULL,
+    cover TEXT NOT NULL,
+    cover_small TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE,
+    UNIQUE (user_id, name)
+);
+
+INSERT INTO manga_new (id, user_id, name, cover, cover_small)
+SELECT id, 1, name, cover, cover_small FROM manga;
+
+DROP TABLE manga;
+
+ALTER TABLE manga_new RENAME TO manga;
+
+INSERT INTO chapter SELECT * FROM chapter_backup;
+INSERT INTO source SELECT * FROM source_backup;
+INSERT INTO remote_chapter SELECT * FROM remote_chapter_backup;
+UPDATE sync_result SET source_id = (
+    SELECT b.source_id FROM sync_result_source_backup b WHERE b.id = sync_result.id
+) WHERE id IN (SELECT id FROM sync_result_source_backup);
+
+DROP TABLE chapter_backup;
+DROP TABLE source_backup;
+DROP TABLE remote_chapter_backup;
+DROP TABLE sync_result_source_backup;
+
+-- Foreign keys are enforced, so an added REFERENCES column must default to NULL
+ALTER TABLE api_key ADD COLUMN user_id INTEGER REFERENCES user(id) ON DELETE CASCADE;
+UPDATE api_key SET user_id = 1;
@@ -0,0 +1,33 @@
+-- A source path was unique per website, which prevented two users from
+-- following the same series on the same website. It is now unique per manga.
+-- As with the manga table, rows referencing the old table are copied before
+-- it is dropped and restored afterwards.
+CREATE TEMP TABLE remote_chapter_backup AS SELECT * FROM remote_chapter;
+CREATE TEMP TABLE sync_result_source_backup AS SELECT id, source_id FROM sync_result WHERE source_id IS NOT NULL;
+
+CREATE TABLE source_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    manga_id INTEGER NOT NULL,
+    website_id INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    external_manga_id TEXT,
+    number_unread_chapter INTEGER DEFAULT 0,
+    FOREIGN KEY (manga_id) REFERENCES manga(id) ON DELETE CASCADE,
+    FOREIGN KEY (website_id) REFERENCES website(id) ON DELETE CASCADE,
+    UNIQUE (manga_id, website_id, path)
+);
+
+INSERT INTO source_new (id, manga_id, website_id, path, external_manga_id, number_unread_chapter)
+SELECT id, manga_id, website_id, path, external_manga_id, number_unread_chapter FROM source;
+
+DROP TABLE source;
+
+ALTER TABLE source_new RENAME TO source;
+
+INSERT INTO remote_chapter SELECT * FROM remote_chapter_backup;
+UPDATE sync_result SET source_id = (
+    SELECT b.source_id FROM sync_result_source_backup b WHERE b.id = sync_result.id
+) WHERE id IN (SELECT id FROM sync_result_source_backup);
+
+DROP TABLE remote_chapter_backup;
+DROP TABLE sync_result_source_backup;
@@ -0,0 +1,6 @@
+-- Scheduled database snapshots, written with VACUUM INTO. Only the most
+-- recent BACKUP_RETENTION snapshots are kept (0 keeps all of them), and an
+-- empty CRON_BACKUP disables the job.
+INSERT OR IGNORE INTO setting (key, value) VALUES ('CRON_BACKUP', '0 0 3 * * *');
+INSERT OR IGNORE INTO setting (key, value) VALUES ('BACKUP_RETENTION', '7');
+INSERT OR IGNORE INTO setting (key, value) VALUES ('BACKUP_DIR', 'secret/backups');
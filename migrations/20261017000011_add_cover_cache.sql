@@ -0,0 +1,6 @@
+-- Covers are downloaded and stored under COVER_DIR, named after the SHA-256
+-- of their content, which is also their ETag. NULL until a cover is cached.
+ALTER TABLE manga ADD COLUMN cover_hash TEXT;
+ALTER TABLE manga ADD COLUMN cover_small_hash TEXT;
+
+INSERT OR IGNORE INTO setting (key, value) VALUES ('COVER_DIR', 'secret/covers');
@@ -0,0 +1,45 @@
+-- Descriptive metadata of a manga. Authors and genres are shared between
+-- manga, alternative titles belong to a single one.
+ALTER TABLE manga ADD COLUMN description TEXT;
+ALTER TABLE manga ADD COLUMN status TEXT CHECK (status IN ('ongoing', 'completed', 'hiatus'));
+ALTER TABLE manga ADD COLUMN year INTEGER;
+
+CREATE TABLE author (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE manga_author (
+    manga_id INTEGER NOT NULL,
+    author_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (manga_id, author_id),
+    FOREIGN KEY (manga_id) REFERENCES manga(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES author(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_manga_author_author ON manga_author(author_id);
+
+CREATE TABLE genre (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE manga_genre (
+    manga_id INTEGER NOT NULL,
+    genre_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (manga_id, genre_id),
+    FOREIGN KEY (manga_id) REFERENCES manga(id) ON DELETE CASCADE,
+    FOREIGN KEY (genre_id) REFERENCES genre(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_manga_genre_genre ON manga_genre(genre_id);
+
+CREATE TABLE manga_alt_title (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    manga_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    FOREIGN KEY (manga_id) REFERENCES manga(id) ON DELETE CASCADE,
+    UNIQUE (manga_id, title)
+);
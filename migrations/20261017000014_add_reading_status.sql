@@ -0,0 +1,14 @@
+-- Where the user is with each manga. started_at is set the first time it is
+-- read, completed_at each time it is finished.
+ALTER TABLE manga ADD COLUMN reading_status TEXT NOT NULL DEFAULT 'plan_to_read'
+    CHECK (reading_status IN ('plan_to_read', 'reading', 'completed', 'on_hold', 'dropped'));
+ALTER TABLE manga ADD COLUMN started_at TIMESTAMP;
+ALTER TABLE manga ADD COLUMN completed_at TIMESTAMP;
+
+-- Manga with a reading history are being read
+UPDATE manga SET
+    reading_status = 'reading',
+    started_at = (SELECT MIN(c.updated_at) FROM chapter c WHERE c.manga_id = manga.id)
+WHERE EXISTS (SELECT 1 FROM chapter c WHERE c.manga_id = manga.id);
+
+CREATE INDEX idx_manga_reading_status ON manga(user_id, reading_status);
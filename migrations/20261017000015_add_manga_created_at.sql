@@ -0,0 +1,15 @@
+-- When each manga was added to the library. A column added to an existing
+-- table cannot default to CURRENT_TIMESTAMP, so new rows get it from a trigger.
+ALTER TABLE manga ADD COLUMN created_at TIMESTAMP;
+
+-- Existing manga were added at the latest when they were first read
+UPDATE manga SET created_at = COALESCE(
+    (SELECT MIN(c.updated_at) FROM chapter c WHERE c.manga_id = manga.id),
+    CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER manga_created_at AFTER INSERT ON manga
+WHEN NEW.created_at IS NULL
+BEGIN
+    UPDATE manga SET created_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
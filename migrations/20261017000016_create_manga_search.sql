@@ -0,0 +1,48 @@
+-- Full-text index of manga names and alternative titles, one row per manga
+-- with the manga id as rowid. Alternative titles are stored one per line.
+-- Matching ignores case and diacritics.
+CREATE VIRTUAL TABLE manga_search USING fts5(
+    name,
+    alt_titles,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO manga_search (rowid, name, alt_titles)
+SELECT m.id, m.name, (SELECT group_concat(t.title, char(10)) FROM manga_alt_title t WHERE t.manga_id = m.id)
+FROM manga m;
+
+CREATE TRIGGER manga_search_insert AFTER INSERT ON manga
+BEGIN
+    INSERT INTO manga_search (rowid, name, alt_titles) VALUES (NEW.id, NEW.name, NULL);
+END;
+
+CREATE TRIGGER manga_search_update AFTER UPDATE OF name ON manga
+BEGIN
+    UPDATE manga_search SET name = NEW.name WHERE rowid = NEW.id;
+END;
+
+CREATE TRIGGER manga_search_delete AFTER DELETE ON manga
+BEGIN
+    DELETE FROM manga_search WHERE rowid = OLD.id;
+END;
+
+CREATE TRIGGER manga_search_alt_title_insert AFTER INSERT ON manga_alt_title
+BEGIN
+    UPDATE manga_search
+    SET alt_titles = (SELECT group_concat(t.title, char(10)) FROM manga_alt_title t WHERE t.manga_id = NEW.manga_id)
+    WHERE rowid = NEW.manga_id;
+END;
+
+CREATE TRIGGER manga_search_alt_title_delete AFTER DELETE ON manga_alt_title
+BEGIN
+    UPDATE manga_search
+    SET alt_titles = (SELECT group_concat(t.title, char(10)) FROM manga_alt_title t WHERE t.manga_id = OLD.manga_id)
+    WHERE rowid = OLD.manga_id;
+END;
+
+CREATE TRIGGER manga_search_alt_title_update AFTER UPDATE ON manga_alt_title
+BEGIN
+    UPDATE manga_search
+    SET alt_titles = (SELECT group_concat(t.title, char(10)) FROM manga_alt_title t WHERE t.manga_id = manga_search.rowid)
+    WHERE rowid IN (OLD.manga_id, NEW.manga_id);
+END;
@@ -0,0 +1,32 @@
+-- Sync history and webhooks were shared by every user. Webhooks now belong
+-- to a user, sync results to the owner of the synced manga, and a run is
+-- shown to each user whose sources it covers along with that user's share.
+-- Existing webhooks and runs without results are given to the default user.
+
+ALTER TABLE webhook ADD COLUMN user_id INTEGER REFERENCES user(id) ON DELETE CASCADE;
+UPDATE webhook SET user_id = 1;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_user ON webhook(user_id);
+
+ALTER TABLE sync_result ADD COLUMN user_id INTEGER REFERENCES user(id) ON DELETE CASCADE;
+UPDATE sync_result SET user_id = COALESCE(
+    (SELECT m.user_id FROM source s JOIN manga m ON m.id = s.manga_id WHERE s.id = sync_result.source_id),
+    1
+);
+
+CREATE TABLE IF NOT EXISTS sync_run_user (
+    sync_run_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    total_sources INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (sync_run_id, user_id),
+    FOREIGN KEY (sync_run_id) REFERENCES sync_run(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_run_user_user ON sync_run_user(user_id);
+
+INSERT INTO sync_run_user (sync_run_id, user_id, total_sources)
+SELECT sync_run_id, user_id, COUNT(*) FROM sync_result GROUP BY sync_run_id, user_id;
+
+INSERT OR IGNORE INTO sync_run_user (sync_run_id, user_id, total_sources)
+SELECT id, 1, total_sources FROM sync_run WHERE id NOT IN (SELECT sync_run_id FROM sync_result);
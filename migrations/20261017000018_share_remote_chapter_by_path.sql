@@ -0,0 +1,43 @@
+-- Scraped chapter lists were stored per source, so a page followed by
+-- several users was stored once per user. They are now keyed by the website
+-- and path they were scraped from and shared by every source following it.
+-- When lists are merged, each chapter keeps the row first seen.
+CREATE TABLE remote_chapter_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    website_id INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    href TEXT NOT NULL,
+    volume INTEGER,
+    number REAL,
+    part TEXT,
+    title TEXT,
+    position INTEGER NOT NULL,
+    first_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (website_id) REFERENCES website(id) ON DELETE CASCADE,
+    UNIQUE (website_id, path, href)
+);
+
+-- The bare columns come from the row holding the earliest first_seen_at
+INSERT INTO remote_chapter_new (id, website_id, path, href, volume, number, part, title, position, first_seen_at)
+SELECT rc.id, s.website_id, s.path, rc.href, rc.volume, rc.number, rc.part, rc.title, rc.position, MIN(rc.first_seen_at)
+FROM remote_chapter rc JOIN source s ON s.id = rc.source_id
+GROUP BY s.website_id, s.path, rc.href;
+
+DROP TABLE remote_chapter;
+
+ALTER TABLE remote_chapter_new RENAME TO remote_chapter;
+
+CREATE INDEX IF NOT EXISTS idx_remote_chapter_position ON remote_chapter(website_id, path, position);
+
+-- A list is deleted with the last source following its page
+CREATE TRIGGER remote_chapter_source_delete AFTER DELETE ON source
+WHEN NOT EXISTS (SELECT 1 FROM source s WHERE s.website_id = OLD.website_id AND s.path = OLD.path)
+BEGIN
+    DELETE FROM remote_chapter WHERE website_id = OLD.website_id AND path = OLD.path;
+END;
+
+CREATE TRIGGER remote_chapter_source_update AFTER UPDATE OF website_id, path ON source
+WHEN NOT EXISTS (SELECT 1 FROM source s WHERE s.website_id = OLD.website_id AND s.path = OLD.path)
+BEGIN
+    DELETE FROM remote_chapter WHERE website_id = OLD.website_id AND path = OLD.path;
+END;
@@ -0,0 +1,5 @@
+-- Covers that failed to download are not retried on every access
+ALTER TABLE manga ADD COLUMN cover_failed_at TIMESTAMP;
+
+-- Covers are only downloaded from public addresses unless this is enabled
+INSERT OR IGNORE INTO setting (key, value) VALUES ('COVER_ALLOW_PRIVATE_HOSTS', 'false');
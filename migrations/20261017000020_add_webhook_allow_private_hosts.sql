@@ -0,0 +1,2 @@
+-- Webhooks only deliver to public addresses unless this is enabled
+INSERT OR IGNORE INTO setting (key, value) VALUES ('WEBHOOK_ALLOW_PRIVATE_HOSTS', 'false');
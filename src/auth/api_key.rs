@@ -0,0 +1,155 @@
+use rand::distr::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use utoipa::ToSchema;
+
+use crate::auth::key_manager::KeyManager;
+
+/// User created by the migrations, owning the legacy key and pre-existing manga
+pub const DEFAULT_USER_ID: i64 = 1;
+
+const KEY_PREFIX: &str = "msk_";
+const KEY_LENGTH: usize = 40;
+
+/// What a key is allowed to do. Scopes are ordered: each one includes the
+/// permissions of the previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyScope {
+    /// GET requests only
+    Read,
+    /// Every request except key, setting and website management
+    Write,
+    /// Everything, including `/key`, `/setting`, `/user` and `/backup`, and
+    /// changes to the websites shared by all users
+    Admin,
+}
+
+impl KeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyScope::Read => "read",
+            KeyScope::Write => "write",
+            KeyScope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(scope: &str) -> Option<Self> {
+        match scope {
+            "read" => Some(KeyScope::Read),
+            "write" => Some(KeyScope::Write),
+            "admin" => Some(KeyScope::Admin),
+            _ => None,
+        }
+    }
+
+    /// Scope required to call `method` on `path`
+    pub fn required_for(method: &axum::http::Method, path: &str) -> Self {
+        let read_only = method == axum::http::Method::GET || method == axum::http::Method::HEAD;
+        if path == "/key"
+            || path.starts_with("/key/")
+            || path.starts_with("/setting")
+            || path.starts_with("/user")
+            || path.starts_with("/backup")
+            || (path.starts_with("/website") && !read_only)
+        {
+            KeyScope::Admin
+        } else if read_only {
+            KeyScope::Read
+        } else {
+            KeyScope::Write
+        }
+    }
+}
+
+/// The key a request was authenticated with, added to the request
+/// extensions by `auth_middleware`
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    /// `None` for the legacy `key.pub` key
+    pub key_id: Option<i64>,
+    pub name: String,
+    pub scope: KeyScope,
+    /// User whose library the request operates on
+    pub user_id: i64,
+}
+
+impl AuthContext {
+    pub fn legacy() -> Self {
+        Self {
+            key_id: None,
+            name: "legacy".to_string(),
+            scope: KeyScope::Admin,
+            user_id: DEFAULT_USER_ID,
+        }
+    }
+}
+
+/// Generates a new plaintext key
+pub fn generate_key() -> String {
+    let key: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(KEY_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{}{}", KEY_PREFIX, key)
+}
+
+/// Looks up an active key by its plaintext value and records its use
+pub async fn resolve(pool: &SqlitePool, token: &str) -> Result<Option<AuthContext>, sqlx::Error> {
+    let row: Option<(i64, String, String, i64)> = sqlx::query_as(
+        "SELECT id, name, scope, user_id FROM api_key WHERE key_hash = ? AND revoked_at IS NULL"
+    )
+        .bind(KeyManager::hash_key(token))
+        .fetch_optional(pool)
+        .await?;
+
+    let Some((id, name, scope, user_id)) = row else {
+        return Ok(None);
+    };
+
+    // Only write once a minute to avoid a write on every request
+    sqlx::query(
+        "UPDATE api_key SET last_used_at = CURRENT_TIMESTAMP
+        WHERE id = ? AND (last_used_at IS NULL OR last_used_at < datetime('now', '-1 minute'))"
+    )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(KeyScope::parse(&scope).map(|scope| AuthContext {
+        key_id: Some(id),
+        name,
+        scope,
+        user_id,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(KeyScope::required_for(&Method::GET, "/manga"), KeyScope::Read);
+        assert_eq!(KeyScope::required_for(&Method::PATCH, "/manga/1"), KeyScope::Write);
+        assert_eq!(KeyScope::required_for(&Method::GET, "/key"), KeyScope::Admin);
+        assert_eq!(KeyScope::required_for(&Method::DELETE, "/key/api/3"), KeyScope::Admin);
+        assert_eq!(KeyScope::required_for(&Method::GET, "/setting"), KeyScope::Admin);
+        assert_eq!(KeyScope::required_for(&Method::GET, "/user"), KeyScope::Admin);
+        assert_eq!(KeyScope::required_for(&Method::POST, "/backup"), KeyScope::Admin);
+        assert_eq!(KeyScope::required_for(&Method::GET, "/website/example.com"), KeyScope::Read);
+        assert_eq!(KeyScope::required_for(&Method::DELETE, "/website/example.com"), KeyScope::Admin);
+        assert!(KeyScope::Admin > KeyScope::Write && KeyScope::Write > KeyScope::Read);
+    }
+
+    #[test]
+    fn test_generate_key() {
+        let key = generate_key();
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_eq!(key.len(), KEY_PREFIX.len() + KEY_LENGTH);
+        assert_ne!(key, generate_key());
+    }
+}
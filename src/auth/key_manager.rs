@@ -64,9 +64,7 @@ impl KeyManager {
 
         // Remove read-only if exists, then write
         if Path::new(&self.key_path).exists() {
-            let mut perms = fs::metadata(&self.key_path)?.permissions();
-            perms.set_readonly(false);
-            fs::set_permissions(&self.key_path, perms)?;
+            Self::make_writable(&self.key_path)?;
         }
 
         fs::write(&self.key_path, &hash)?;
@@ -81,6 +79,22 @@ impl KeyManager {
         Ok(key)
     }
 
+    /// Grants write access to the owner only, instead of clearing the
+    /// read-only flag which would make the file world-writable on Unix
+    fn make_writable(path: &str) -> Result<()> {
+        let mut perms = fs::metadata(path)?.permissions();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            perms.set_mode(perms.mode() | 0o200);
+        }
+        #[cfg(not(unix))]
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(path, perms)?;
+        Ok(())
+    }
+
     fn get_file_age(metadata: &fs::Metadata) -> Result<std::time::Duration> {
         let file_time = metadata.created().or_else(|_| {
             warn!("Creation time not available, falling back to modification time");
@@ -109,7 +123,7 @@ impl KeyManager {
         Ok(())
     }
 
-    fn hash_key(key: &str) -> String {
+    pub fn hash_key(key: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(key.as_bytes());
         hex::encode(hasher.finalize())
@@ -140,9 +154,7 @@ mod tests {
 
     fn cleanup(path: &str) {
         if Path::new(path).exists() {
-            let mut perms = fs::metadata(path).unwrap().permissions();
-            perms.set_readonly(false);
-            fs::set_permissions(path, perms).ok();
+            KeyManager::make_writable(path).ok();
             fs::remove_file(path).ok();
         }
     }
@@ -1,32 +1,71 @@
 use axum::{
-    extract::{State, Request},
+    extract::{Query, State, Request},
     middleware::Next,
     response::Response,
     http::header::AUTHORIZATION,
 };
-use std::sync::Arc;
-use crate::auth::key_manager::KeyManager;
+use std::collections::HashMap;
+use crate::auth::api_key::{self, AuthContext, KeyScope};
+use crate::state::AppState;
 use crate::utils::response::ApiError;
 
+/// Paths that also accept the key as a `?token=` query parameter, since feed
+/// readers and browser `EventSource` clients cannot send an Authorization
+/// header. Keys in URLs end up in logs, so this is limited to read-only streams.
+const QUERY_TOKEN_PATHS: &[&str] = &["/events", "/feed.atom", "/feed.rss"];
+
+/// Covers are also loaded by `<img>` tags, which cannot send the header either
+fn accepts_query_token(path: &str) -> bool {
+    QUERY_TOKEN_PATHS.contains(&path)
+        || (path.starts_with("/manga/") && (path.ends_with("/cover") || path.ends_with("/cover/small")))
+}
+
+fn query_token(req: &Request) -> Option<String> {
+    if !accepts_query_token(req.uri().path()) {
+        return None;
+    }
+
+    let Query(mut params) = Query::<HashMap<String, String>>::try_from_uri(req.uri()).ok()?;
+    params.remove("token")
+}
+
+/// Resolves a named key first, then falls back to the legacy `key.pub` key
+async fn authenticate(state: &AppState, token: &str) -> Result<AuthContext, ApiError> {
+    if let Some(context) = api_key::resolve(&state.pool, token)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+    {
+        return Ok(context);
+    }
+
+    if state.key_manager.validate_token(token) {
+        return Ok(AuthContext::legacy());
+    }
+
+    Err(ApiError::Forbidden)
+}
+
 pub async fn auth_middleware(
-    State(key_manager): State<Arc<KeyManager>>,
-    req: Request,
+    State(state): State<AppState>,
+    mut req: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
     let auth_header = req.headers()
         .get(AUTHORIZATION)
         .and_then(|h| h.to_str().ok());
 
-    match auth_header {
-        Some(auth_str) if auth_str.starts_with("Bearer ") => {
-            let token = &auth_str[7..];
-            if key_manager.validate_token(token) {
-                Ok(next.run(req).await)
-            } else {
-                Err(ApiError::Forbidden)
-            }
-        }
-        Some(_) => Err(ApiError::Unauthorized),
-        None => Err(ApiError::Unauthorized),
+    let token = match auth_header {
+        Some(auth_str) if auth_str.starts_with("Bearer ") => auth_str[7..].to_string(),
+        Some(_) => return Err(ApiError::Unauthorized),
+        None => query_token(&req).ok_or(ApiError::Unauthorized)?,
+    };
+
+    let context = authenticate(&state, &token).await?;
+
+    if context.scope < KeyScope::required_for(req.method(), req.uri().path()) {
+        return Err(ApiError::Forbidden);
     }
+
+    req.extensions_mut().insert(context);
+    Ok(next.run(req).await)
 }
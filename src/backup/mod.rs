@@ -0,0 +1,169 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio_cron_scheduler::{Job, JobScheduler};
+use utoipa::ToSchema;
+
+use crate::settings;
+
+/// Dropped next to the database, this file replaces it on the next startup
+pub const RESTORE_FILE: &str = "restore.db";
+
+const BACKUP_PREFIX: &str = "manga-";
+const BACKUP_EXTENSION: &str = ".db";
+
+#[derive(Serialize, ToSchema)]
+pub struct BackupFile {
+    pub name: String,
+    pub size: u64,
+    pub created_at: NaiveDateTime,
+}
+
+async fn backup_dir(pool: &SqlitePool) -> anyhow::Result<PathBuf> {
+    Ok(PathBuf::from(settings::get_setting_string(pool, "BACKUP_DIR", "secret/backups").await?))
+}
+
+/// Writes a consistent snapshot of the database with `VACUUM INTO`, which is
+/// safe while the server is running, then prunes the oldest snapshots
+pub async fn create_backup(pool: &SqlitePool) -> anyhow::Result<BackupFile> {
+    let dir = backup_dir(pool).await?;
+    let retention = settings::get_setting_u64(pool, "BACKUP_RETENTION", 7).await?;
+    fs::create_dir_all(&dir)?;
+
+    let timestamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut path = dir.join(format!("{}{}{}", BACKUP_PREFIX, timestamp, BACKUP_EXTENSION));
+    let mut attempt = 1;
+    while path.exists() {
+        path = dir.join(format!("{}{}-{}{}", BACKUP_PREFIX, timestamp, attempt, BACKUP_EXTENSION));
+        attempt += 1;
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+
+    let pruned = prune(&dir, retention as usize)?;
+    if pruned > 0 {
+        tracing::info!("Deleted {} old backup(s)", pruned);
+    }
+
+    backup_file(&path)
+}
+
+/// Snapshots in the backup directory, most recent first
+pub async fn list_backups(pool: &SqlitePool) -> anyhow::Result<Vec<BackupFile>> {
+    let dir = backup_dir(pool).await?;
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    read_backups(&dir)
+}
+
+fn read_backups(dir: &Path) -> anyhow::Result<Vec<BackupFile>> {
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.starts_with(BACKUP_PREFIX) && name.ends_with(BACKUP_EXTENSION) {
+            backups.push(backup_file(&path)?);
+        }
+    }
+
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.name.cmp(&a.name)));
+    Ok(backups)
+}
+
+fn backup_file(path: &Path) -> anyhow::Result<BackupFile> {
+    let metadata = fs::metadata(path)?;
+    Ok(BackupFile {
+        name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+        size: metadata.len(),
+        created_at: DateTime::<Utc>::from(metadata.modified()?).naive_utc(),
+    })
+}
+
+/// Deletes all but the `retention` most recent snapshots, 0 keeps all of them
+fn prune(dir: &Path, retention: usize) -> anyhow::Result<usize> {
+    if retention == 0 {
+        return Ok(0);
+    }
+
+    let backups = read_backups(dir)?;
+    let mut pruned = 0;
+    for backup in backups.iter().skip(retention) {
+        fs::remove_file(dir.join(&backup.name))?;
+        pruned += 1;
+    }
+    Ok(pruned)
+}
+
+pub async fn add_backup_job(scheduler: &JobScheduler, pool: SqlitePool, cron_expression: &str) -> anyhow::Result<()> {
+    let job = Job::new_async(cron_expression, move |_uuid, _lock| {
+        let pool = pool.clone();
+        Box::pin(async move {
+            match create_backup(&pool).await {
+                Ok(backup) => tracing::info!("Backup job wrote {} ({} bytes)", backup.name, backup.size),
+                Err(e) => tracing::error!("Backup job failed: {}", e),
+            }
+        })
+    })?;
+
+    scheduler.add(job).await?;
+
+    tracing::info!("Backup job scheduled with cron expression: {}", cron_expression);
+    Ok(())
+}
+
+/// Replaces the database with the `restore.db` file next to it, if any.
+/// Must run before the database is opened. The previous database is kept as
+/// `manga.db.<timestamp>.bak`. Returns whether a restore happened.
+pub async fn restore_pending(db_path: &Path) -> anyhow::Result<bool> {
+    let restore = db_path.with_file_name(RESTORE_FILE);
+    if !restore.exists() {
+        return Ok(false);
+    }
+
+    validate(&restore).await?;
+
+    if db_path.exists() {
+        let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
+        let db_name = db_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        // Keep the WAL files with the database they belong to
+        for suffix in ["", "-wal", "-shm"] {
+            let file = db_path.with_file_name(format!("{}{}", db_name, suffix));
+            if file.exists() {
+                fs::rename(&file, db_path.with_file_name(format!("{}.{}.bak{}", db_name, timestamp, suffix)))?;
+            }
+        }
+    }
+
+    fs::rename(&restore, db_path)?;
+    Ok(true)
+}
+
+/// Checks that a file is an intact manga-sync database
+async fn validate(path: &Path) -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await?;
+    if integrity != "ok" {
+        anyhow::bail!("{} is corrupted: {}", path.display(), integrity);
+    }
+
+    let manga_table: Option<String> = sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'manga'")
+        .fetch_optional(&mut conn)
+        .await?;
+    if manga_table.is_none() {
+        anyhow::bail!("{} is not a manga-sync database", path.display());
+    }
+
+    conn.close().await?;
+    Ok(())
+}
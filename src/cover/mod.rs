@@ -0,0 +1,245 @@
+use image::{DynamicImage, ImageFormat};
+use reqwest::header::{LOCATION, REFERER};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::settings;
+use crate::sync::http_client::{create_pinned_client, resolve};
+
+/// Generated thumbnails fit in this box, which keeps the usual 2:3 cover ratio
+pub const THUMBNAIL_WIDTH: u32 = 300;
+pub const THUMBNAIL_HEIGHT: u32 = 450;
+
+const MAX_COVER_SIZE: usize = 10 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 5;
+
+/// A failed download is not retried on access before this delay, so a broken
+/// cover does not trigger a download on every request
+const RETRY_DELAY: &str = "-1 hour";
+
+pub async fn cover_dir(pool: &SqlitePool) -> anyhow::Result<PathBuf> {
+    Ok(PathBuf::from(settings::get_setting_string(pool, "COVER_DIR", "secret/covers").await?))
+}
+
+/// Only remote covers are downloaded, anything else is left to the client
+fn is_remote(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Whether the covers of a manga may be downloaded on access, i.e. the last
+/// attempt did not fail less than [`RETRY_DELAY`] ago
+pub async fn should_retry(pool: &SqlitePool, manga_id: i64) -> anyhow::Result<bool> {
+    let retry: Option<bool> = sqlx::query_scalar(
+        "SELECT cover_failed_at IS NULL OR cover_failed_at < datetime('now', ?) FROM manga WHERE id = ?"
+    )
+        .bind(RETRY_DELAY)
+        .bind(manga_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(retry.unwrap_or(false))
+}
+
+/// Downloads and caches the covers of a manga. The small cover is generated
+/// from the full one when it is missing, identical to it, or cannot be fetched.
+/// A failure is recorded so that [`should_retry`] can hold back new attempts.
+pub async fn cache_covers(pool: &SqlitePool, manga_id: i64) -> anyhow::Result<()> {
+    let result = try_cache_covers(pool, manga_id).await;
+    let failed_at = if result.is_err() { "CURRENT_TIMESTAMP" } else { "NULL" };
+    sqlx::query(&format!("UPDATE manga SET cover_failed_at = {} WHERE id = ?", failed_at))
+        .bind(manga_id)
+        .execute(pool)
+        .await?;
+    result
+}
+
+async fn try_cache_covers(pool: &SqlitePool, manga_id: i64) -> anyhow::Result<()> {
+    let row = sqlx::query("SELECT cover, cover_small FROM manga WHERE id = ?")
+        .bind(manga_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some(row) = row else {
+        return Ok(());
+    };
+    let cover: String = row.get("cover");
+    let cover_small: String = row.get("cover_small");
+
+    if !is_remote(&cover) {
+        return Ok(());
+    }
+
+    let allow_private = settings::get_setting_string(pool, "COVER_ALLOW_PRIVATE_HOSTS", "false").await? == "true";
+    let bytes = download(&cover, allow_private).await?;
+
+    let dir = cover_dir(pool).await?;
+    fs::create_dir_all(&dir)?;
+    let cover_hash = store(&dir, &bytes)?;
+
+    let mut small = None;
+    if cover_small != cover && is_remote(&cover_small) {
+        match download(&cover_small, allow_private).await {
+            Ok(b) => small = Some(b),
+            Err(e) => tracing::warn!("Failed to download small cover of manga {}, generating it: {}", manga_id, e),
+        }
+    }
+    let small = match small {
+        Some(b) => b,
+        None => tokio::task::spawn_blocking(move || thumbnail(&bytes)).await??,
+    };
+    let cover_small_hash = store(&dir, &small)?;
+
+    sqlx::query("UPDATE manga SET cover_hash = ?, cover_small_hash = ? WHERE id = ?")
+        .bind(&cover_hash)
+        .bind(&cover_small_hash)
+        .bind(manga_id)
+        .execute(pool)
+        .await?;
+
+    prune(pool).await?;
+    Ok(())
+}
+
+/// Downloads an image of at most [`MAX_COVER_SIZE`] bytes. Unless
+/// `allow_private` is set, every host on the way, redirects included, must
+/// resolve to public addresses only, and the connection is pinned to them.
+async fn download(url: &str, allow_private: bool) -> anyhow::Result<Vec<u8>> {
+    let mut target = Url::parse(url)?;
+    // Hot-link protection usually only lets through requests coming from the site itself
+    let referer = format!("{}://{}/", target.scheme(), target.host_str().unwrap_or_default());
+
+    for _ in 0..=MAX_REDIRECTS {
+        let addrs = resolve(&target, allow_private).await?;
+        let host = target.host_str().unwrap_or_default().to_string();
+        let response = create_pinned_client(&host, &addrs)
+            .get(target.clone())
+            .header(REFERER, &referer)
+            .send()
+            .await?;
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .ok_or_else(|| anyhow::anyhow!("{} redirects without a location", target))?;
+            target = target.join(location)?;
+            continue;
+        }
+
+        let mut response = response.error_for_status()?;
+        if response.content_length().is_some_and(|len| len as usize > MAX_COVER_SIZE) {
+            anyhow::bail!("{} is larger than {} bytes", url, MAX_COVER_SIZE);
+        }
+
+        // The length is not always announced, so the body is read until the limit
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if bytes.len() + chunk.len() > MAX_COVER_SIZE {
+                anyhow::bail!("{} is larger than {} bytes", url, MAX_COVER_SIZE);
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        if image::guess_format(&bytes).is_err() {
+            anyhow::bail!("{} is not an image", url);
+        }
+        return Ok(bytes);
+    }
+
+    anyhow::bail!("{} redirects more than {} times", url, MAX_REDIRECTS)
+}
+
+/// Writes an image under the hex SHA-256 of its content and returns the hash
+fn store(dir: &Path, bytes: &[u8]) -> anyhow::Result<String> {
+    let hash = hex::encode(Sha256::digest(bytes));
+    let path = dir.join(&hash);
+    if !path.exists() {
+        fs::write(path, bytes)?;
+    }
+    Ok(hash)
+}
+
+/// Scales an image down to fit in the thumbnail box and encodes it as JPEG
+pub fn thumbnail(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut image = image::load_from_memory(bytes)?;
+    // `thumbnail` would also scale smaller images up
+    if image.width() > THUMBNAIL_WIDTH || image.height() > THUMBNAIL_HEIGHT {
+        image = image.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+    }
+    // JPEG has no alpha channel
+    let image = DynamicImage::ImageRgb8(image.to_rgb8());
+
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, ImageFormat::Jpeg)?;
+    Ok(out.into_inner())
+}
+
+/// MIME type of a cached cover, detected from its content
+pub fn content_type(bytes: &[u8]) -> &'static str {
+    image::guess_format(bytes)
+        .map(|format| format.to_mime_type())
+        .unwrap_or("application/octet-stream")
+}
+
+/// Deletes the cached files no manga refers to anymore
+pub async fn prune(pool: &SqlitePool) -> anyhow::Result<()> {
+    let dir = cover_dir(pool).await?;
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let used: HashSet<String> = sqlx::query_scalar(
+        "SELECT cover_hash FROM manga WHERE cover_hash IS NOT NULL
+        UNION SELECT cover_small_hash FROM manga WHERE cover_small_hash IS NOT NULL"
+    )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.len() == 64 && name.chars().all(|c| c.is_ascii_hexdigit()) && !used.contains(name) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbaImage};
+
+    #[test]
+    fn test_thumbnail_keeps_ratio() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(600, 1200))
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+
+        let small = thumbnail(png.get_ref()).unwrap();
+        assert_eq!(content_type(&small), "image/jpeg");
+        assert_eq!(image::load_from_memory(&small).unwrap().dimensions(), (225, 450));
+    }
+
+    #[test]
+    fn test_is_remote() {
+        assert!(is_remote("https://example.com/cover.jpg"));
+        assert!(!is_remote("cover.jpg"));
+        assert!(!is_remote(""));
+    }
+
+    #[tokio::test]
+    async fn test_download_rejects_private_hosts() {
+        let error = download("http://127.0.0.1:1/cover.jpg", false).await.unwrap_err();
+        assert!(error.to_string().contains("non-public"));
+        let error = download("http://[::1]:1/cover.jpg", false).await.unwrap_err();
+        assert!(error.to_string().contains("non-public"));
+        assert!(download("file:///etc/passwd", false).await.is_err());
+    }
+}
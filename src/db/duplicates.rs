@@ -0,0 +1,329 @@
+use serde::Serialize;
+use sqlx::{Row, SqliteConnection};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use utoipa::ToSchema;
+
+use crate::db::metadata;
+
+/// Lowest title similarity reported when none is requested
+pub const DEFAULT_SIMILARITY: f64 = 0.85;
+
+/// Why two manga look like the same series
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DuplicateReason {
+    /// Both follow this path on the same website
+    SamePath { domain: String, path: String },
+    /// Both follow the manga with this ID on the same website
+    SameExternalId { domain: String, external_id: String },
+    /// A title of each, from their names and alternative titles, are alike
+    SimilarTitle { title: String, other_title: String, similarity: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DuplicateManga {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Two manga that are likely the same series, the oldest one first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Duplicate {
+    pub manga: DuplicateManga,
+    pub other: DuplicateManga,
+    pub reasons: Vec<DuplicateReason>,
+}
+
+/// What a merge moved into the kept manga
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MergeReport {
+    pub manga_id: i64,
+    pub merged_id: i64,
+    pub moved_sources: u64,
+    /// Sources of the merged manga the kept one already followed
+    pub dropped_sources: u64,
+    pub moved_chapters: u64,
+}
+
+/// Lowercases a title and keeps its words of letters and digits, so that
+/// "Solo Leveling!" and "solo-leveling" compare equal
+pub fn normalize_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(title: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = title.chars().filter(|c| *c != ' ').collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Dice coefficient of the character pairs of two normalized titles, from 0
+/// for unrelated titles to 1 for identical ones
+pub fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+struct Title {
+    title: String,
+    normalized: String,
+}
+
+/// Finds the pairs of the user's manga sharing a source path or an external
+/// ID on a website, or whose titles are at least `min_similarity` alike.
+/// Pairs sharing a source come first, then the most similar titles.
+pub async fn find(conn: &mut SqliteConnection, user_id: i64, min_similarity: f64) -> Result<Vec<Duplicate>, sqlx::Error> {
+    let manga: Vec<(i64, String)> = sqlx::query_as("SELECT id, name FROM manga WHERE user_id = ? ORDER BY id")
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let mut titles: HashMap<i64, Vec<Title>> = HashMap::new();
+    let alt_titles: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT t.manga_id, t.title FROM manga_alt_title t JOIN manga m ON m.id = t.manga_id
+        WHERE m.user_id = ? ORDER BY t.id"
+    )
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    for (id, title) in manga.iter().cloned().chain(alt_titles) {
+        let normalized = normalize_title(&title);
+        if !normalized.is_empty() {
+            titles.entry(id).or_default().push(Title { title, normalized });
+        }
+    }
+
+    let mut reasons: BTreeMap<(i64, i64), Vec<DuplicateReason>> = BTreeMap::new();
+
+    let sources = sqlx::query(
+        "SELECT s.manga_id, w.domain, s.path, s.external_manga_id
+        FROM source s JOIN website w ON w.id = s.website_id JOIN manga m ON m.id = s.manga_id
+        WHERE m.user_id = ? ORDER BY s.manga_id"
+    )
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let mut by_path: BTreeMap<(String, String), Vec<i64>> = BTreeMap::new();
+    let mut by_external_id: BTreeMap<(String, String), Vec<i64>> = BTreeMap::new();
+    for row in &sources {
+        let manga_id: i64 = row.get("manga_id");
+        let domain: String = row.get("domain");
+        by_path.entry((domain.clone(), row.get("path"))).or_default().push(manga_id);
+        if let Some(external_id) = row.get::<Option<String>, _>("external_manga_id") {
+            by_external_id.entry((domain, external_id)).or_default().push(manga_id);
+        }
+    }
+
+    for ((domain, path), ids) in by_path {
+        for pair in pairs(&ids) {
+            reasons.entry(pair).or_default().push(DuplicateReason::SamePath { domain: domain.clone(), path: path.clone() });
+        }
+    }
+    for ((domain, external_id), ids) in by_external_id {
+        for pair in pairs(&ids) {
+            reasons.entry(pair).or_default().push(DuplicateReason::SameExternalId {
+                domain: domain.clone(),
+                external_id: external_id.clone(),
+            });
+        }
+    }
+
+    for (i, (id, _)) in manga.iter().enumerate() {
+        for (other_id, _) in &manga[i + 1..] {
+            let (Some(a), Some(b)) = (titles.get(id), titles.get(other_id)) else {
+                continue;
+            };
+
+            let best = a
+                .iter()
+                .flat_map(|a| b.iter().map(move |b| (a, b, similarity(&a.normalized, &b.normalized))))
+                .max_by(|x, y| x.2.total_cmp(&y.2));
+            if let Some((a, b, similarity)) = best
+                && similarity >= min_similarity
+            {
+                reasons.entry((*id, *other_id)).or_default().push(DuplicateReason::SimilarTitle {
+                    title: a.title.clone(),
+                    other_title: b.title.clone(),
+                    similarity: (similarity * 100.0).round() / 100.0,
+                });
+            }
+        }
+    }
+
+    let names: HashMap<i64, String> = manga.into_iter().collect();
+    let mut duplicates: Vec<Duplicate> = reasons
+        .into_iter()
+        .map(|((id, other_id), reasons)| Duplicate {
+            manga: DuplicateManga { id, name: names[&id].clone() },
+            other: DuplicateManga { id: other_id, name: names[&other_id].clone() },
+            reasons,
+        })
+        .collect();
+    duplicates.sort_by(|a, b| rank(b).total_cmp(&rank(a)));
+    Ok(duplicates)
+}
+
+/// Distinct pairs of manga IDs, the lower one first
+fn pairs(ids: &[i64]) -> Vec<(i64, i64)> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    let mut pairs = Vec::new();
+    for (i, id) in ids.iter().enumerate() {
+        for other_id in &ids[i + 1..] {
+            pairs.push((*id, *other_id));
+        }
+    }
+    pairs
+}
+
+/// A shared source outranks any title similarity
+fn rank(duplicate: &Duplicate) -> f64 {
+    duplicate
+        .reasons
+        .iter()
+        .map(|reason| match reason {
+            DuplicateReason::SimilarTitle { similarity, .. } => *similarity,
+            _ => 2.0,
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Merges `other_id` into `manga_id` and deletes it. Its sources and reading
+/// history are moved over, except sources the kept manga already follows,
+/// whose sync results are kept on the matching source. Its name becomes an
+/// alternative title, and its metadata, categories and reading dates fill
+/// in what the kept manga lacks. Both manga must exist.
+pub async fn merge(conn: &mut SqliteConnection, manga_id: i64, other_id: i64) -> Result<MergeReport, sqlx::Error> {
+    let same_sources: Vec<(i64, i64, Option<String>)> = sqlx::query_as(
+        "SELECT o.id, k.id, o.external_manga_id FROM source o
+        JOIN source k ON k.website_id = o.website_id AND k.path = o.path AND k.manga_id = ?
+        WHERE o.manga_id = ?"
+    )
+        .bind(manga_id)
+        .bind(other_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    for (source_id, kept_source_id, external_id) in &same_sources {
+        sqlx::query("UPDATE sync_result SET source_id = ? WHERE source_id = ?")
+            .bind(kept_source_id)
+            .bind(source_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("UPDATE source SET external_manga_id = COALESCE(external_manga_id, ?) WHERE id = ?")
+            .bind(external_id)
+            .bind(kept_source_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("DELETE FROM source WHERE id = ?")
+            .bind(source_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let moved_sources = sqlx::query("UPDATE source SET manga_id = ? WHERE manga_id = ?")
+        .bind(manga_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    let moved_chapters = sqlx::query("UPDATE chapter SET manga_id = ? WHERE manga_id = ?")
+        .bind(manga_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    sqlx::query(
+        "INSERT OR IGNORE INTO manga_category (manga_id, category_id)
+        SELECT ?, category_id FROM manga_category WHERE manga_id = ?"
+    )
+        .bind(manga_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let kept_name: String = sqlx::query_scalar("SELECT name FROM manga WHERE id = ?")
+        .bind(manga_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let other_name: String = sqlx::query_scalar("SELECT name FROM manga WHERE id = ?")
+        .bind(other_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let kept = metadata::load(conn, manga_id).await?;
+    let mut other = metadata::load(conn, other_id).await?;
+    other.alt_titles.insert(0, other_name);
+    let mut combined = metadata::combine(&kept, &other);
+    combined.alt_titles.retain(|title| !title.eq_ignore_ascii_case(&kept_name));
+    metadata::save(conn, manga_id, &combined).await?;
+
+    // The earliest dates win, and a manga never started takes the progress
+    // of the merged one
+    sqlx::query(
+        "UPDATE manga SET
+            created_at = COALESCE(MIN(manga.created_at, o.created_at), manga.created_at, o.created_at),
+            started_at = COALESCE(MIN(manga.started_at, o.started_at), manga.started_at, o.started_at),
+            completed_at = CASE WHEN manga.reading_status = 'plan_to_read' THEN o.completed_at ELSE manga.completed_at END,
+            reading_status = CASE WHEN manga.reading_status = 'plan_to_read' THEN o.reading_status ELSE manga.reading_status END
+        FROM (SELECT created_at, started_at, completed_at, reading_status FROM manga WHERE id = ?) AS o
+        WHERE manga.id = ?"
+    )
+        .bind(other_id)
+        .bind(manga_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("DELETE FROM manga WHERE id = ?")
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(MergeReport {
+        manga_id,
+        merged_id: other_id,
+        moved_sources,
+        dropped_sources: same_sources.len() as u64,
+        moved_chapters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("  Solo Leveling! (Webtoon)"), "solo leveling webtoon");
+        assert_eq!(normalize_title("solo-leveling"), "solo leveling");
+        assert_eq!(normalize_title("!!"), "");
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("solo leveling", "solo leveling"), 1.0);
+        assert!(similarity("solo leveling", "solo levelling") > 0.9);
+        assert!(similarity("solo leveling", "one piece") < 0.3);
+        assert_eq!(similarity("a", "b"), 0.0);
+    }
+
+    #[test]
+    fn test_pairs() {
+        assert_eq!(pairs(&[3, 1, 3, 2]), vec![(1, 2), (1, 3), (2, 3)]);
+        assert!(pairs(&[1]).is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+use sqlx::{QueryBuilder, Sqlite};
+use std::collections::HashMap;
+
+use crate::db::reading::ReadingStatus;
+use crate::db::search;
+use crate::sync::metadata::PublicationStatus;
+
+/// A condition on the manga listed by `GET /manga`
+#[derive(Debug, Clone, PartialEq)]
+pub enum MangaFilter {
+    Text(String),
+    Website(String),
+    Author(String),
+    Genre(String),
+    Category(String),
+    Status(PublicationStatus),
+    ReadingStatus(ReadingStatus),
+    Year(i64),
+    HasUnread(bool),
+    UnreadAtLeast(i64),
+    NoSource(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    ReadAt,
+    Name,
+    Unread,
+    Added,
+    LastSync,
+    StartedAt,
+    CompletedAt,
+}
+
+impl SortKey {
+    fn parse(key: &str) -> Option<Self> {
+        match key {
+            "READ_AT" => Some(SortKey::ReadAt),
+            "NAME" => Some(SortKey::Name),
+            "UNREAD" => Some(SortKey::Unread),
+            "ADDED" => Some(SortKey::Added),
+            "LAST_SYNC" => Some(SortKey::LastSync),
+            "STARTED_AT" => Some(SortKey::StartedAt),
+            "COMPLETED_AT" => Some(SortKey::CompletedAt),
+            _ => None,
+        }
+    }
+
+    fn expression(self) -> &'static str {
+        match self {
+            SortKey::ReadAt => "(SELECT MAX(c.updated_at) FROM chapter c WHERE c.manga_id = m.id)",
+            SortKey::Name => "m.name COLLATE NOCASE",
+            SortKey::Unread => "(SELECT MAX(s.number_unread_chapter) FROM source s WHERE s.manga_id = m.id)",
+            SortKey::Added => "m.created_at",
+            SortKey::LastSync => "(SELECT MAX(r.created_at) FROM sync_result r JOIN source s ON s.id = r.source_id WHERE s.manga_id = m.id)",
+            SortKey::StartedAt => "m.started_at",
+            SortKey::CompletedAt => "m.completed_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn as_sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+/// Filters and sort of `GET /manga`, parsed from its `filter` keys. Every
+/// value is bound as a parameter, only the fixed SQL of each key is inlined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MangaQuery {
+    pub filters: Vec<MangaFilter>,
+    pub sort: SortKey,
+    pub order: SortOrder,
+}
+
+impl Default for MangaQuery {
+    fn default() -> Self {
+        MangaQuery {
+            filters: Vec::new(),
+            sort: SortKey::ReadAt,
+            order: SortOrder::Desc,
+        }
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("Invalid {} value", key)),
+    }
+}
+
+impl MangaQuery {
+    /// Parses `filter` entries. Sort keys take `ASC` or `DESC`, and the last
+    /// one given wins.
+    pub fn parse(entries: &[HashMap<String, String>]) -> Result<Self, String> {
+        let mut query = MangaQuery::default();
+
+        for (key, value) in entries.iter().flatten() {
+            let key = key.as_str();
+            if let Some(sort) = SortKey::parse(key) {
+                query.order = match value.as_str() {
+                    "ASC" => SortOrder::Asc,
+                    "DESC" => SortOrder::Desc,
+                    _ => return Err(format!("Invalid {} value", key)),
+                };
+                query.sort = sort;
+                continue;
+            }
+
+            let filter = match key {
+                "TEXT" => MangaFilter::Text(value.clone()),
+                "WEBSITE" => MangaFilter::Website(value.clone()),
+                "AUTHOR" => MangaFilter::Author(value.clone()),
+                "GENRE" => MangaFilter::Genre(value.clone()),
+                "CATEGORY" => MangaFilter::Category(value.clone()),
+                "STATUS" => MangaFilter::Status(match value.as_str() {
+                    "ongoing" => PublicationStatus::Ongoing,
+                    "completed" => PublicationStatus::Completed,
+                    "hiatus" => PublicationStatus::Hiatus,
+                    _ => return Err("Invalid STATUS value".into()),
+                }),
+                "READING_STATUS" => MangaFilter::ReadingStatus(
+                    ReadingStatus::parse(value).ok_or("Invalid READING_STATUS value")?,
+                ),
+                "YEAR" => MangaFilter::Year(value.parse().map_err(|_| "Invalid YEAR value")?),
+                "HAS_UNREAD" => MangaFilter::HasUnread(parse_bool(key, value)?),
+                "UNREAD_MIN" => MangaFilter::UnreadAtLeast(
+                    value.parse().ok().filter(|n| *n >= 0).ok_or("Invalid UNREAD_MIN value")?,
+                ),
+                "NO_SOURCE" => MangaFilter::NoSource(parse_bool(key, value)?),
+                _ => return Err(format!("Unknown filter key: {}", key)),
+            };
+            query.filters.push(filter);
+        }
+
+        Ok(query)
+    }
+
+    /// Appends the `WHERE` clause restricting the list to the user's manga
+    /// matching every filter
+    pub fn push_where(&self, builder: &mut QueryBuilder<'_, Sqlite>, user_id: i64) {
+        builder.push(" WHERE m.user_id = ").push_bind(user_id);
+
+        for filter in &self.filters {
+            builder.push(" AND ");
+            match filter {
+                MangaFilter::Text(text) => match search::match_query(text) {
+                    Some(terms) => {
+                        builder
+                            .push("m.id IN (SELECT rowid FROM manga_search WHERE manga_search MATCH ")
+                            .push_bind(terms)
+                            .push(")");
+                    }
+                    None => {
+                        builder.push("0");
+                    }
+                },
+                MangaFilter::Website(domain) => {
+                    builder
+                        .push("EXISTS (SELECT 1 FROM source s JOIN website w ON s.website_id = w.id WHERE s.manga_id = m.id AND w.domain = ")
+                        .push_bind(domain.clone())
+                        .push(")");
+                }
+                MangaFilter::Author(name) => {
+                    builder
+                        .push("EXISTS (SELECT 1 FROM manga_author ma JOIN author a ON a.id = ma.author_id WHERE ma.manga_id = m.id AND a.name = ")
+                        .push_bind(name.clone())
+                        .push(")");
+                }
+                MangaFilter::Genre(name) => {
+                    builder
+                        .push("EXISTS (SELECT 1 FROM manga_genre mg JOIN genre g ON g.id = mg.genre_id WHERE mg.manga_id = m.id AND g.name = ")
+                        .push_bind(name.clone())
+                        .push(")");
+                }
+                MangaFilter::Category(name) => {
+                    builder
+                        .push("EXISTS (SELECT 1 FROM manga_category mc JOIN category c ON c.id = mc.category_id WHERE mc.manga_id = m.id AND c.name = ")
+                        .push_bind(name.clone())
+                        .push(")");
+                }
+                MangaFilter::Status(status) => {
+                    builder.push("m.status = ").push_bind(*status);
+                }
+                MangaFilter::ReadingStatus(status) => {
+                    builder.push("m.reading_status = ").push_bind(*status);
+                }
+                MangaFilter::Year(year) => {
+                    builder.push("m.year = ").push_bind(*year);
+                }
+                MangaFilter::HasUnread(has_unread) => {
+                    if !has_unread {
+                        builder.push("NOT ");
+                    }
+                    builder.push("EXISTS (SELECT 1 FROM source s WHERE s.manga_id = m.id AND s.number_unread_chapter > 0)");
+                }
+                MangaFilter::UnreadAtLeast(count) => {
+                    builder
+                        .push("COALESCE((SELECT MAX(s.number_unread_chapter) FROM source s WHERE s.manga_id = m.id), 0) >= ")
+                        .push_bind(*count);
+                }
+                MangaFilter::NoSource(no_source) => {
+                    if *no_source {
+                        builder.push("NOT ");
+                    }
+                    builder.push("EXISTS (SELECT 1 FROM source s WHERE s.manga_id = m.id)");
+                }
+            }
+        }
+    }
+
+    /// Appends the `ORDER BY` clause. Manga without a value for the sort key
+    /// come last, and ties are broken by id so pages do not overlap.
+    pub fn push_order_by(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        let expression = self.sort.expression();
+        let order = self.order.as_sql();
+        builder.push(format!(
+            " ORDER BY {expression} IS NULL, {expression} {order}, m.id {order}"
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<HashMap<String, String>> {
+        pairs
+            .iter()
+            .map(|(k, v)| HashMap::from([(k.to_string(), v.to_string())]))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let query = MangaQuery::parse(&entries(&[("GENRE", "Action"), ("NAME", "ASC"), ("UNREAD_MIN", "3")])).unwrap();
+        assert_eq!(query.filters, vec![MangaFilter::Genre("Action".into()), MangaFilter::UnreadAtLeast(3)]);
+        assert_eq!((query.sort, query.order), (SortKey::Name, SortOrder::Asc));
+
+        assert_eq!(MangaQuery::parse(&[]).unwrap(), MangaQuery::default());
+        assert!(MangaQuery::parse(&entries(&[("READ_AT", "UP")])).is_err());
+        assert!(MangaQuery::parse(&entries(&[("UNREAD_MIN", "-1")])).is_err());
+        assert!(MangaQuery::parse(&entries(&[("HAS_UNREAD", "yes")])).is_err());
+        assert_eq!(MangaQuery::parse(&entries(&[("SIZE", "1")])).unwrap_err(), "Unknown filter key: SIZE");
+    }
+
+    #[test]
+    fn test_values_are_bound() {
+        let query = MangaQuery::parse(&entries(&[("TEXT", "100%' OR 1=1 --")])).unwrap();
+        let mut builder = QueryBuilder::new("SELECT m.id FROM manga m");
+        query.push_where(&mut builder, 1);
+        let sql = builder.sql();
+        assert!(!sql.contains("OR 1=1"));
+        assert_eq!(sql.matches('?').count(), 2);
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqliteConnection;
+use utoipa::ToSchema;
+
+use crate::sync::metadata::PublicationStatus;
+
+/// Descriptive fields of a manga, besides its name and covers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct MangaInfo {
+    pub description: Option<String>,
+    pub status: Option<PublicationStatus>,
+    pub year: Option<i64>,
+    pub authors: Vec<String>,
+    pub genres: Vec<String>,
+    pub alt_titles: Vec<String>,
+}
+
+/// Names shared between manga through a link table
+#[derive(Clone, Copy)]
+pub enum Tag {
+    Author,
+    Genre,
+}
+
+impl Tag {
+    fn table(self) -> &'static str {
+        match self {
+            Tag::Author => "author",
+            Tag::Genre => "genre",
+        }
+    }
+}
+
+/// Trims the names and drops the empty ones and case-insensitive duplicates
+fn normalize(names: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !result.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+            result.push(name.to_string());
+        }
+    }
+    result
+}
+
+pub async fn load(conn: &mut SqliteConnection, manga_id: i64) -> Result<MangaInfo, sqlx::Error> {
+    let (description, status, year): (Option<String>, Option<PublicationStatus>, Option<i64>) =
+        sqlx::query_as("SELECT description, status, year FROM manga WHERE id = ?")
+            .bind(manga_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    Ok(MangaInfo {
+        description,
+        status,
+        year,
+        authors: load_tags(conn, manga_id, Tag::Author).await?,
+        genres: load_tags(conn, manga_id, Tag::Genre).await?,
+        alt_titles: load_alt_titles(conn, manga_id).await?,
+    })
+}
+
+pub async fn load_tags(conn: &mut SqliteConnection, manga_id: i64, tag: Tag) -> Result<Vec<String>, sqlx::Error> {
+    let table = tag.table();
+    sqlx::query_scalar(&format!(
+        "SELECT t.name FROM manga_{table} l JOIN {table} t ON t.id = l.{table}_id
+        WHERE l.manga_id = ? ORDER BY l.position"
+    ))
+        .bind(manga_id)
+        .fetch_all(conn)
+        .await
+}
+
+pub async fn load_alt_titles(conn: &mut SqliteConnection, manga_id: i64) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT title FROM manga_alt_title WHERE manga_id = ? ORDER BY id")
+        .bind(manga_id)
+        .fetch_all(conn)
+        .await
+}
+
+/// Overwrites every field of a manga's metadata
+pub async fn save(conn: &mut SqliteConnection, manga_id: i64, info: &MangaInfo) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE manga SET description = ?, status = ?, year = ? WHERE id = ?")
+        .bind(&info.description)
+        .bind(info.status)
+        .bind(info.year)
+        .bind(manga_id)
+        .execute(&mut *conn)
+        .await?;
+
+    set_tags(conn, manga_id, Tag::Author, &info.authors).await?;
+    set_tags(conn, manga_id, Tag::Genre, &info.genres).await?;
+    set_alt_titles(conn, manga_id, &info.alt_titles).await
+}
+
+/// Sets the fields a manga does not have yet, and returns whether any of the
+/// fields it has differs from `info`
+pub async fn fill_missing(conn: &mut SqliteConnection, manga_id: i64, info: &MangaInfo) -> Result<bool, sqlx::Error> {
+    let current = load(conn, manga_id).await?;
+
+    let mut merged = current.clone();
+    let mut differs = false;
+    differs |= fill(&mut merged.description, &info.description, Option::is_none);
+    differs |= fill(&mut merged.status, &info.status, Option::is_none);
+    differs |= fill(&mut merged.year, &info.year, Option::is_none);
+    differs |= fill(&mut merged.authors, &info.authors, Vec::is_empty);
+    differs |= fill(&mut merged.genres, &info.genres, Vec::is_empty);
+    differs |= fill(&mut merged.alt_titles, &info.alt_titles, Vec::is_empty);
+
+    if merged != current {
+        save(conn, manga_id, &merged).await?;
+    }
+    Ok(differs)
+}
+
+/// Metadata of a manga another one is merged into: the fields it lacks come
+/// from `other`, whose authors, genres and alternative titles are appended
+pub fn combine(kept: &MangaInfo, other: &MangaInfo) -> MangaInfo {
+    let append = |a: &[String], b: &[String]| normalize(&[a, b].concat());
+    MangaInfo {
+        description: kept.description.clone().or_else(|| other.description.clone()),
+        status: kept.status.or(other.status),
+        year: kept.year.or(other.year),
+        authors: append(&kept.authors, &other.authors),
+        genres: append(&kept.genres, &other.genres),
+        alt_titles: append(&kept.alt_titles, &other.alt_titles),
+    }
+}
+
+/// Takes `new` when `current` is empty, otherwise returns whether they differ
+fn fill<T: Clone + PartialEq>(current: &mut T, new: &T, is_empty: fn(&T) -> bool) -> bool {
+    if is_empty(current) {
+        *current = new.clone();
+        false
+    } else {
+        !is_empty(new) && current != new
+    }
+}
+
+/// Replaces the authors or genres of a manga, keeping their order
+pub async fn set_tags(conn: &mut SqliteConnection, manga_id: i64, tag: Tag, names: &[String]) -> Result<(), sqlx::Error> {
+    let table = tag.table();
+
+    sqlx::query(&format!("DELETE FROM manga_{table} WHERE manga_id = ?"))
+        .bind(manga_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (position, name) in normalize(names).iter().enumerate() {
+        sqlx::query(&format!("INSERT INTO {table} (name) VALUES (?) ON CONFLICT(name) DO NOTHING"))
+            .bind(name)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(&format!(
+            "INSERT INTO manga_{table} (manga_id, {table}_id, position)
+            SELECT ?, id, ? FROM {table} WHERE name = ?"
+        ))
+            .bind(manga_id)
+            .bind(position as i64)
+            .bind(name)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    // Names no manga uses anymore
+    sqlx::query(&format!("DELETE FROM {table} WHERE id NOT IN (SELECT {table}_id FROM manga_{table})"))
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn set_alt_titles(conn: &mut SqliteConnection, manga_id: i64, titles: &[String]) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM manga_alt_title WHERE manga_id = ?")
+        .bind(manga_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for title in normalize(titles) {
+        sqlx::query("INSERT INTO manga_alt_title (manga_id, title) VALUES (?, ?)")
+            .bind(manga_id)
+            .bind(&title)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let names = vec![" Action ".to_string(), "".to_string(), "action".to_string(), "Drama".to_string()];
+        assert_eq!(normalize(&names), vec!["Action", "Drama"]);
+    }
+
+    #[test]
+    fn test_combine() {
+        let kept = MangaInfo {
+            year: Some(2018),
+            genres: vec!["Action".into()],
+            ..Default::default()
+        };
+        let other = MangaInfo {
+            description: Some("Hunters".into()),
+            year: Some(2016),
+            genres: vec!["action".into(), "Fantasy".into()],
+            ..Default::default()
+        };
+        let combined = combine(&kept, &other);
+        assert_eq!(combined.description.as_deref(), Some("Hunters"));
+        assert_eq!(combined.year, Some(2018));
+        assert_eq!(combined.genres, vec!["Action", "Fantasy"]);
+    }
+}
@@ -1,3 +1,9 @@
+pub mod manga_query;
+pub mod metadata;
+pub mod reading;
+pub mod search;
+pub mod duplicates;
+
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 use anyhow::Result;
 use std::fs;
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection};
+use utoipa::ToSchema;
+
+use crate::sync::chapter_number::{self, ChapterNumber};
+use crate::sync::metadata::PublicationStatus;
+
+/// Where the user is with a manga
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ReadingStatus {
+    #[default]
+    PlanToRead,
+    Reading,
+    Completed,
+    OnHold,
+    Dropped,
+}
+
+impl ReadingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadingStatus::PlanToRead => "plan_to_read",
+            ReadingStatus::Reading => "reading",
+            ReadingStatus::Completed => "completed",
+            ReadingStatus::OnHold => "on_hold",
+            ReadingStatus::Dropped => "dropped",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "plan_to_read" => Some(ReadingStatus::PlanToRead),
+            "reading" => Some(ReadingStatus::Reading),
+            "completed" => Some(ReadingStatus::Completed),
+            "on_hold" => Some(ReadingStatus::OnHold),
+            "dropped" => Some(ReadingStatus::Dropped),
+            _ => None,
+        }
+    }
+}
+
+/// Changes the reading status of a manga. Reading or completing it sets
+/// `started_at` if it was never started, completing it sets `completed_at`,
+/// and any other status clears `completed_at`.
+pub async fn set_status(conn: &mut SqliteConnection, manga_id: i64, status: ReadingStatus) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE manga SET
+            started_at = CASE WHEN ?1 IN ('reading', 'completed')
+                THEN COALESCE(started_at, (SELECT MIN(updated_at) FROM chapter WHERE manga_id = manga.id), CURRENT_TIMESTAMP)
+                ELSE started_at END,
+            completed_at = CASE WHEN ?1 <> 'completed' THEN NULL
+                WHEN reading_status = 'completed' THEN completed_at
+                ELSE CURRENT_TIMESTAMP END,
+            reading_status = ?1
+        WHERE id = ?2"
+    )
+        .bind(status)
+        .bind(manga_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Marks a manga planned or put on hold as being read, once it has a reading
+/// history. Returns whether the status changed.
+pub async fn start_reading(conn: &mut SqliteConnection, manga_id: i64) -> Result<bool, sqlx::Error> {
+    let status: Option<ReadingStatus> = sqlx::query_scalar(
+        "SELECT reading_status FROM manga WHERE id = ? AND EXISTS (SELECT 1 FROM chapter WHERE manga_id = manga.id)"
+    )
+        .bind(manga_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    match status {
+        Some(ReadingStatus::PlanToRead | ReadingStatus::OnHold) => {
+            set_status(conn, manga_id, ReadingStatus::Reading).await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Marks a manga as completed when its series is finished and the chapter
+/// read is the latest one any of its sources lists. Dropped manga are left
+/// alone. Returns whether the status changed.
+pub async fn complete_if_caught_up(conn: &mut SqliteConnection, manga_id: i64) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT m.status, m.reading_status,
+        (SELECT c.number FROM chapter c WHERE c.manga_id = m.id ORDER BY c.updated_at DESC, c.id DESC LIMIT 1) as current_chapter
+        FROM manga m WHERE m.id = ?"
+    )
+        .bind(manga_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    let Some(row) = row else {
+        return Ok(false);
+    };
+
+    let status: Option<PublicationStatus> = row.get("status");
+    let reading_status: ReadingStatus = row.get("reading_status");
+    if status != Some(PublicationStatus::Completed)
+        || matches!(reading_status, ReadingStatus::Completed | ReadingStatus::Dropped)
+    {
+        return Ok(false);
+    }
+
+    let Some(current) = row.get::<Option<String>, _>("current_chapter").as_deref().and_then(ChapterNumber::parse) else {
+        return Ok(false);
+    };
+
+    let chapters: Vec<ChapterNumber> = sqlx::query_as::<_, (Option<i64>, f64, Option<String>)>(
+        "SELECT rc.volume, rc.number, rc.part FROM remote_chapter rc
+        JOIN source s ON s.website_id = rc.website_id AND s.path = rc.path
+        WHERE s.manga_id = ? AND rc.number IS NOT NULL"
+    )
+        .bind(manga_id)
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|(volume, number, part)| ChapterNumber { volume, number, part })
+        .collect();
+
+    if chapters.is_empty() || chapter_number::count_newer(&chapters, &current) > 0 {
+        return Ok(false);
+    }
+
+    set_status(conn, manga_id, ReadingStatus::Completed).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reading_status_round_trip() {
+        for status in [
+            ReadingStatus::PlanToRead,
+            ReadingStatus::Reading,
+            ReadingStatus::Completed,
+            ReadingStatus::OnHold,
+            ReadingStatus::Dropped,
+        ] {
+            assert_eq!(ReadingStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(ReadingStatus::parse("finished"), None);
+    }
+}
@@ -0,0 +1,52 @@
+/// Marks wrapped around the matched words of highlighted titles
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+/// Builds the FTS5 query of `manga_search` from user input: every word must
+/// match the start of a word of the name or of an alternative title. Only
+/// letters and digits are kept, so the input cannot use the query syntax.
+/// Returns `None` when there is no word to search.
+pub fn match_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Picks the first highlighted line of the alternative titles column, the
+/// title that matched
+pub fn matched_alt_title(alt_titles: &str) -> Option<String> {
+    alt_titles
+        .lines()
+        .find(|title| title.contains(HIGHLIGHT_START))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_query() {
+        assert_eq!(match_query("one pie").as_deref(), Some("\"one\"* \"pie\"*"));
+        assert_eq!(match_query("\"x\" OR name:y*").as_deref(), Some("\"x\"* \"OR\"* \"name\"* \"y\"*"));
+        assert_eq!(match_query("Pokémon").as_deref(), Some("\"Pokémon\"*"));
+        assert_eq!(match_query(" %' -- "), None);
+    }
+
+    #[test]
+    fn test_matched_alt_title() {
+        assert_eq!(
+            matched_alt_title("Na Honjaman\n<mark>Level</mark> Up Alone").as_deref(),
+            Some("<mark>Level</mark> Up Alone")
+        );
+        assert_eq!(matched_alt_title("Na Honjaman"), None);
+    }
+}
@@ -0,0 +1,136 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before it starts missing some
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Change to the library, streamed to clients by `GET /events`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LibraryEvent {
+    MangaCreated {
+        manga_id: i64,
+        name: String,
+    },
+    MangaUpdated {
+        manga_id: i64,
+    },
+    MangaDeleted {
+        manga_id: i64,
+    },
+    ChapterRead {
+        manga_id: i64,
+        chapter_number: String,
+    },
+    SyncStarted {
+        run_id: i64,
+        scope: String,
+        scope_id: Option<i64>,
+        total_sources: usize,
+    },
+    SyncFinished {
+        run_id: i64,
+        success_count: usize,
+        error_count: usize,
+        new_chapters: usize,
+    },
+    UnreadCountChanged {
+        manga_id: i64,
+        source_id: i64,
+        number_unread_chapter: i64,
+    },
+}
+
+impl LibraryEvent {
+    /// SSE event name, the same as the `type` field of the JSON data
+    pub fn name(&self) -> &'static str {
+        match self {
+            LibraryEvent::MangaCreated { .. } => "manga_created",
+            LibraryEvent::MangaUpdated { .. } => "manga_updated",
+            LibraryEvent::MangaDeleted { .. } => "manga_deleted",
+            LibraryEvent::ChapterRead { .. } => "chapter_read",
+            LibraryEvent::SyncStarted { .. } => "sync_started",
+            LibraryEvent::SyncFinished { .. } => "sync_finished",
+            LibraryEvent::UnreadCountChanged { .. } => "unread_count_changed",
+        }
+    }
+}
+
+/// Broadcasts library events to the connected clients of the user owning
+/// the library
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<(i64, LibraryEvent)>,
+}
+
+/// Receives the events of one user's library
+pub struct Subscription {
+    user_id: i64,
+    receiver: broadcast::Receiver<(i64, LibraryEvent)>,
+}
+
+impl Subscription {
+    /// Waits for the next event of the user. Lagging counts the events of
+    /// every user the subscriber missed.
+    pub async fn recv(&mut self) -> Result<LibraryEvent, broadcast::error::RecvError> {
+        loop {
+            let (user_id, event) = self.receiver.recv().await?;
+            if user_id == self.user_id {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Sends an event of `user_id`'s library to the user's current
+    /// subscribers, if any
+    pub fn publish(&self, user_id: i64, event: LibraryEvent) {
+        // An error only means nobody is listening
+        let _ = self.sender.send((user_id, event));
+    }
+
+    pub fn subscribe(&self, user_id: i64) -> Subscription {
+        Subscription {
+            user_id,
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_subscribe() {
+        let bus = EventBus::new();
+
+        // Publishing without subscribers is not an error
+        bus.publish(1, LibraryEvent::MangaDeleted { manga_id: 1 });
+
+        let mut rx = bus.subscribe(1);
+        // Events of other users are skipped
+        bus.publish(2, LibraryEvent::MangaCreated { manga_id: 3, name: "Monster".into() });
+        bus.publish(1, LibraryEvent::MangaUpdated { manga_id: 2 });
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event, LibraryEvent::MangaUpdated { manga_id: 2 });
+        assert_eq!(event.name(), "manga_updated");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "manga_updated", "manga_id": 2 })
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use tracing::info;
+use crate::backup::{self, BackupFile};
+use crate::state::AppState;
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+
+#[utoipa::path(
+    post,
+    path = "/backup",
+    responses(
+        (status = 200, description = "Database snapshot written to the backup directory", body = ApiResponse<BackupFile>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_backup(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<BackupFile>>, ApiError> {
+    let backup = backup::create_backup(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    info!("Backup written: {} ({} bytes)", backup.name, backup.size);
+
+    Ok(Json(ApiResponse::success(backup)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/backup",
+    responses(
+        (status = 200, description = "List database snapshots, most recent first", body = ApiResponse<Page<BackupFile>>),
+        (status = 400, description = "Invalid page size")
+    ),
+    params(PageQuery),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_backups(
+    State(state): State<AppState>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<BackupFile>>>, ApiError> {
+    let (size, _) = query.bounds()?;
+
+    let backups = backup::list_backups(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::from_all(backups, query.page.unwrap_or(1), size))))
+}
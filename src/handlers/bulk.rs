@@ -0,0 +1,215 @@
+use axum::{
+    extract::State,
+    Extension,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, SqliteConnection};
+use crate::auth::api_key::AuthContext;
+use crate::cover;
+use crate::events::LibraryEvent;
+use crate::handlers::{category, manga};
+use crate::handlers::manga::{AppliedUpdate, CreateMangaSource, UpdateManga};
+use crate::state::AppState;
+use crate::utils::response::{ApiResponse, ApiError};
+
+use utoipa::ToSchema;
+
+/// Most operations accepted in one request
+pub const MAX_OPERATIONS: usize = 500;
+
+/// One change of `POST /manga/bulk`, named by its `op` field
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// Takes the fields of `PATCH /manga/{id}`, such as `chapter_number`,
+    /// `status` or `reading_status`
+    Update {
+        manga_id: i64,
+        #[serde(flatten)]
+        changes: Box<UpdateManga>,
+    },
+    AddCategory {
+        manga_id: i64,
+        category_id: i64,
+    },
+    RemoveCategory {
+        manga_id: i64,
+        category_id: i64,
+    },
+    Delete {
+        manga_id: i64,
+    },
+    /// Takes the fields of `POST /manga/{id}/source`
+    AddSource {
+        manga_id: i64,
+        #[serde(flatten)]
+        source: CreateMangaSource,
+    },
+}
+
+impl BulkOperation {
+    fn manga_id(&self) -> i64 {
+        match self {
+            BulkOperation::Update { manga_id, .. }
+            | BulkOperation::AddCategory { manga_id, .. }
+            | BulkOperation::RemoveCategory { manga_id, .. }
+            | BulkOperation::Delete { manga_id }
+            | BulkOperation::AddSource { manga_id, .. } => *manga_id,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BulkRequest {
+    pub operations: Vec<BulkOperation>,
+    /// Save nothing unless every operation succeeds, the default. When
+    /// false, the failed operations are skipped and the others saved.
+    #[serde(default = "default_atomic")]
+    pub atomic: bool,
+}
+
+fn default_atomic() -> bool {
+    true
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkItemResult {
+    /// Position of the operation in the request
+    pub index: usize,
+    pub manga_id: i64,
+    pub success: bool,
+    /// Status code the operation would have on its own endpoint
+    pub status: u16,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkReport {
+    /// Whether the successful operations were saved, false when an `atomic`
+    /// request had a failure
+    pub committed: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/manga/bulk",
+    request_body = BulkRequest,
+    responses(
+        (status = 200, description = "Apply operations to the user's manga in one transaction and report the result of each", body = ApiResponse<BulkReport>),
+        (status = 400, description = "No operation or too many operations")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn bulk_manga(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Json(payload): Json<BulkRequest>,
+) -> Result<Json<ApiResponse<BulkReport>>, ApiError> {
+    let BulkRequest { operations, atomic } = payload;
+    if operations.is_empty() || operations.len() > MAX_OPERATIONS {
+        return Err(ApiError::BadRequest(format!("Between 1 and {} operations required", MAX_OPERATIONS)));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    let mut results = Vec::with_capacity(operations.len());
+    // Index of the successful operations, with what is left to do once committed
+    let mut applied: Vec<(usize, Option<AppliedUpdate>)> = Vec::new();
+
+    for (index, operation) in operations.iter().enumerate() {
+        // Each operation runs in a savepoint, so a failed one leaves nothing behind
+        let mut savepoint = Connection::begin(&mut *tx).await.map_err(|e| ApiError::Internal(e.to_string()))?;
+        let outcome = apply(&mut savepoint, context.user_id, operation).await;
+
+        let error = match outcome {
+            Ok(update) => {
+                savepoint.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+                applied.push((index, update));
+                None
+            }
+            Err(e) => {
+                savepoint.rollback().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+                Some(e.parts())
+            }
+        };
+
+        results.push(BulkItemResult {
+            index,
+            manga_id: operation.manga_id(),
+            success: error.is_none(),
+            status: error.as_ref().map_or(200, |(status, _)| status.as_u16()),
+            error: error.map(|(_, message)| message),
+        });
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    let committed = !(atomic && failed > 0);
+    if committed {
+        tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+        // Covers and unread counts may have to be fetched from the source
+        // sites, which is too slow to wait for with hundreds of operations
+        let user_id = context.user_id;
+        tokio::spawn(async move { finish(&state, user_id, &operations, applied).await });
+    } else {
+        tx.rollback().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
+
+    Ok(Json(ApiResponse::success(BulkReport {
+        committed,
+        succeeded: results.len() - failed,
+        failed,
+        results,
+    })))
+}
+
+/// Runs one operation with the checks of its own endpoint
+async fn apply(conn: &mut SqliteConnection, user_id: i64, operation: &BulkOperation) -> Result<Option<AppliedUpdate>, ApiError> {
+    match operation {
+        BulkOperation::Update { manga_id, changes } => {
+            manga::validate_update(changes)?;
+            manga::apply_update(conn, user_id, *manga_id, changes).await.map(Some)
+        }
+        BulkOperation::AddCategory { manga_id, category_id } => {
+            category::set_membership(conn, user_id, *category_id, *manga_id, true).await.map(|_| None)
+        }
+        BulkOperation::RemoveCategory { manga_id, category_id } => {
+            category::set_membership(conn, user_id, *category_id, *manga_id, false).await.map(|_| None)
+        }
+        BulkOperation::Delete { manga_id } => {
+            manga::remove_manga(conn, user_id, *manga_id).await.map(|_| None)
+        }
+        BulkOperation::AddSource { manga_id, source } => {
+            manga::insert_source(conn, user_id, *manga_id, source).await.map(|_| None)
+        }
+    }
+}
+
+/// Does what each endpoint does after saving: events, covers and unread counts
+async fn finish(state: &AppState, user_id: i64, operations: &[BulkOperation], applied: Vec<(usize, Option<AppliedUpdate>)>) {
+    let mut deleted = false;
+
+    for (index, update) in applied {
+        match (&operations[index], update) {
+            (BulkOperation::Update { manga_id, changes }, Some(update)) => {
+                manga::finish_update(state, user_id, *manga_id, changes, update).await;
+            }
+            (BulkOperation::Delete { manga_id }, _) => {
+                deleted = true;
+                state.events.publish(user_id, LibraryEvent::MangaDeleted { manga_id: *manga_id });
+            }
+            (BulkOperation::AddSource { manga_id, .. }, _) => {
+                state.events.publish(user_id, LibraryEvent::MangaUpdated { manga_id: *manga_id });
+            }
+            _ => {}
+        }
+    }
+
+    if deleted && let Err(e) = cover::prune(&state.pool).await {
+        tracing::warn!("Failed to delete unused covers: {}", e);
+    }
+}
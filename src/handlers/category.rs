@@ -0,0 +1,353 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqliteConnection;
+use crate::auth::api_key::AuthContext;
+use crate::models::Category;
+use crate::state::AppState;
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+
+use utoipa::ToSchema;
+
+const CATEGORY_COLUMNS: &str = "c.id, c.name, c.position,
+    (SELECT COUNT(*) FROM manga_category mc WHERE mc.category_id = c.id) as manga_count,
+    c.created_at";
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateCategory {
+    pub name: String,
+    /// Defaults to the end of the list
+    pub position: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateCategory {
+    pub name: Option<String>,
+    /// Moves the category, shifting the ones in between
+    pub position: Option<i64>,
+}
+
+fn validate_name(name: &str) -> Result<&str, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("name is required".into()));
+    }
+    Ok(name)
+}
+
+fn map_unique_error(e: sqlx::Error) -> ApiError {
+    if e.to_string().contains("UNIQUE constraint failed") {
+        ApiError::BadRequest("Category already exists".into())
+    } else {
+        ApiError::Internal(e.to_string())
+    }
+}
+
+async fn fetch_category(state: &AppState, user_id: i64, id: i64) -> Result<Category, ApiError> {
+    sqlx::query_as::<sqlx::Sqlite, Category>(&format!(
+        "SELECT {} FROM category c WHERE c.id = ? AND c.user_id = ?",
+        CATEGORY_COLUMNS
+    ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Category not found".into()))
+}
+
+/// Rewrites the positions of a user's categories as 0, 1, 2... placing
+/// `moved` at `position` when given
+async fn reorder(conn: &mut SqliteConnection, user_id: i64, moved: Option<(i64, i64)>) -> Result<(), sqlx::Error> {
+    let mut ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM category WHERE user_id = ? ORDER BY position, id")
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    if let Some((id, position)) = moved {
+        ids.retain(|other| *other != id);
+        let index = position.clamp(0, ids.len() as i64) as usize;
+        ids.insert(index, id);
+    }
+
+    for (position, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE category SET position = ? WHERE id = ?")
+            .bind(position as i64)
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/category",
+    responses(
+        (status = 200, description = "List the caller's categories in order", body = ApiResponse<Page<Category>>),
+        (status = 400, description = "Invalid page size")
+    ),
+    params(PageQuery),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_categories(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<Category>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM category WHERE user_id = ?")
+        .bind(context.user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let categories = sqlx::query_as::<sqlx::Sqlite, Category>(&format!(
+        "SELECT {} FROM category c WHERE c.user_id = ? ORDER BY c.position, c.id LIMIT ? OFFSET ?",
+        CATEGORY_COLUMNS
+    ))
+        .bind(context.user_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(categories, total, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/category",
+    request_body = CreateCategory,
+    responses(
+        (status = 200, description = "Category created successfully", body = ApiResponse<Category>),
+        (status = 400, description = "Name missing or already taken")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_category(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Json(payload): Json<CreateCategory>,
+) -> Result<Json<ApiResponse<Category>>, ApiError> {
+    let name = validate_name(&payload.name)?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let id = sqlx::query(
+        "INSERT INTO category (user_id, name, position)
+        SELECT ?, ?, COALESCE(MAX(position) + 1, 0) FROM category WHERE user_id = ?"
+    )
+        .bind(context.user_id)
+        .bind(name)
+        .bind(context.user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_unique_error)?
+        .last_insert_rowid();
+
+    if let Some(position) = payload.position {
+        reorder(&mut tx, context.user_id, Some((id, position)))
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
+
+    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(fetch_category(&state, context.user_id, id).await?)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/category/{id}",
+    request_body = UpdateCategory,
+    responses(
+        (status = 200, description = "Category updated successfully", body = ApiResponse<Category>),
+        (status = 400, description = "Name empty or already taken"),
+        (status = 404, description = "Category not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Category ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_category(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+    Json(payload): Json<UpdateCategory>,
+) -> Result<Json<ApiResponse<Category>>, ApiError> {
+    if payload.name.is_none() && payload.position.is_none() {
+        return Err(ApiError::BadRequest("At least one field must be provided".into()));
+    }
+    let name = payload.name.as_deref().map(validate_name).transpose()?;
+
+    fetch_category(&state, context.user_id, id).await?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if let Some(name) = name {
+        sqlx::query("UPDATE category SET name = ? WHERE id = ?")
+            .bind(name)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_unique_error)?;
+    }
+
+    if let Some(position) = payload.position {
+        reorder(&mut tx, context.user_id, Some((id, position)))
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
+
+    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(fetch_category(&state, context.user_id, id).await?)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/category/{id}",
+    responses(
+        (status = 200, description = "Category deleted, its manga are kept", body = Object),
+        (status = 404, description = "Category not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Category ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_category(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let result = sqlx::query("DELETE FROM category WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(context.user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Category not found".into()));
+    }
+
+    // Closes the gap left in the positions
+    reorder(&mut tx, context.user_id, None)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+/// Adds a manga to a category, or removes it, once checked that both belong
+/// to the caller
+pub async fn set_membership(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    id: i64,
+    manga_id: i64,
+    member: bool,
+) -> Result<(), ApiError> {
+    sqlx::query("SELECT id FROM category WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Category not found".into()))?;
+
+    sqlx::query("SELECT id FROM manga WHERE id = ? AND user_id = ?")
+        .bind(manga_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Manga not found".into()))?;
+
+    let query = if member {
+        "INSERT OR IGNORE INTO manga_category (manga_id, category_id) VALUES (?, ?)"
+    } else {
+        "DELETE FROM manga_category WHERE manga_id = ? AND category_id = ?"
+    };
+    sqlx::query(query)
+        .bind(manga_id)
+        .bind(id)
+        .execute(conn)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    put,
+    path = "/category/{id}/manga/{manga_id}",
+    responses(
+        (status = 200, description = "Manga added to the category", body = Object),
+        (status = 404, description = "Category or manga not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Category ID"),
+        ("manga_id" = i64, Path, description = "Manga ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_category_manga(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path((id, manga_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let mut conn = state.pool.acquire().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    set_membership(&mut conn, context.user_id, id, manga_id, true).await?;
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/category/{id}/manga/{manga_id}",
+    responses(
+        (status = 200, description = "Manga removed from the category", body = Object),
+        (status = 404, description = "Category or manga not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Category ID"),
+        ("manga_id" = i64, Path, description = "Manga ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn remove_category_manga(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path((id, manga_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let mut conn = state.pool.acquire().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    set_membership(&mut conn, context.user_id, id, manga_id, false).await?;
+
+    Ok(Json(ApiResponse::success_null()))
+}
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use sqlx::Row;
+use crate::auth::api_key::AuthContext;
+use crate::cover;
+use crate::state::AppState;
+use crate::utils::response::ApiError;
+
+/// Clients keep covers but revalidate them with their ETag, as the cover of a
+/// manga changes whenever it is updated
+const CACHE_CONTROL: &str = "private, no-cache";
+
+#[utoipa::path(
+    get,
+    path = "/manga/{id}/cover",
+    responses(
+        (status = 200, description = "Cached cover image", content_type = "image/*", body = Vec<u8>),
+        (status = 304, description = "Cover unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Manga not found or cover not available")
+    ),
+    params(
+        ("id" = i64, Path, description = "Manga ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_manga_cover(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    serve_cover(&state, &context, id, "cover_hash", &headers).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/manga/{id}/cover/small",
+    responses(
+        (status = 200, description = "Cached small cover, generated from the cover when none was given", content_type = "image/*", body = Vec<u8>),
+        (status = 304, description = "Cover unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Manga not found or cover not available")
+    ),
+    params(
+        ("id" = i64, Path, description = "Manga ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_manga_cover_small(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    serve_cover(&state, &context, id, "cover_small_hash", &headers).await
+}
+
+async fn cover_hash(state: &AppState, context: &AuthContext, id: i64, column: &str) -> Result<Option<String>, ApiError> {
+    let row = sqlx::query(&format!("SELECT {} AS hash FROM manga WHERE id = ? AND user_id = ?", column))
+        .bind(id)
+        .bind(context.user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    match row {
+        Some(r) => Ok(r.get("hash")),
+        None => Err(ApiError::NotFound("Manga not found".into())),
+    }
+}
+
+async fn serve_cover(
+    state: &AppState,
+    context: &AuthContext,
+    id: i64,
+    column: &str,
+    headers: &HeaderMap,
+) -> Result<Response, ApiError> {
+    let dir = cover::cover_dir(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let mut hash = cover_hash(state, context, id, column).await?;
+
+    // Manga imported or created before covers were cached are downloaded on
+    // first access, and again once a failed attempt is old enough
+    let missing = hash.as_ref().is_none_or(|h| !dir.join(h).exists());
+    if missing && cover::should_retry(&state.pool, id).await.map_err(|e| ApiError::Internal(e.to_string()))? {
+        if let Err(e) = cover::cache_covers(&state.pool, id).await {
+            tracing::warn!("Failed to cache cover of manga {}: {}", id, e);
+        }
+        hash = cover_hash(state, context, id, column).await?;
+    }
+
+    let Some(hash) = hash else {
+        return Err(ApiError::NotFound("Cover not available".into()));
+    };
+
+    let etag = format!("\"{}\"", hash);
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag == "*" || tag.trim_start_matches("W/") == etag
+        }));
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, CACHE_CONTROL.to_string())],
+        ).into_response());
+    }
+
+    let bytes = tokio::fs::read(dir.join(&hash))
+        .await
+        .map_err(|_| ApiError::NotFound("Cover not available".into()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, cover::content_type(&bytes).to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+        ],
+        bytes,
+    ).into_response())
+}
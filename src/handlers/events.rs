@@ -0,0 +1,41 @@
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use futures::stream::{self, Stream};
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+use crate::auth::api_key::AuthContext;
+use crate::state::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/events",
+    responses(
+        (status = 200, description = "Server-Sent Events stream of changes to the user's library. Each event is named after its `type` and carries the event as JSON data. A `lagged` event with the number of missed events is sent when the client falls behind.", content_type = "text/event-stream", body = String)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.events.subscribe(context.user_id);
+
+    let stream = stream::unfold(receiver, |mut receiver| async move {
+        let event = match receiver.recv().await {
+            Ok(event) => Event::default()
+                .event(event.name())
+                .json_data(&event)
+                .unwrap_or_else(|_| Event::default().event("error")),
+            Err(RecvError::Lagged(missed)) => Event::default().event("lagged").data(missed.to_string()),
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(event), receiver))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
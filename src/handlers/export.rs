@@ -0,0 +1,36 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+    Json,
+};
+use crate::auth::api_key::{AuthContext, KeyScope};
+use crate::import::library::{self, LibraryExport};
+use crate::state::AppState;
+use crate::utils::response::ApiError;
+
+#[utoipa::path(
+    get,
+    path = "/export",
+    responses(
+        (status = 200, description = "The user's library as a versioned JSON document, with the settings for admin keys", body = LibraryExport)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_library(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+) -> Result<Response, ApiError> {
+    let export = library::export_library(&state.pool, context.user_id, context.scope >= KeyScope::Admin)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let filename = format!("manga-sync-{}.json", export.exported_at.format("%Y%m%d-%H%M%S"));
+    Ok((
+        [(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))],
+        Json(export),
+    ).into_response())
+}
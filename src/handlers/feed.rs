@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use crate::auth::api_key::AuthContext;
+use crate::state::AppState;
+use crate::utils::response::ApiError;
+
+use utoipa::{ToSchema, IntoParams};
+
+const DEFAULT_FEED_SIZE: i64 = 50;
+const MAX_FEED_SIZE: i64 = 200;
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedQuery {
+    /// Number of chapters in the feed (default 50, max 200)
+    pub size: Option<i64>,
+    /// Only list the chapters of this manga
+    pub manga_id: Option<i64>,
+    /// API key, for feed readers that cannot send an Authorization header
+    pub token: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct FeedItem {
+    id: i64,
+    href: String,
+    title: Option<String>,
+    number: Option<f64>,
+    first_seen_at: NaiveDateTime,
+    manga_name: String,
+    domain: String,
+    base_url: Option<String>,
+}
+
+impl FeedItem {
+    fn title(&self) -> String {
+        let chapter = match (&self.title, self.number) {
+            (Some(title), _) => title.clone(),
+            (None, Some(number)) => format!("Chapter {}", number),
+            (None, None) => self.href.trim_end_matches('/').rsplit('/').next().unwrap_or(&self.href).to_string(),
+        };
+        format!("{} - {}", self.manga_name, chapter)
+    }
+
+    /// Absolute link to the chapter on the source site
+    fn link(&self) -> String {
+        if self.href.starts_with("http://") || self.href.starts_with("https://") {
+            return self.href.clone();
+        }
+
+        let base_url = self
+            .base_url
+            .clone()
+            .unwrap_or_else(|| format!("https://{}", self.domain));
+        format!("{}/{}", base_url.trim_end_matches('/'), self.href.trim_start_matches('/'))
+    }
+}
+
+/// Chapters detected by a sync after the first scrape of the page their
+/// source follows, most recent first. The first scrape only records what was
+/// already published.
+async fn fetch_items(state: &AppState, user_id: i64, query: &FeedQuery) -> Result<Vec<FeedItem>, ApiError> {
+    let size = query.size.unwrap_or(DEFAULT_FEED_SIZE).clamp(1, MAX_FEED_SIZE);
+
+    sqlx::query_as::<sqlx::Sqlite, FeedItem>(
+        "SELECT rc.id, rc.href, rc.title, rc.number, rc.first_seen_at,
+            m.name as manga_name, w.domain, w.base_url
+        FROM remote_chapter rc
+        JOIN source s ON s.website_id = rc.website_id AND s.path = rc.path
+        JOIN manga m ON m.id = s.manga_id
+        JOIN website w ON w.id = s.website_id
+        WHERE rc.first_seen_at > (
+            SELECT MIN(f.first_seen_at) FROM remote_chapter f WHERE f.website_id = rc.website_id AND f.path = rc.path
+        )
+        AND m.user_id = ?
+        AND (? IS NULL OR m.id = ?)
+        ORDER BY rc.first_seen_at DESC, rc.position ASC
+        LIMIT ?"
+    )
+        .bind(user_id)
+        .bind(query.manga_id)
+        .bind(query.manga_id)
+        .bind(size)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))
+}
+
+/// Base URL of this server as seen by the client
+fn server_url(headers: &HeaderMap) -> String {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("localhost:7783");
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("http");
+    format!("{}://{}", scheme, host)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn render_atom(items: &[FeedItem], server_url: &str) -> String {
+    let updated = items
+        .first()
+        .map(|i| i.first_seen_at)
+        .unwrap_or_default()
+        .and_utc()
+        .to_rfc3339();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str("  <title>Manga Manager - New chapters</title>\n");
+    xml.push_str(&format!("  <id>{}/feed.atom</id>\n", escape(server_url)));
+    xml.push_str(&format!("  <link rel=\"self\" href=\"{}/feed.atom\"/>\n", escape(server_url)));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated));
+
+    for item in items {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&item.title())));
+        xml.push_str(&format!("    <id>{}/feed/chapter/{}</id>\n", escape(server_url), item.id));
+        xml.push_str(&format!("    <link href=\"{}\"/>\n", escape(&item.link())));
+        xml.push_str(&format!("    <updated>{}</updated>\n", item.first_seen_at.and_utc().to_rfc3339()));
+        xml.push_str(&format!("    <author><name>{}</name></author>\n", escape(&item.domain)));
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn render_rss(items: &[FeedItem], server_url: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\">\n<channel>\n");
+    xml.push_str("  <title>Manga Manager - New chapters</title>\n");
+    xml.push_str(&format!("  <link>{}/feed.rss</link>\n", escape(server_url)));
+    xml.push_str("  <description>Chapters detected by the sync job</description>\n");
+
+    for item in items {
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&item.title())));
+        xml.push_str(&format!("    <link>{}</link>\n", escape(&item.link())));
+        xml.push_str(&format!("    <guid isPermaLink=\"false\">{}/feed/chapter/{}</guid>\n", escape(server_url), item.id));
+        xml.push_str(&format!("    <pubDate>{}</pubDate>\n", item.first_seen_at.and_utc().to_rfc2822()));
+        xml.push_str(&format!("    <category>{}</category>\n", escape(&item.domain)));
+        xml.push_str("  </item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed.atom",
+    params(FeedQuery),
+    responses(
+        (status = 200, description = "Atom feed of newly detected chapters", content_type = "application/atom+xml", body = String)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn atom_feed(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    headers: HeaderMap,
+    Query(query): Query<FeedQuery>,
+) -> Result<Response, ApiError> {
+    let items = fetch_items(&state, context.user_id, &query).await?;
+    let xml = render_atom(&items, &server_url(&headers));
+
+    Ok(([(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")], xml).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed.rss",
+    params(FeedQuery),
+    responses(
+        (status = 200, description = "RSS 2.0 feed of newly detected chapters", content_type = "application/rss+xml", body = String)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn rss_feed(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    headers: HeaderMap,
+    Query(query): Query<FeedQuery>,
+) -> Result<Response, ApiError> {
+    let items = fetch_items(&state, context.user_id, &query).await?;
+    let xml = render_rss(&items, &server_url(&headers));
+
+    Ok(([(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")], xml).into_response())
+}
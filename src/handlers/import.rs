@@ -0,0 +1,117 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    Extension,
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+use crate::auth::api_key::{AuthContext, KeyScope};
+use crate::events::LibraryEvent;
+use crate::import::library::{self, ImportMode, LibraryExport, LibraryImportReport};
+use crate::import::mihon::{self, ImportReport};
+use crate::import::ImportError;
+use crate::state::AppState;
+use crate::utils::response::{ApiResponse, ApiError};
+
+use utoipa::IntoParams;
+
+/// Backups with many manga and chapters easily exceed axum's 2 MB default
+pub const MAX_BACKUP_SIZE: usize = 50 * 1024 * 1024;
+
+impl From<ImportError> for ApiError {
+    fn from(e: ImportError) -> Self {
+        match e {
+            ImportError::InvalidFile(_) => ApiError::BadRequest(e.to_string()),
+            ImportError::Database(_) => ApiError::Internal(e.to_string()),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/import/mihon",
+    request_body(content = Vec<u8>, description = "Mihon or Tachiyomi `.tachibk` backup file", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Backup imported, with the manga whose source matched no website", body = ApiResponse<ImportReport>),
+        (status = 400, description = "Invalid backup file")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn import_mihon(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    body: Bytes,
+) -> Result<Json<ApiResponse<ImportReport>>, ApiError> {
+    let backup = mihon::parse_backup(&body)?;
+    let report = mihon::import_backup(&state.pool, context.user_id, &backup).await?;
+
+    info!(
+        "Mihon backup imported: {} manga created, {} updated, {} unmatched",
+        report.manga_created, report.manga_updated, report.unmatched.len()
+    );
+
+    for (manga_id, name) in &report.created {
+        state.events.publish(context.user_id, LibraryEvent::MangaCreated { manga_id: *manga_id, name: name.clone() });
+    }
+    for manga_id in &report.updated {
+        state.events.publish(context.user_id, LibraryEvent::MangaUpdated { manga_id: *manga_id });
+    }
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LibraryImportQuery {
+    /// `merge` (default) or `replace`
+    pub mode: Option<ImportMode>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/import",
+    params(LibraryImportQuery),
+    request_body(content = LibraryExport, description = "Document produced by `GET /export`"),
+    responses(
+        (status = 200, description = "Library imported, with what could not be imported as is", body = ApiResponse<LibraryImportReport>),
+        (status = 400, description = "Unsupported export version")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn import_library(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<LibraryImportQuery>,
+    Json(document): Json<LibraryExport>,
+) -> Result<Json<ApiResponse<LibraryImportReport>>, ApiError> {
+    let mode = query.mode.unwrap_or_default();
+    let report = library::import_library(
+        &state.pool,
+        context.user_id,
+        &document,
+        mode,
+        context.scope >= KeyScope::Admin,
+    ).await?;
+
+    info!(
+        "Library imported ({:?}): {} manga created, {} merged, {} conflicts",
+        mode, report.manga_created, report.manga_merged, report.conflicts.len()
+    );
+
+    for manga_id in &report.deleted {
+        state.events.publish(context.user_id, LibraryEvent::MangaDeleted { manga_id: *manga_id });
+    }
+    for (manga_id, name) in &report.created {
+        state.events.publish(context.user_id, LibraryEvent::MangaCreated { manga_id: *manga_id, name: name.clone() });
+    }
+    for manga_id in &report.merged {
+        state.events.publish(context.user_id, LibraryEvent::MangaUpdated { manga_id: *manga_id });
+    }
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -1,11 +1,18 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
+    Extension,
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::info;
+use crate::auth::api_key::{self, AuthContext, KeyScope};
+use crate::auth::key_manager::KeyManager;
+use crate::models::ApiKey;
 use crate::state::AppState;
-use crate::utils::response::{ApiResponse, ApiError};
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+
+use utoipa::ToSchema;
 
 #[derive(Serialize)]
 pub struct KeyAgeResponse {
@@ -60,3 +67,150 @@ pub async fn refresh_key(
 
     Ok(Json(ApiResponse::success(KeyRefreshResponse { key: new_key })))
 }
+
+const API_KEY_COLUMNS: &str = "id, user_id, name, scope, created_at, last_used_at, revoked_at";
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scope: KeyScope,
+    /// User the key is bound to, defaults to the user of the calling key
+    pub user_id: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    /// Plaintext key, only returned once
+    pub key: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/key/api",
+    responses(
+        (status = 200, description = "List named API keys, including revoked ones", body = ApiResponse<Page<ApiKey>>),
+        (status = 400, description = "Invalid page size")
+    ),
+    params(PageQuery),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<ApiKey>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_key")
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let keys = sqlx::query_as::<sqlx::Sqlite, ApiKey>(&format!("SELECT {} FROM api_key ORDER BY id LIMIT ? OFFSET ?", API_KEY_COLUMNS))
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(keys, total, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/key/api",
+    request_body = CreateApiKey,
+    responses(
+        (status = 200, description = "Create a named API key and return its plaintext value", body = ApiResponse<CreatedApiKey>),
+        (status = 400, description = "Name missing, already used by an active key or unknown user")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Json(payload): Json<CreateApiKey>,
+) -> Result<Json<ApiResponse<CreatedApiKey>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("name is required".into()));
+    }
+
+    let user_id = payload.user_id.unwrap_or(context.user_id);
+    let user = sqlx::query("SELECT id FROM user WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if user.is_none() {
+        return Err(ApiError::BadRequest("User does not exist".into()));
+    }
+
+    let key = api_key::generate_key();
+
+    let id = sqlx::query("INSERT INTO api_key (user_id, name, key_hash, scope) VALUES (?, ?, ?, ?)")
+        .bind(user_id)
+        .bind(name)
+        .bind(KeyManager::hash_key(&key))
+        .bind(payload.scope.as_str())
+        .execute(&state.pool)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                ApiError::BadRequest("An active key already uses this name".into())
+            } else {
+                ApiError::Internal(e.to_string())
+            }
+        })?
+        .last_insert_rowid();
+
+    let api_key = sqlx::query_as::<sqlx::Sqlite, ApiKey>(&format!("SELECT {} FROM api_key WHERE id = ?", API_KEY_COLUMNS))
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    info!("API key '{}' ({}) created by '{}'", api_key.name, api_key.scope, context.name);
+
+    Ok(Json(ApiResponse::success(CreatedApiKey { api_key, key })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/key/api/{id}",
+    responses(
+        (status = 200, description = "API key revoked successfully", body = Object),
+        (status = 404, description = "API key not found or already revoked")
+    ),
+    params(
+        ("id" = i64, Path, description = "API key ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let result = sqlx::query("UPDATE api_key SET revoked_at = CURRENT_TIMESTAMP WHERE id = ? AND revoked_at IS NULL")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("API key not found or already revoked".into()));
+    }
+
+    info!("API key #{} revoked by '{}'", id, context.name);
+
+    Ok(Json(ApiResponse::success_null()))
+}
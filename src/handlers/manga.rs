@@ -1,18 +1,38 @@
 use axum::{
     extract::{Path, Query, State},
+    Extension,
     Json,
 };
-use sqlx::Row;
+use reqwest::Url;
+use sqlx::{QueryBuilder, Row, SqliteConnection};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::auth::api_key::AuthContext;
+use crate::cover;
+use crate::db::duplicates::{self, Duplicate, MergeReport};
+use crate::db::manga_query::MangaQuery;
+use crate::db::metadata::{self, MangaInfo, Tag};
+use crate::db::reading::{self, ReadingStatus};
+use crate::events::LibraryEvent;
 use crate::state::AppState;
+use crate::sync::history::{SyncScope, SyncTrigger};
 use crate::sync::http_client::create_client;
+use crate::sync::metadata::{MangaMetadata, PublicationStatus};
+use crate::sync::reconcile;
+use crate::sync::remote_chapters;
+use crate::sync::service::SyncService;
 use crate::sync::strategies::StrategyRegistry;
-use crate::utils::response::{ApiResponse, ApiError};
+use crate::utils::nullable;
+use crate::utils::pagination::{page_bounds, PageQuery};
+use crate::utils::response::{ApiResponse, ApiError, Page};
+use crate::models::RemoteChapter;
 
 use utoipa::{ToSchema, IntoParams};
 
+/// Query of `GET /manga`. Filters are sent as `filter[KEY]=value` (or
+/// `filter[0][KEY]=value`), and may be repeated.
 #[derive(Deserialize, ToSchema, IntoParams)]
+#[serde(try_from = "Vec<(String, String)>")]
 #[into_params(parameter_in = Query)]
 pub struct Pagination {
     pub size: Option<i64>,
@@ -20,6 +40,37 @@ pub struct Pagination {
     pub filter: Option<Vec<HashMap<String, String>>>,
 }
 
+impl TryFrom<Vec<(String, String)>> for Pagination {
+    type Error = String;
+
+    fn try_from(pairs: Vec<(String, String)>) -> Result<Self, Self::Error> {
+        let mut pagination = Pagination { size: None, page: None, filter: None };
+
+        for (key, value) in pairs {
+            match key.as_str() {
+                "size" => pagination.size = Some(value.parse().map_err(|_| "Invalid size")?),
+                "page" => pagination.page = Some(value.parse().map_err(|_| "Invalid page")?),
+                _ => {
+                    let Some(filter) = key.strip_prefix("filter[").and_then(|k| k.strip_suffix(']')) else {
+                        // e.g. the `token` of clients that cannot set headers
+                        continue;
+                    };
+                    let filter_key = match filter.split_once("][") {
+                        Some((index, filter_key)) if index.parse::<usize>().is_ok() => filter_key,
+                        _ => filter,
+                    };
+                    pagination
+                        .filter
+                        .get_or_insert_with(Vec::new)
+                        .push(HashMap::from([(filter_key.to_string(), value)]));
+                }
+            }
+        }
+
+        Ok(pagination)
+    }
+}
+
 #[derive(Serialize, sqlx::FromRow, ToSchema)]
 pub struct MangaListItem {
     pub id: i64,
@@ -27,6 +78,9 @@ pub struct MangaListItem {
     pub cover: String,
     pub current_chapter: Option<String>,
     pub number_unread_chapter: Option<i64>,
+    /// Domain of the source listing the most recent chapter
+    pub furthest_source: Option<String>,
+    pub reading_status: ReadingStatus,
 }
 
 #[utoipa::path(
@@ -34,7 +88,8 @@ pub struct MangaListItem {
     path = "/manga",
     params(Pagination),
     responses(
-        (status = 200, description = "List manga successfully", body = Object)
+        (status = 200, description = "List manga successfully", body = ApiResponse<Page<MangaListItem>>),
+        (status = 400, description = "Invalid page size, filter or sort")
     ),
     security(
         ("bearer_auth" = [])
@@ -42,60 +97,45 @@ pub struct MangaListItem {
 )]
 pub async fn list_manga(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Query(pagination): Query<Pagination>,
-) -> Result<Json<ApiResponse<Vec<MangaListItem>>>, ApiError> {
-    let size = pagination.size.unwrap_or(20);
-    let page = pagination.page.unwrap_or(1);
-    let offset = (page - 1) * size;
+) -> Result<Json<ApiResponse<Page<MangaListItem>>>, ApiError> {
+    let (size, offset) = page_bounds(pagination.size, pagination.page)?;
+
+    let query = MangaQuery::parse(pagination.filter.as_deref().unwrap_or_default())
+        .map_err(ApiError::BadRequest)?;
 
-    let mut query_builder = String::from(
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM manga m");
+    query.push_where(&mut count, context.user_id);
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let mut select = QueryBuilder::new(
         "SELECT m.id, m.name, m.cover_small as cover,
         (SELECT c.number FROM chapter c WHERE c.manga_id = m.id ORDER BY c.updated_at DESC LIMIT 1) as current_chapter,
-        (SELECT MAX(s.number_unread_chapter) FROM source s WHERE s.manga_id = m.id) as number_unread_chapter
+        (SELECT MAX(s.number_unread_chapter) FROM source s WHERE s.manga_id = m.id) as number_unread_chapter,
+        (SELECT w.domain FROM source s
+            JOIN website w ON w.id = s.website_id
+            JOIN remote_chapter rc ON rc.website_id = s.website_id AND rc.path = s.path
+            WHERE s.manga_id = m.id AND rc.number IS NOT NULL
+            ORDER BY rc.number DESC, s.number_unread_chapter DESC LIMIT 1) as furthest_source,
+        m.reading_status
         FROM manga m"
     );
+    query.push_where(&mut select, context.user_id);
+    query.push_order_by(&mut select);
+    select.push(" LIMIT ").push_bind(size).push(" OFFSET ").push_bind(offset);
 
-    let mut filters = Vec::new();
-    let mut sort = "ORDER BY (SELECT MAX(updated_at) FROM chapter WHERE manga_id = m.id) DESC";
-
-    if let Some(filter_list) = pagination.filter {
-        for f in filter_list {
-            for (key, value) in f {
-                match key.as_str() {
-                    "READ_AT" => {
-                        sort = match value.as_str() {
-                            "ASC" => "ORDER BY (SELECT MAX(updated_at) FROM chapter WHERE manga_id = m.id) ASC",
-                            "DESC" => "ORDER BY (SELECT MAX(updated_at) FROM chapter WHERE manga_id = m.id) DESC",
-                            _ => return Err(ApiError::BadRequest("Invalid READ_AT value".into())),
-                        };
-                    }
-                    "TEXT" => {
-                        filters.push(format!("m.name LIKE '%{}%'", value.replace("'", "''")));
-                    }
-                    "WEBSITE" => {
-                        filters.push(format!("EXISTS (SELECT 1 FROM source s JOIN website w ON s.website_id = w.id WHERE s.manga_id = m.id AND w.domain = '{}')", value.replace("'", "''")));
-                    }
-                    _ => return Err(ApiError::BadRequest(format!("Unknown filter key: {}", key))),
-                }
-            }
-        }
-    }
-
-    if !filters.is_empty() {
-        query_builder.push_str(" WHERE ");
-        query_builder.push_str(&filters.join(" AND "));
-    }
-
-    query_builder.push_str(" ");
-    query_builder.push_str(sort);
-    query_builder.push_str(&format!(" LIMIT {} OFFSET {}", size, offset));
-
-    let manga_list = sqlx::query_as::<sqlx::Sqlite, MangaListItem>(&query_builder)
+    let items = select
+        .build_query_as::<MangaListItem>()
         .fetch_all(&state.pool)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(manga_list)))
+    Ok(Json(ApiResponse::success(Page::new(items, total, pagination.page.unwrap_or(1), size))))
 }
 
 #[derive(Serialize, sqlx::FromRow, ToSchema)]
@@ -106,6 +146,27 @@ pub struct MangaDetail {
     pub current_chapter: Option<String>,
     pub last_read_at: Option<chrono::NaiveDateTime>,
     pub number_unread_chapter: Option<i64>,
+    /// Domain of the source listing the most recent chapter
+    pub furthest_source: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<PublicationStatus>,
+    pub year: Option<i64>,
+    pub reading_status: ReadingStatus,
+    /// When the manga was first read
+    pub started_at: Option<chrono::NaiveDateTime>,
+    /// When the manga was last finished, while it is `completed`
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    /// When the manga was added to the library
+    pub created_at: Option<chrono::NaiveDateTime>,
+    #[sqlx(skip)]
+    pub authors: Vec<String>,
+    #[sqlx(skip)]
+    pub genres: Vec<String>,
+    #[sqlx(skip)]
+    pub alt_titles: Vec<String>,
+    /// Names of the categories the manga is in, in the user's order
+    #[sqlx(skip)]
+    pub categories: Vec<String>,
 }
 
 #[utoipa::path(
@@ -124,24 +185,49 @@ pub struct MangaDetail {
 )]
 pub async fn get_manga(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path(id): Path<i64>,
 ) -> Result<Json<ApiResponse<MangaDetail>>, ApiError> {
     let manga = sqlx::query_as::<sqlx::Sqlite, MangaDetail>(
         "SELECT m.id, m.name, m.cover,
         (SELECT c.number FROM chapter c WHERE c.manga_id = m.id ORDER BY c.updated_at DESC LIMIT 1) as current_chapter,
         (SELECT MAX(c.updated_at) FROM chapter c WHERE c.manga_id = m.id) as last_read_at,
-        (SELECT MAX(s.number_unread_chapter) FROM source s WHERE s.manga_id = m.id) as number_unread_chapter
-        FROM manga m WHERE m.id = ?"
+        (SELECT MAX(s.number_unread_chapter) FROM source s WHERE s.manga_id = m.id) as number_unread_chapter,
+        (SELECT w.domain FROM source s
+            JOIN website w ON w.id = s.website_id
+            JOIN remote_chapter rc ON rc.website_id = s.website_id AND rc.path = s.path
+            WHERE s.manga_id = m.id AND rc.number IS NOT NULL
+            ORDER BY rc.number DESC, s.number_unread_chapter DESC LIMIT 1) as furthest_source,
+        m.description, m.status, m.year, m.reading_status, m.started_at, m.completed_at, m.created_at
+        FROM manga m WHERE m.id = ? AND m.user_id = ?"
     )
     .bind(id)
+    .bind(context.user_id)
     .fetch_optional(&state.pool)
     .await
     .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    match manga {
-        Some(m) => Ok(Json(ApiResponse::success(m))),
-        None => Err(ApiError::NotFound("Manga not found".into())),
-    }
+    let Some(mut manga) = manga else {
+        return Err(ApiError::NotFound("Manga not found".into()));
+    };
+
+    let mut conn = state.pool.acquire().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    let info = metadata::load(&mut conn, id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    manga.authors = info.authors;
+    manga.genres = info.genres;
+    manga.alt_titles = info.alt_titles;
+    manga.categories = sqlx::query_scalar(
+        "SELECT c.name FROM manga_category mc JOIN category c ON c.id = mc.category_id
+        WHERE mc.manga_id = ? ORDER BY c.position"
+    )
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(manga)))
 }
 
 #[derive(Serialize, sqlx::FromRow, ToSchema)]
@@ -151,16 +237,21 @@ pub struct MangaSource {
     pub website_id: i64,
     pub path: String,
     pub number_unread_chapter: Option<i64>,
+    /// Highest chapter number in the source's stored chapter list
+    pub latest_chapter: Option<f64>,
+    pub is_furthest_ahead: bool,
 }
 
 #[utoipa::path(
     get,
     path = "/manga/{id}/source",
     responses(
-        (status = 200, description = "Get manga sources", body = ApiResponse<Vec<MangaSource>>)
+        (status = 200, description = "Get manga sources", body = ApiResponse<Page<MangaSource>>),
+        (status = 400, description = "Invalid page size")
     ),
     params(
-        ("id" = i64, Path, description = "Manga ID")
+        ("id" = i64, Path, description = "Manga ID"),
+        PageQuery
     ),
     security(
         ("bearer_auth" = [])
@@ -168,17 +259,103 @@ pub struct MangaSource {
 )]
 pub async fn get_manga_sources(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path(id): Path<i64>,
-) -> Result<Json<ApiResponse<Vec<MangaSource>>>, ApiError> {
-    let sources = sqlx::query_as::<sqlx::Sqlite, MangaSource>(
-        "SELECT id, manga_id, website_id, path, number_unread_chapter FROM source WHERE manga_id = ?"
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<MangaSource>>>, ApiError> {
+    let (size, _) = query.bounds()?;
+
+    // The source furthest ahead is picked among all of them, so the page is cut afterwards
+    let mut sources = sqlx::query_as::<sqlx::Sqlite, MangaSource>(
+        "SELECT s.id, s.manga_id, s.website_id, s.path, s.number_unread_chapter,
+        (SELECT MAX(rc.number) FROM remote_chapter rc WHERE rc.website_id = s.website_id AND rc.path = s.path) as latest_chapter,
+        0 as is_furthest_ahead
+        FROM source s JOIN manga m ON m.id = s.manga_id
+        WHERE s.manga_id = ? AND m.user_id = ? ORDER BY s.id"
     )
         .bind(id)
+        .bind(context.user_id)
         .fetch_all(&state.pool)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(sources)))
+    let furthest = sources
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.latest_chapter.map(|latest| (i, latest, s.number_unread_chapter)))
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)))
+        .map(|(i, _, _)| i);
+    if let Some(i) = furthest {
+        sources[i].is_furthest_ahead = true;
+    }
+
+    Ok(Json(ApiResponse::success(Page::from_all(sources, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/manga/{id}/source/{domain}/chapters",
+    responses(
+        (status = 200, description = "Get the chapter list scraped from a source, newest first", body = ApiResponse<Page<RemoteChapter>>),
+        (status = 400, description = "Invalid page size"),
+        (status = 404, description = "Source not found for this manga")
+    ),
+    params(
+        ("id" = i64, Path, description = "Manga ID"),
+        ("domain" = String, Path, description = "Website domain"),
+        PageQuery
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_manga_source_chapters(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path((id, domain)): Path<(i64, String)>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<RemoteChapter>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let source = sqlx::query(
+        "SELECT s.id FROM source s
+        JOIN website w ON w.id = s.website_id
+        JOIN manga m ON m.id = s.manga_id
+        WHERE s.manga_id = ? AND w.domain = ? AND m.user_id = ?"
+    )
+        .bind(id)
+        .bind(&domain)
+        .bind(context.user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let source_id = match source {
+        Some(s) => s.get::<i64, _>("id"),
+        None => return Err(ApiError::NotFound("Source not found for this manga".into())),
+    };
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM remote_chapter rc JOIN source s ON s.website_id = rc.website_id AND s.path = rc.path WHERE s.id = ?"
+    )
+        .bind(source_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let chapters = sqlx::query_as::<sqlx::Sqlite, RemoteChapter>(
+        "SELECT rc.id, s.id as source_id, rc.href, rc.volume, rc.number, rc.part, rc.title, rc.position, rc.first_seen_at
+        FROM remote_chapter rc JOIN source s ON s.website_id = rc.website_id AND s.path = rc.path
+        WHERE s.id = ? ORDER BY rc.position LIMIT ? OFFSET ?"
+    )
+        .bind(source_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(chapters, total, query.page.unwrap_or(1), size))))
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -205,13 +382,30 @@ pub struct CreateMangaSource {
 )]
 pub async fn create_manga_source(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path(id): Path<i64>,
     Json(payload): Json<CreateMangaSource>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let mut conn = state.pool.acquire().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    insert_source(&mut conn, context.user_id, id, &payload).await?;
+
+    state.events.publish(context.user_id, LibraryEvent::MangaUpdated { manga_id: id });
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+/// Adds a source to a manga of the user
+pub async fn insert_source(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    id: i64,
+    payload: &CreateMangaSource,
+) -> Result<(), ApiError> {
     // Verify manga exists
-    let manga = sqlx::query("SELECT id FROM manga WHERE id = ?")
+    let manga = sqlx::query("SELECT id FROM manga WHERE id = ? AND user_id = ?")
         .bind(id)
-        .fetch_optional(&state.pool)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
@@ -222,7 +416,7 @@ pub async fn create_manga_source(
     // Verify website exists
     let website = sqlx::query("SELECT id FROM website WHERE id = ?")
         .bind(payload.website_id)
-        .fetch_optional(&state.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
@@ -237,7 +431,7 @@ pub async fn create_manga_source(
         .bind(id)
         .bind(payload.website_id)
         .bind(path)
-        .execute(&state.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| {
             if e.to_string().contains("UNIQUE constraint failed") {
@@ -247,11 +441,13 @@ pub async fn create_manga_source(
             }
         })?;
 
-    Ok(Json(ApiResponse::success_null()))
+    Ok(())
 }
 
 #[derive(Serialize, sqlx::FromRow, ToSchema)]
 pub struct HistoryItem {
+    #[serde(skip)]
+    pub id: i64,
     pub number: String,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -260,10 +456,12 @@ pub struct HistoryItem {
     get,
     path = "/manga/{id}/history",
     responses(
-        (status = 200, description = "Get manga reading history", body = ApiResponse<Vec<HistoryItem>>)
+        (status = 200, description = "Get manga reading history, most recent first", body = ApiResponse<Page<HistoryItem>>),
+        (status = 400, description = "Invalid page size or cursor")
     ),
     params(
-        ("id" = i64, Path, description = "Manga ID")
+        ("id" = i64, Path, description = "Manga ID"),
+        PageQuery
     ),
     security(
         ("bearer_auth" = [])
@@ -271,24 +469,49 @@ pub struct HistoryItem {
 )]
 pub async fn get_manga_history(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path(id): Path<i64>,
-) -> Result<Json<ApiResponse<Vec<HistoryItem>>>, ApiError> {
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<HistoryItem>>>, ApiError> {
+    // Chapters read after the first page was fetched sort before the cursor,
+    // so they never shift the following pages
+    let (size, offset, after) = query.keyset::<(chrono::NaiveDateTime, i64)>()?;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM chapter c JOIN manga m ON m.id = c.manga_id WHERE c.manga_id = ? AND m.user_id = ?"
+    )
+    .bind(id)
+    .bind(context.user_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let (updated_at, chapter_id) = after.unzip();
     let history = sqlx::query_as::<sqlx::Sqlite, HistoryItem>(
-        "SELECT number, updated_at FROM chapter WHERE manga_id = ? ORDER BY updated_at DESC"
+        "SELECT c.id, c.number, c.updated_at FROM chapter c JOIN manga m ON m.id = c.manga_id
+        WHERE c.manga_id = ? AND m.user_id = ? AND (? IS NULL OR (c.updated_at, c.id) < (?, ?))
+        ORDER BY c.updated_at DESC, c.id DESC LIMIT ? OFFSET ?"
     )
     .bind(id)
+    .bind(context.user_id)
+    .bind(chapter_id)
+    .bind(updated_at)
+    .bind(chapter_id)
+    .bind(size + 1)
+    .bind(offset)
     .fetch_all(&state.pool)
     .await
     .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(history)))
+    Ok(Json(ApiResponse::success(Page::keyset(history, total, &query, size, |item| (item.updated_at, item.id)))))
 }
 
 #[derive(Deserialize, ToSchema)]
 pub struct CreateManga {
     pub name: String,
     pub cover: String,
-    pub cover_small: String,
+    /// Generated from `cover` when omitted
+    pub cover_small: Option<String>,
     pub source_path: Option<String>,
     pub website_domain: Option<String>,
 }
@@ -306,6 +529,7 @@ pub struct CreateManga {
 )]
 pub async fn create_manga(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Json(payload): Json<CreateManga>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
     if payload.source_path.is_some() != payload.website_domain.is_some() {
@@ -314,10 +538,12 @@ pub async fn create_manga(
 
     let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    let manga_id = sqlx::query("INSERT INTO manga (name, cover, cover_small) VALUES (?, ?, ?)")
-        .bind(payload.name.trim())
+    let name = payload.name.trim().to_string();
+    let manga_id = sqlx::query("INSERT INTO manga (user_id, name, cover, cover_small) VALUES (?, ?, ?, ?)")
+        .bind(context.user_id)
+        .bind(&name)
         .bind(&payload.cover)
-        .bind(&payload.cover_small)
+        .bind(payload.cover_small.as_deref().unwrap_or(&payload.cover))
         .execute(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?
@@ -348,9 +574,144 @@ pub async fn create_manga(
 
     tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
 
+    if let Err(e) = cover::cache_covers(&state.pool, manga_id).await {
+        tracing::warn!("Failed to cache cover of manga {}: {}", manga_id, e);
+    }
+
+    state.events.publish(context.user_id, LibraryEvent::MangaCreated { manga_id, name });
+
     Ok(Json(ApiResponse::success_null()))
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct CreateMangaFromUrl {
+    /// Manga page on a configured website, e.g. `https://www.mangaread.org/manga/solo-leveling/`
+    pub url: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedManga {
+    pub id: i64,
+    pub name: String,
+    pub website_domain: String,
+    pub source_path: String,
+    /// Everything read from the page
+    pub metadata: MangaMetadata,
+}
+
+#[utoipa::path(
+    post,
+    path = "/manga/from-url",
+    request_body = CreateMangaFromUrl,
+    responses(
+        (status = 200, description = "Manga created from its source page, with its source", body = ApiResponse<CreatedManga>),
+        (status = 400, description = "Unknown website, unreadable page or manga already in the library")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_manga_from_url(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Json(payload): Json<CreateMangaFromUrl>,
+) -> Result<Json<ApiResponse<CreatedManga>>, ApiError> {
+    let url = Url::parse(payload.url.trim()).map_err(|e| ApiError::BadRequest(format!("Invalid URL: {}", e)))?;
+    let host = url.host_str().ok_or_else(|| ApiError::BadRequest("URL has no host".into()))?;
+    let path = url.path().trim_end_matches('/').to_string();
+
+    // Websites are often configured with or without the `www.` prefix
+    let alternate_host = match host.strip_prefix("www.") {
+        Some(bare) => bare.to_string(),
+        None => format!("www.{}", host),
+    };
+    let website = sqlx::query("SELECT id, domain FROM website WHERE domain IN (?, ?) ORDER BY domain = ? DESC LIMIT 1")
+        .bind(host)
+        .bind(&alternate_host)
+        .bind(host)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let (website_id, domain) = match website {
+        Some(w) => (w.get::<i64, _>("id"), w.get::<String, _>("domain")),
+        None => return Err(ApiError::BadRequest(format!("No website configured for {}", host))),
+    };
+
+    let registry = StrategyRegistry::load(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let strategy = registry
+        .get(&domain)
+        .ok_or_else(|| ApiError::BadRequest(format!("No scraping strategy for {}", domain)))?;
+
+    let metadata = strategy
+        .fetch_metadata(&create_client(), &path)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read {}: {}", url, e)))?
+        .ok_or_else(|| ApiError::BadRequest(format!("{} does not support reading manga details", domain)))?;
+
+    let name = metadata
+        .title
+        .clone()
+        .ok_or_else(|| ApiError::BadRequest(format!("No title found on {}", url)))?;
+    let cover = metadata.cover.clone().unwrap_or_default();
+
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let manga_id = sqlx::query("INSERT INTO manga (user_id, name, cover, cover_small) VALUES (?, ?, ?, ?)")
+        .bind(context.user_id)
+        .bind(&name)
+        .bind(&cover)
+        .bind(&cover)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                ApiError::BadRequest(format!("Manga '{}' already exists", name))
+            } else {
+                ApiError::Internal(e.to_string())
+            }
+        })?
+        .last_insert_rowid();
+
+    sqlx::query("INSERT INTO source (manga_id, website_id, path) VALUES (?, ?, ?)")
+        .bind(manga_id)
+        .bind(website_id)
+        .bind(&path)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let info = MangaInfo {
+        description: metadata.description.clone(),
+        status: metadata.status,
+        year: None,
+        authors: metadata.authors.clone(),
+        genres: metadata.genres.clone(),
+        alt_titles: metadata.alt_titles.clone(),
+    };
+    metadata::save(&mut tx, manga_id, &info)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if let Err(e) = cover::cache_covers(&state.pool, manga_id).await {
+        tracing::warn!("Failed to cache cover of manga {}: {}", manga_id, e);
+    }
+
+    state.events.publish(context.user_id, LibraryEvent::MangaCreated { manga_id, name: name.clone() });
+
+    Ok(Json(ApiResponse::success(CreatedManga {
+        id: manga_id,
+        name,
+        website_domain: domain,
+        source_path: path,
+        metadata,
+    })))
+}
+
 #[derive(Deserialize, ToSchema)]
 pub struct UpdateManga {
     pub name: Option<String>,
@@ -359,6 +720,30 @@ pub struct UpdateManga {
     pub source_path: Option<String>,
     pub website_domain: Option<String>,
     pub chapter_number: Option<String>,
+    /// An empty description clears it
+    pub description: Option<String>,
+    /// `null` clears it
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<PublicationStatus>, nullable)]
+    pub status: Option<Option<PublicationStatus>>,
+    /// `null` clears it
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<i64>, nullable)]
+    pub year: Option<Option<i64>>,
+    /// Replace the whole list
+    pub authors: Option<Vec<String>>,
+    pub genres: Option<Vec<String>>,
+    pub alt_titles: Option<Vec<String>>,
+    /// Set automatically when not given: reading a chapter starts a planned
+    /// manga, and reading the last chapter of a finished series completes it
+    pub reading_status: Option<ReadingStatus>,
+}
+
+impl UpdateManga {
+    fn updates_metadata(&self) -> bool {
+        self.description.is_some() || self.status.is_some() || self.year.is_some() ||
+            self.authors.is_some() || self.genres.is_some() || self.alt_titles.is_some()
+    }
 }
 
 #[utoipa::path(
@@ -378,36 +763,107 @@ pub struct UpdateManga {
 )]
 pub async fn update_manga(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path(id): Path<i64>,
     Json(payload): Json<UpdateManga>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
+    validate_update(&payload)?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    let applied = apply_update(&mut tx, context.user_id, id, &payload).await?;
+    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    finish_update(&state, context.user_id, id, &payload, applied).await;
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+/// Rejects the updates `PATCH /manga/{id}` cannot apply, before any query
+pub fn validate_update(payload: &UpdateManga) -> Result<(), ApiError> {
     if payload.name.is_none() && payload.cover.is_none() && payload.cover_small.is_none() &&
-       payload.source_path.is_none() && payload.website_domain.is_none() && payload.chapter_number.is_none() {
+       payload.source_path.is_none() && payload.website_domain.is_none() && payload.chapter_number.is_none() &&
+       payload.reading_status.is_none() && !payload.updates_metadata() {
         return Err(ApiError::BadRequest("At least one field required".into()));
     }
 
+    if let Some(Some(year)) = payload.year
+        && !(1000..=9999).contains(&year)
+    {
+        return Err(ApiError::BadRequest("Invalid year".into()));
+    }
+
     if payload.source_path.is_some() && payload.website_domain.is_none() {
         return Err(ApiError::BadRequest("website_domain required if source_path exists".into()));
     }
 
-    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(())
+}
+
+/// What [`apply_update`] changed, for [`finish_update`] once committed
+pub struct AppliedUpdate {
+    /// (source_id, domain, path) of the source the chapter was read on
+    source_info: Option<(i64, String, String)>,
+    details_updated: bool,
+    chapter_read: bool,
+    reading_changed: bool,
+}
+
+/// Writes a validated update of a manga of the user
+pub async fn apply_update(
+    tx: &mut SqliteConnection,
+    user_id: i64,
+    id: i64,
+    payload: &UpdateManga,
+) -> Result<AppliedUpdate, ApiError> {
+    let manga = sqlx::query("SELECT id FROM manga WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if manga.is_none() {
+        return Err(ApiError::NotFound("Manga not found".into()));
+    }
+
+    if payload.name.is_some() || payload.cover.is_some() || payload.cover_small.is_some() ||
+       payload.description.is_some() || payload.status.is_some() || payload.year.is_some() {
+        // A small cover generated from the previous cover follows the new one
+        let follow_cover = payload.cover.is_some() && payload.cover_small.is_none();
 
-    if payload.name.is_some() || payload.cover.is_some() || payload.cover_small.is_some() {
         let mut updates = Vec::new();
         if payload.name.is_some() { updates.push("name = ?"); }
         if payload.cover.is_some() { updates.push("cover = ?"); }
         if payload.cover_small.is_some() { updates.push("cover_small = ?"); }
+        if follow_cover { updates.push("cover_small = CASE WHEN cover_small = cover THEN ? ELSE cover_small END"); }
+        if payload.description.is_some() { updates.push("description = NULLIF(TRIM(?), '')"); }
+        if payload.status.is_some() { updates.push("status = ?"); }
+        if payload.year.is_some() { updates.push("year = ?"); }
 
         let query = format!("UPDATE manga SET {} WHERE id = ?", updates.join(", "));
         let mut q = sqlx::query(&query);
         if let Some(ref v) = payload.name { q = q.bind(v); }
         if let Some(ref v) = payload.cover { q = q.bind(v); }
         if let Some(ref v) = payload.cover_small { q = q.bind(v); }
+        if follow_cover { q = q.bind(payload.cover.as_ref()); }
+        if let Some(ref v) = payload.description { q = q.bind(v); }
+        if let Some(v) = payload.status { q = q.bind(v); }
+        if let Some(v) = payload.year { q = q.bind(v); }
         q = q.bind(id);
 
         q.execute(&mut *tx).await.map_err(|e| ApiError::Internal(e.to_string()))?;
     }
 
+    if let Some(ref authors) = payload.authors {
+        metadata::set_tags(tx, id, Tag::Author, authors).await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
+    if let Some(ref genres) = payload.genres {
+        metadata::set_tags(tx, id, Tag::Genre, genres).await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
+    if let Some(ref alt_titles) = payload.alt_titles {
+        metadata::set_alt_titles(tx, id, alt_titles).await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
+
     // Track source info for potential unread refresh
     let mut source_info: Option<(i64, String, String)> = None; // (source_id, domain, path)
 
@@ -467,8 +923,11 @@ pub async fn update_manga(
         }
     }
 
-    let chapter_number = payload.chapter_number.clone();
-    if let Some(ref chapter_num) = chapter_number {
+    let details_updated = payload.name.is_some() || payload.cover.is_some() || payload.cover_small.is_some() ||
+        payload.source_path.is_some() || payload.reading_status.is_some() || payload.updates_metadata();
+    let mut chapter_read = false;
+
+    if let Some(ref chapter_num) = payload.chapter_number {
         let last_chapter = sqlx::query("SELECT number FROM chapter WHERE manga_id = ? ORDER BY updated_at DESC LIMIT 1")
             .bind(id)
             .fetch_optional(&mut *tx)
@@ -487,64 +946,149 @@ pub async fn update_manga(
                 .execute(&mut *tx)
                 .await
                 .map_err(|e| ApiError::Internal(e.to_string()))?;
+            chapter_read = true;
         }
     }
 
-    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    let mut reading_changed = false;
+    if let Some(status) = payload.reading_status {
+        reading::set_status(tx, id, status).await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    } else if chapter_read {
+        reading_changed = reading::start_reading(tx, id).await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    }
 
-    // If both chapter_number and website_domain were provided, refresh unread count
-    if let (Some(chapter_num), Some((source_id, domain, path))) = (chapter_number, source_info) {
-        let registry = StrategyRegistry::new();
-        if let Some(strategy) = registry.get(&domain) {
-            // Try to get chapters from cache first
-            let chapters = if let Some(cached) = state.cache.get(&domain, &path).await {
-                cached
-            } else {
-                // Fetch from website
-                let client = create_client();
-                match strategy.fetch_chapters(&client, &path, None).await {
-                    Ok(c) => {
-                        state.cache.set(&domain, &path, c.clone()).await;
-                        c
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to fetch chapters for unread refresh: {}", e);
-                        return Ok(Json(ApiResponse::success_null()));
+    Ok(AppliedUpdate { source_info, details_updated, chapter_read, reading_changed })
+}
+
+/// Caches the new covers, publishes the events of a committed update and
+/// carries a new reading position over to the unread counts
+pub async fn finish_update(state: &AppState, user_id: i64, id: i64, payload: &UpdateManga, applied: AppliedUpdate) {
+    let AppliedUpdate { source_info, details_updated, chapter_read, mut reading_changed } = applied;
+
+    if (payload.cover.is_some() || payload.cover_small.is_some())
+        && let Err(e) = cover::cache_covers(&state.pool, id).await
+    {
+        tracing::warn!("Failed to cache cover of manga {}: {}", id, e);
+    }
+
+    if details_updated {
+        state.events.publish(user_id, LibraryEvent::MangaUpdated { manga_id: id });
+    }
+
+    if let Some(ref chapter_num) = payload.chapter_number {
+        if chapter_read {
+            state.events.publish(user_id, LibraryEvent::ChapterRead { manga_id: id, chapter_number: chapter_num.clone() });
+        }
+
+        let previous_counts = unread_counts(&state.pool, id).await.unwrap_or_default();
+
+        // If website_domain was provided, refresh that source's chapter list first
+        if let Some((source_id, domain, path)) = source_info {
+            refresh_source_unread(state, source_id, &domain, &path, chapter_num).await;
+        }
+
+        // Then carry the reading position over to every other source
+        if let Err(e) = reconcile::reconcile_manga(&state.pool, id, chapter_num).await {
+            tracing::warn!("Failed to reconcile unread counts: {}", e);
+        }
+
+        for (source_id, count) in unread_counts(&state.pool, id).await.unwrap_or_default() {
+            let previous = previous_counts.iter().find(|(s, _)| *s == source_id).and_then(|(_, c)| *c);
+            if let Some(count) = count
+                && previous != Some(count)
+            {
+                state.events.publish(user_id, LibraryEvent::UnreadCountChanged {
+                    manga_id: id,
+                    source_id,
+                    number_unread_chapter: count,
+                });
+            }
+        }
+    }
+
+    // Left to the caller when they set the status themselves
+    if payload.reading_status.is_none() && (payload.chapter_number.is_some() || payload.status.is_some()) {
+        match complete_if_caught_up(&state.pool, id).await {
+            Ok(completed) => reading_changed |= completed,
+            Err(e) => tracing::warn!("Failed to check whether manga {} is completed: {}", id, e),
+        }
+    }
+
+    if reading_changed && !details_updated {
+        state.events.publish(user_id, LibraryEvent::MangaUpdated { manga_id: id });
+    }
+}
+
+async fn complete_if_caught_up(pool: &sqlx::SqlitePool, manga_id: i64) -> Result<bool, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    reading::complete_if_caught_up(&mut conn, manga_id).await
+}
+
+/// Unread count of every source of a manga
+async fn unread_counts(pool: &sqlx::SqlitePool, manga_id: i64) -> Result<Vec<(i64, Option<i64>)>, sqlx::Error> {
+    sqlx::query_as("SELECT id, number_unread_chapter FROM source WHERE manga_id = ?")
+        .bind(manga_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Refreshes the unread count of the source the chapter was read on, fetching
+/// its chapter list when it is not cached or does not contain the chapter yet
+async fn refresh_source_unread(state: &AppState, source_id: i64, domain: &str, path: &str, chapter_num: &str) {
+    let registry = StrategyRegistry::load_or_default(&state.pool).await;
+    if let Some(strategy) = registry.get(domain) {
+        // Try to get chapters from cache first
+        let chapters = if let Some(cached) = state.cache.get(domain, path).await {
+            cached
+        } else {
+            // Fetch from website
+            let client = create_client();
+            match strategy.fetch_chapters(&client, path, None).await {
+                Ok(c) => {
+                    state.cache.set(domain, path, c.clone()).await;
+                    if let Err(e) = remote_chapters::store_chapters(&state.pool, source_id, &c).await {
+                        tracing::warn!("Failed to store chapter list: {}", e);
                     }
+                    c
                 }
-            };
-
-            // Count new chapters
-            match strategy.count_new_chapters(&chapters, &chapter_num) {
-                Ok(count) => {
-                    if let Err(e) = sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
-                        .bind(count as i64)
-                        .bind(source_id)
-                        .execute(&state.pool)
-                        .await
-                    {
-                        tracing::warn!("Failed to update unread count: {}", e);
-                    }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch chapters for unread refresh: {}", e);
+                    return;
                 }
-                Err(_) => {
-                    // Chapter not found in cache, fetch fresh and retry
-                    let client = create_client();
-                    if let Ok(fresh_chapters) = strategy.fetch_chapters(&client, &path, None).await {
-                        state.cache.set(&domain, &path, fresh_chapters.clone()).await;
-                        if let Ok(count) = strategy.count_new_chapters(&fresh_chapters, &chapter_num) {
-                            let _ = sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
-                                .bind(count as i64)
-                                .bind(source_id)
-                                .execute(&state.pool)
-                                .await;
-                        }
+            }
+        };
+
+        // Count new chapters
+        match strategy.count_new_chapters(&chapters, chapter_num) {
+            Ok(count) => {
+                if let Err(e) = sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
+                    .bind(count as i64)
+                    .bind(source_id)
+                    .execute(&state.pool)
+                    .await
+                {
+                    tracing::warn!("Failed to update unread count: {}", e);
+                }
+            }
+            Err(_) => {
+                // Chapter not found in cache, fetch fresh and retry
+                let client = create_client();
+                if let Ok(fresh_chapters) = strategy.fetch_chapters(&client, path, None).await {
+                    state.cache.set(domain, path, fresh_chapters.clone()).await;
+                    if let Err(e) = remote_chapters::store_chapters(&state.pool, source_id, &fresh_chapters).await {
+                        tracing::warn!("Failed to store chapter list: {}", e);
+                    }
+                    if let Ok(count) = strategy.count_new_chapters(&fresh_chapters, chapter_num) {
+                        let _ = sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
+                            .bind(count as i64)
+                            .bind(source_id)
+                            .execute(&state.pool)
+                            .await;
                     }
                 }
             }
         }
     }
-
-    Ok(Json(ApiResponse::success_null()))
 }
 
 #[utoipa::path(
@@ -563,11 +1107,27 @@ pub async fn update_manga(
 )]
 pub async fn delete_manga(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path(id): Path<i64>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
-    let result = sqlx::query("DELETE FROM manga WHERE id = ?")
+    let mut conn = state.pool.acquire().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    remove_manga(&mut conn, context.user_id, id).await?;
+
+    if let Err(e) = cover::prune(&state.pool).await {
+        tracing::warn!("Failed to delete unused covers: {}", e);
+    }
+
+    state.events.publish(context.user_id, LibraryEvent::MangaDeleted { manga_id: id });
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+/// Deletes a manga of the user, with its sources and history
+pub async fn remove_manga(conn: &mut SqliteConnection, user_id: i64, id: i64) -> Result<(), ApiError> {
+    let result = sqlx::query("DELETE FROM manga WHERE id = ? AND user_id = ?")
         .bind(id)
-        .execute(&state.pool)
+        .bind(user_id)
+        .execute(conn)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
@@ -575,7 +1135,7 @@ pub async fn delete_manga(
         return Err(ApiError::NotFound("Manga not found".into()));
     }
 
-    Ok(Json(ApiResponse::success_null()))
+    Ok(())
 }
 
 #[utoipa::path(
@@ -595,6 +1155,7 @@ pub async fn delete_manga(
 )]
 pub async fn delete_manga_source(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
     Path((id, domain)): Path<(i64, String)>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
     let website = sqlx::query("SELECT id FROM website WHERE domain = ?")
@@ -608,9 +1169,13 @@ pub async fn delete_manga_source(
         None => return Err(ApiError::NotFound("Website domain not found".into())),
     };
 
-    let result = sqlx::query("DELETE FROM source WHERE manga_id = ? AND website_id = ?")
+    let result = sqlx::query(
+        "DELETE FROM source WHERE manga_id = ? AND website_id = ?
+        AND manga_id IN (SELECT id FROM manga WHERE user_id = ?)"
+    )
         .bind(id)
         .bind(website_id)
+        .bind(context.user_id)
         .execute(&state.pool)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -619,6 +1184,8 @@ pub async fn delete_manga_source(
         return Err(ApiError::NotFound("Source not found for this manga".into()));
     }
 
+    state.events.publish(context.user_id, LibraryEvent::MangaUpdated { manga_id: id });
+
     Ok(Json(ApiResponse::success_null()))
 }
 
@@ -643,7 +1210,7 @@ pub struct RefreshSummary {
     post,
     path = "/manga/refresh-unread",
     responses(
-        (status = 200, description = "Refresh all unread chapter counts", body = Object)
+        (status = 200, description = "Refresh the unread chapter counts of the user's library", body = Object)
     ),
     security(
         ("bearer_auth" = [])
@@ -651,186 +1218,49 @@ pub struct RefreshSummary {
 )]
 pub async fn refresh_all_unread(
     State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
 ) -> Result<Json<ApiResponse<RefreshSummary>>, ApiError> {
-    // Get all sources with their current chapter
-    let sources = sqlx::query(
-        r#"
-        SELECT
-            s.id as source_id,
-            s.manga_id,
-            m.name as manga_name,
-            w.domain,
-            s.path,
-            s.external_manga_id,
-            (
-                SELECT c.number
-                FROM chapter c
-                WHERE c.manga_id = s.manga_id
-                ORDER BY c.updated_at DESC
-                LIMIT 1
-            ) as current_chapter
-        FROM source s
-        JOIN manga m ON m.id = s.manga_id
-        JOIN website w ON w.id = s.website_id
-        "#
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| ApiError::Internal(e.to_string()))?;
-
-    let registry = StrategyRegistry::new();
-    let client = create_client();
-    let mut results = Vec::new();
-
-    for row in sources {
-        let source_id: i64 = row.get("source_id");
-        let manga_id: i64 = row.get("manga_id");
-        let manga_name: String = row.get("manga_name");
-        let domain: String = row.get("domain");
-        let path: String = row.get("path");
-        let external_manga_id: Option<String> = row.get("external_manga_id");
-        let current_chapter: Option<String> = row.get("current_chapter");
-
-        let strategy = match registry.get(&domain) {
-            Some(s) => s,
-            None => {
-                results.push(RefreshResult {
-                    manga_id,
-                    manga_name,
-                    domain,
-                    unread_count: None,
-                    error: Some("No strategy for this domain".to_string()),
-                });
-                continue;
-            }
-        };
-
-        // Extract external_id if not already stored
-        let external_manga_id = match external_manga_id {
-            Some(id) => Some(id),
-            None => {
-                match strategy.extract_external_id(&client, &path).await {
-                    Ok(Some(id)) => {
-                        // Save to database for future use
-                        if let Err(e) = sqlx::query("UPDATE source SET external_manga_id = ? WHERE id = ?")
-                            .bind(&id)
-                            .bind(source_id)
-                            .execute(&state.pool)
-                            .await
-                        {
-                            tracing::warn!(
-                                "Failed to save external_manga_id for source {}: {}",
-                                source_id,
-                                e
-                            );
-                        }
-                        Some(id)
-                    }
-                    Ok(None) => None,
-                    Err(e) => {
-                        results.push(RefreshResult {
-                            manga_id,
-                            manga_name,
-                            domain,
-                            unread_count: None,
-                            error: Some(format!("Failed to extract external ID: {}", e)),
-                        });
-                        continue;
-                    }
-                }
-            }
-        };
-
-        // Try to get chapters from cache
-        let mut chapters = state.cache.get(&domain, &path).await;
-
-        // If not cached, fetch from website
-        if chapters.is_none() {
-            match strategy.fetch_chapters(&client, &path, external_manga_id.as_deref()).await {
-                Ok(c) => {
-                    state.cache.set(&domain, &path, c.clone()).await;
-                    chapters = Some(c);
-                }
-                Err(e) => {
-                    results.push(RefreshResult {
-                        manga_id,
-                        manga_name,
-                        domain,
-                        unread_count: None,
-                        error: Some(format!("Failed to fetch chapters: {}", e)),
-                    });
-                    continue;
-                }
-            }
-        }
-
-        let chapters = chapters.unwrap();
-
-        // Count new chapters
-        // If no chapter has been read yet, all available chapters are considered unread
-        let count = match &current_chapter {
-            Some(current_chapter) => match strategy.count_new_chapters(&chapters, current_chapter) {
-                Ok(c) => c,
-                Err(_) => {
-                    // Chapter not found, try fetching fresh
-                    match strategy.fetch_chapters(&client, &path, external_manga_id.as_deref()).await {
-                        Ok(fresh) => {
-                            state.cache.set(&domain, &path, fresh.clone()).await;
-                            match strategy.count_new_chapters(&fresh, current_chapter) {
-                                Ok(c) => c,
-                                Err(e) => {
-                                    results.push(RefreshResult {
-                                        manga_id,
-                                        manga_name,
-                                        domain,
-                                        unread_count: None,
-                                        error: Some(format!("Chapter not found: {}", e)),
-                                    });
-                                    continue;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            results.push(RefreshResult {
-                                manga_id,
-                                manga_name,
-                                domain,
-                                unread_count: None,
-                                error: Some(format!("Failed to fetch fresh chapters: {}", e)),
-                            });
-                            continue;
-                        }
-                    }
-                }
-            },
-            None => chapters.len(),
-        };
+    let service = SyncService::new(state.pool.clone(), state.cache.clone(), state.events.clone(), state.limiter.clone()).await;
+    let (_, sync_results) = service
+        .run(SyncTrigger::Manual, SyncScope::User(context.user_id), Some(context.user_id))
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-        // Update the database
-        if let Err(e) = sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
-            .bind(count as i64)
-            .bind(source_id)
-            .execute(&state.pool)
-            .await
-        {
-            results.push(RefreshResult {
+    let mut results: Vec<RefreshResult> = sync_results
+        .into_iter()
+        .map(|r| RefreshResult {
+            manga_id: r.manga_id,
+            manga_name: r.manga_name,
+            domain: r.domain,
+            unread_count: r.error.is_none().then_some(r.new_chapters as i64),
+            error: r.error,
+        })
+        .collect();
+
+    // The sync leaves out sources on websites without a strategy, which are
+    // still reported here as errors
+    let domains = service.registry().supported_domains();
+    let unsupported = sqlx::query_as::<sqlx::Sqlite, (i64, String, String)>(
+        "SELECT m.id, m.name, w.domain
+        FROM source s JOIN manga m ON m.id = s.manga_id JOIN website w ON w.id = s.website_id
+        WHERE m.user_id = ? ORDER BY m.id, s.id"
+    )
+        .bind(context.user_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    results.extend(
+        unsupported
+            .into_iter()
+            .filter(|(_, _, domain)| !domains.contains(&domain.as_str()))
+            .map(|(manga_id, manga_name, domain)| RefreshResult {
                 manga_id,
                 manga_name,
                 domain,
                 unread_count: None,
-                error: Some(format!("Failed to update database: {}", e)),
-            });
-            continue;
-        }
-
-        results.push(RefreshResult {
-            manga_id,
-            manga_name,
-            domain,
-            unread_count: Some(count as i64),
-            error: None,
-        });
-    }
+                error: Some("No strategy for this domain".to_string()),
+            }),
+    );
 
     let success = results.iter().filter(|r| r.error.is_none()).count();
     let errors = results.iter().filter(|r| r.error.is_some()).count();
@@ -843,3 +1273,111 @@ pub async fn refresh_all_unread(
         results,
     })))
 }
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DuplicateQuery {
+    /// Lowest similarity, from 0 to 1, of two titles reported alike. Defaults to 0.85.
+    pub similarity: Option<f64>,
+    pub size: Option<i64>,
+    pub page: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/manga/duplicates",
+    params(DuplicateQuery),
+    responses(
+        (status = 200, description = "List pairs of the user's manga that look like the same series, most likely first", body = ApiResponse<Page<Duplicate>>),
+        (status = 400, description = "Invalid similarity or page size")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn find_duplicates(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<DuplicateQuery>,
+) -> Result<Json<ApiResponse<Page<Duplicate>>>, ApiError> {
+    let (size, _) = page_bounds(query.size, query.page)?;
+    let min_similarity = query.similarity.unwrap_or(duplicates::DEFAULT_SIMILARITY);
+    if !(0.0..=1.0).contains(&min_similarity) {
+        return Err(ApiError::BadRequest("similarity must be between 0 and 1".into()));
+    }
+
+    let mut conn = state.pool.acquire().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+    let duplicates = duplicates::find(&mut conn, context.user_id, min_similarity)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::from_all(duplicates, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/manga/{id}/merge/{other_id}",
+    responses(
+        (status = 200, description = "Merge a manga into another one and delete it", body = ApiResponse<MergeReport>),
+        (status = 400, description = "A manga cannot be merged into itself"),
+        (status = 404, description = "Manga not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "ID of the manga to keep"),
+        ("other_id" = i64, Path, description = "ID of the manga merged into it and deleted")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn merge_manga(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path((id, other_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<MergeReport>>, ApiError> {
+    if id == other_id {
+        return Err(ApiError::BadRequest("A manga cannot be merged into itself".into()));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM manga WHERE id IN (?, ?) AND user_id = ?")
+        .bind(id)
+        .bind(other_id)
+        .bind(context.user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    if found < 2 {
+        return Err(ApiError::NotFound("Manga not found".into()));
+    }
+
+    let report = duplicates::merge(&mut tx, id, other_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    tx.commit().await.map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    // The merged history may be ahead of the sources that were kept
+    let current_chapter: Option<String> = sqlx::query_scalar(
+        "SELECT number FROM chapter WHERE manga_id = ? ORDER BY updated_at DESC, id DESC LIMIT 1"
+    )
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    if let Some(current_chapter) = current_chapter
+        && let Err(e) = reconcile::reconcile_manga(&state.pool, id, &current_chapter).await
+    {
+        tracing::warn!("Failed to reconcile unread counts: {}", e);
+    }
+
+    if let Err(e) = cover::prune(&state.pool).await {
+        tracing::warn!("Failed to delete unused covers: {}", e);
+    }
+
+    state.events.publish(context.user_id, LibraryEvent::MangaDeleted { manga_id: other_id });
+    state.events.publish(context.user_id, LibraryEvent::MangaUpdated { manga_id: id });
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -3,3 +3,15 @@ pub mod website;
 pub mod setting;
 pub mod source;
 pub mod key;
+pub mod sync;
+pub mod webhook;
+pub mod feed;
+pub mod events;
+pub mod user;
+pub mod import;
+pub mod export;
+pub mod backup;
+pub mod cover;
+pub mod category;
+pub mod search;
+pub mod bulk;
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Query, State},
+    Extension,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use crate::auth::api_key::AuthContext;
+use crate::db::search::{self, HIGHLIGHT_END, HIGHLIGHT_START};
+use crate::state::AppState;
+use crate::utils::pagination::page_bounds;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+
+use utoipa::{ToSchema, IntoParams};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Words to find at the start of words of manga names or alternative titles
+    pub q: String,
+    pub size: Option<i64>,
+    pub page: Option<i64>,
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+pub struct SearchResult {
+    pub id: i64,
+    pub name: String,
+    pub cover: String,
+    /// Name with the matched words wrapped in `<mark>` and `</mark>`
+    pub name_highlight: String,
+    /// Alternative title that matched, highlighted like the name
+    pub alt_title: Option<String>,
+    /// Relevance, higher is better. Matches in the name weigh more than in
+    /// alternative titles.
+    pub score: f64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Search the user's manga by name and alternative titles, best matches first", body = ApiResponse<Page<SearchResult>>),
+        (status = 400, description = "Empty search or invalid page size")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn search_manga(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<ApiResponse<Page<SearchResult>>>, ApiError> {
+    let (size, offset) = page_bounds(query.size, query.page)?;
+    let terms = search::match_query(&query.q)
+        .ok_or_else(|| ApiError::BadRequest("q must contain a letter or a digit".into()))?;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM manga_search JOIN manga m ON m.id = manga_search.rowid
+        WHERE manga_search MATCH ? AND m.user_id = ?"
+    )
+        .bind(&terms)
+        .bind(context.user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let mut results = sqlx::query_as::<sqlx::Sqlite, SearchResult>(
+        "SELECT m.id, m.name, m.cover_small as cover,
+        highlight(manga_search, 0, ?1, ?2) as name_highlight,
+        highlight(manga_search, 1, ?1, ?2) as alt_title,
+        -bm25(manga_search, 10.0, 1.0) as score
+        FROM manga_search JOIN manga m ON m.id = manga_search.rowid
+        WHERE manga_search MATCH ?3 AND m.user_id = ?4
+        ORDER BY score DESC, m.id LIMIT ?5 OFFSET ?6"
+    )
+        .bind(HIGHLIGHT_START)
+        .bind(HIGHLIGHT_END)
+        .bind(&terms)
+        .bind(context.user_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    for result in &mut results {
+        result.alt_title = result.alt_title.as_deref().and_then(search::matched_alt_title);
+    }
+
+    Ok(Json(ApiResponse::success(Page::new(results, total, query.page.unwrap_or(1), size))))
+}
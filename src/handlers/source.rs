@@ -1,30 +1,54 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
+    Extension,
     Json,
 };
+use crate::auth::api_key::AuthContext;
 use crate::state::AppState;
-use crate::utils::response::{ApiResponse, ApiError};
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
 use crate::models::Source;
 
 #[utoipa::path(
     get,
     path = "/source",
     responses(
-        (status = 200, description = "List all sources", body = ApiResponse<Vec<Source>>)
+        (status = 200, description = "List the sources of the user's manga", body = ApiResponse<Page<Source>>),
+        (status = 400, description = "Invalid page size or cursor")
     ),
+    params(PageQuery),
     security(
         ("bearer_auth" = [])
     )
 )]
 pub async fn list_sources(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<Source>>>, ApiError> {
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<Source>>>, ApiError> {
+    // Ordered by id, so sources added while paging come after the cursor
+    let (size, offset, after) = query.keyset::<i64>()?;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM source s JOIN manga m ON m.id = s.manga_id WHERE m.user_id = ?"
+    )
+        .bind(context.user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
     let sources = sqlx::query_as::<sqlx::Sqlite, Source>(
-        "SELECT id, manga_id, website_id, path, external_manga_id, number_unread_chapter FROM source"
+        "SELECT s.id, s.manga_id, s.website_id, s.path, s.external_manga_id, s.number_unread_chapter
+        FROM source s JOIN manga m ON m.id = s.manga_id WHERE m.user_id = ? AND s.id > ?
+        ORDER BY s.id LIMIT ? OFFSET ?"
     )
+        .bind(context.user_id)
+        .bind(after.unwrap_or(0))
+        .bind(size + 1)
+        .bind(offset)
         .fetch_all(&state.pool)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(sources)))
+    Ok(Json(ApiResponse::success(Page::keyset(sources, total, &query, size, |source| source.id))))
 }
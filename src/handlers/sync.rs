@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    Json,
+};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use crate::auth::api_key::AuthContext;
+use crate::state::AppState;
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+use crate::models::{SyncRun, SyncResultRecord};
+use crate::sync::history::{self, SyncScope, SyncTrigger};
+use crate::sync::service::SyncService;
+
+use utoipa::ToSchema;
+
+/// Columns of a run as seen by the user of `u`, counting only the results of
+/// their own sources
+const SYNC_RUN_COLUMNS: &str = "r.id, r.triggered_by, r.scope, r.scope_id,
+    CASE WHEN r.finished_at IS NULL THEN 'running' ELSE 'finished' END as status,
+    r.started_at, r.finished_at, u.total_sources,
+    (SELECT COUNT(*) FROM sync_result x WHERE x.sync_run_id = r.id AND x.user_id = u.user_id) as completed_sources,
+    (SELECT COUNT(*) FROM sync_result x WHERE x.sync_run_id = r.id AND x.user_id = u.user_id AND x.error IS NULL) as success_count,
+    (SELECT COUNT(*) FROM sync_result x WHERE x.sync_run_id = r.id AND x.user_id = u.user_id AND x.error IS NOT NULL) as error_count,
+    (SELECT COALESCE(SUM(x.new_chapters), 0) FROM sync_result x WHERE x.sync_run_id = r.id AND x.user_id = u.user_id) as new_chapters";
+
+/// Runs shown to a user: the ones covering their sources or started by them
+const SYNC_RUN_TABLES: &str = "sync_run r JOIN sync_run_user u ON u.sync_run_id = r.id AND u.user_id = ?";
+
+#[derive(Serialize, ToSchema)]
+pub struct SyncJob {
+    pub run_id: i64,
+    pub total_sources: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SyncRunDetail {
+    #[serde(flatten)]
+    pub run: SyncRun,
+    pub results: Vec<SyncResultRecord>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/sync/runs",
+    params(PageQuery),
+    responses(
+        (status = 200, description = "List the sync runs covering the user's sources, most recent first", body = ApiResponse<Page<SyncRun>>),
+        (status = 400, description = "Invalid page size")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_sync_runs(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<SyncRun>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", SYNC_RUN_TABLES))
+        .bind(context.user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let runs = sqlx::query_as::<sqlx::Sqlite, SyncRun>(
+        &format!("SELECT {} FROM {} ORDER BY r.id DESC LIMIT ? OFFSET ?", SYNC_RUN_COLUMNS, SYNC_RUN_TABLES)
+    )
+        .bind(context.user_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(runs, total, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sync/runs/{id}",
+    responses(
+        (status = 200, description = "Get a sync run, its progress and the results of the user's sources", body = ApiResponse<SyncRunDetail>),
+        (status = 404, description = "Sync run not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Sync run ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_sync_run(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<SyncRunDetail>>, ApiError> {
+    let run = sqlx::query_as::<sqlx::Sqlite, SyncRun>(
+        &format!("SELECT {} FROM {} WHERE r.id = ?", SYNC_RUN_COLUMNS, SYNC_RUN_TABLES)
+    )
+        .bind(context.user_id)
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Sync run not found".into()))?;
+
+    let results = sqlx::query_as::<sqlx::Sqlite, SyncResultRecord>(
+        "SELECT id, source_id, manga_name, domain, new_chapters, error, duration_ms, created_at
+        FROM sync_result WHERE sync_run_id = ? AND user_id = ? ORDER BY id"
+    )
+        .bind(id)
+        .bind(context.user_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(SyncRunDetail { run, results })))
+}
+
+/// Held while checking for a running run and recording a new one, so that
+/// concurrent requests cannot both start the same run
+static STARTING_RUN: Mutex<()> = Mutex::const_new(());
+
+/// Records a manual run of `user_id` for `scope` and syncs it in the
+/// background. When the same run is still going, it is returned instead of
+/// syncing the same sources twice.
+async fn spawn_run(state: &AppState, user_id: i64, scope: SyncScope) -> Result<Json<ApiResponse<SyncJob>>, ApiError> {
+    let _starting = STARTING_RUN.lock().await;
+
+    let running = history::find_running(&state.pool, scope, user_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    if let Some((run_id, total_sources)) = running {
+        return Ok(Json(ApiResponse::success(SyncJob {
+            run_id,
+            total_sources: total_sources as usize,
+        })));
+    }
+
+    let service = SyncService::new(state.pool.clone(), state.cache.clone(), state.events.clone(), state.limiter.clone()).await;
+    let run = service
+        .start_run(SyncTrigger::Manual, scope, Some(user_id))
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let job = SyncJob {
+        run_id: run.id,
+        total_sources: run.total_sources(),
+    };
+
+    tokio::spawn(async move {
+        let run_id = run.id;
+        if let Err(e) = service.execute_run(run).await {
+            tracing::error!("Manual sync run #{} failed: {}", run_id, e);
+        }
+    });
+
+    Ok(Json(ApiResponse::success(job)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sync",
+    responses(
+        (status = 200, description = "Sync of every source of the user started, or the same sync already running", body = ApiResponse<SyncJob>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn sync_all(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+) -> Result<Json<ApiResponse<SyncJob>>, ApiError> {
+    spawn_run(&state, context.user_id, SyncScope::User(context.user_id)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/manga/{id}/sync",
+    responses(
+        (status = 200, description = "Sync of the manga's sources started, or the same sync already running", body = ApiResponse<SyncJob>),
+        (status = 404, description = "Manga not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Manga ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn sync_manga(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<SyncJob>>, ApiError> {
+    let manga = sqlx::query("SELECT id FROM manga WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(context.user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if manga.is_none() {
+        return Err(ApiError::NotFound("Manga not found".into()));
+    }
+
+    spawn_run(&state, context.user_id, SyncScope::Manga(id)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/source/{id}/sync",
+    responses(
+        (status = 200, description = "Sync of the source started, or the same sync already running", body = ApiResponse<SyncJob>),
+        (status = 404, description = "Source not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Source ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn sync_source(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<SyncJob>>, ApiError> {
+    let source = sqlx::query("SELECT s.id FROM source s JOIN manga m ON m.id = s.manga_id WHERE s.id = ? AND m.user_id = ?")
+        .bind(id)
+        .bind(context.user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if source.is_none() {
+        return Err(ApiError::NotFound("Source not found".into()));
+    }
+
+    spawn_run(&state, context.user_id, SyncScope::Source(id)).await
+}
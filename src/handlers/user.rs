@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+use crate::auth::api_key::DEFAULT_USER_ID;
+use crate::cover;
+use crate::models::User;
+use crate::state::AppState;
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateUser {
+    pub name: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/user",
+    responses(
+        (status = 200, description = "List users", body = ApiResponse<Page<User>>),
+        (status = 400, description = "Invalid page size")
+    ),
+    params(PageQuery),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_users(
+    State(state): State<AppState>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<User>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user")
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let users = sqlx::query_as::<sqlx::Sqlite, User>("SELECT id, name, created_at FROM user ORDER BY id LIMIT ? OFFSET ?")
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(users, total, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user",
+    request_body = CreateUser,
+    responses(
+        (status = 200, description = "User created successfully", body = ApiResponse<User>),
+        (status = 400, description = "Name missing or already taken")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_user(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateUser>,
+) -> Result<Json<ApiResponse<User>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("name is required".into()));
+    }
+
+    let id = sqlx::query("INSERT INTO user (name) VALUES (?)")
+        .bind(name)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                ApiError::BadRequest("User already exists".into())
+            } else {
+                ApiError::Internal(e.to_string())
+            }
+        })?
+        .last_insert_rowid();
+
+    let user = sqlx::query_as::<sqlx::Sqlite, User>("SELECT id, name, created_at FROM user WHERE id = ?")
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    info!("User '{}' created", user.name);
+
+    Ok(Json(ApiResponse::success(user)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/{id}",
+    responses(
+        (status = 200, description = "User deleted along with their library and keys", body = Object),
+        (status = 400, description = "The default user cannot be deleted"),
+        (status = 404, description = "User not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_user(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    // The legacy key.pub key authenticates as the default user
+    if id == DEFAULT_USER_ID {
+        return Err(ApiError::BadRequest("The default user cannot be deleted".into()));
+    }
+
+    let result = sqlx::query("DELETE FROM user WHERE id = ?")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("User not found".into()));
+    }
+
+    if let Err(e) = cover::prune(&state.pool).await {
+        tracing::warn!("Failed to delete unused covers: {}", e);
+    }
+
+    Ok(Json(ApiResponse::success_null()))
+}
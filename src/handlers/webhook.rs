@@ -0,0 +1,274 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    Json,
+};
+use serde::Deserialize;
+use crate::auth::api_key::AuthContext;
+use crate::state::AppState;
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
+use crate::models::{Webhook, WebhookDelivery};
+use crate::webhook::{self, WebhookDispatcher, WEBHOOK_COLUMNS};
+
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookPayload {
+    pub url: String,
+    /// Key used to sign the body, sent as `X-MangaSync-Signature: sha256=<hex HMAC>`
+    pub secret: Option<String>,
+    /// Comma-separated events to subscribe to, defaults to `new_chapters`
+    pub events: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Validates the payload and returns its normalized event list. The URL
+/// must resolve to public addresses unless `WEBHOOK_ALLOW_PRIVATE_HOSTS` is
+/// enabled; deliveries check it again since DNS records can change.
+async fn validate_payload(state: &AppState, payload: &WebhookPayload) -> Result<String, ApiError> {
+    if !payload.url.starts_with("http://") && !payload.url.starts_with("https://") {
+        return Err(ApiError::BadRequest("url must start with http:// or https://".into()));
+    }
+    let allow_private = webhook::allow_private_hosts(&state.pool).await;
+    webhook::resolve(&payload.url, allow_private)
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let events = payload.events.as_deref().unwrap_or(webhook::EVENT_NEW_CHAPTERS);
+    webhook::parse_events(events)
+        .map(|events| events.join(","))
+        .map_err(ApiError::BadRequest)
+}
+
+async fn fetch_webhook(state: &AppState, user_id: i64, id: i64) -> Result<Webhook, ApiError> {
+    sqlx::query_as::<sqlx::Sqlite, Webhook>(&format!("SELECT {} FROM webhook WHERE id = ? AND user_id = ?", WEBHOOK_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhook",
+    responses(
+        (status = 200, description = "List the user's webhooks", body = ApiResponse<Page<Webhook>>),
+        (status = 400, description = "Invalid page size")
+    ),
+    params(PageQuery),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<Webhook>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhook WHERE user_id = ?")
+        .bind(context.user_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let webhooks = sqlx::query_as::<sqlx::Sqlite, Webhook>(&format!(
+        "SELECT {} FROM webhook WHERE user_id = ? ORDER BY id LIMIT ? OFFSET ?",
+        WEBHOOK_COLUMNS
+    ))
+        .bind(context.user_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(webhooks, total, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook",
+    request_body = WebhookPayload,
+    responses(
+        (status = 200, description = "Webhook created successfully", body = ApiResponse<Webhook>),
+        (status = 400, description = "Invalid or non-public URL, or invalid event list")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Json(payload): Json<WebhookPayload>,
+) -> Result<Json<ApiResponse<Webhook>>, ApiError> {
+    let events = validate_payload(&state, &payload).await?;
+
+    let id = sqlx::query("INSERT INTO webhook (user_id, url, secret, events, enabled) VALUES (?, ?, ?, ?, ?)")
+        .bind(context.user_id)
+        .bind(&payload.url)
+        .bind(&payload.secret)
+        .bind(&events)
+        .bind(payload.enabled.unwrap_or(true))
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .last_insert_rowid();
+
+    Ok(Json(ApiResponse::success(fetch_webhook(&state, context.user_id, id).await?)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/webhook/{id}",
+    request_body = WebhookPayload,
+    responses(
+        (status = 200, description = "Webhook replaced successfully", body = ApiResponse<Webhook>),
+        (status = 400, description = "Invalid or non-public URL, or invalid event list"),
+        (status = 404, description = "Webhook not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Webhook ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+    Json(payload): Json<WebhookPayload>,
+) -> Result<Json<ApiResponse<Webhook>>, ApiError> {
+    let events = validate_payload(&state, &payload).await?;
+
+    let result = sqlx::query("UPDATE webhook SET url = ?, secret = ?, events = ?, enabled = ? WHERE id = ? AND user_id = ?")
+        .bind(&payload.url)
+        .bind(&payload.secret)
+        .bind(&events)
+        .bind(payload.enabled.unwrap_or(true))
+        .bind(id)
+        .bind(context.user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Webhook not found".into()));
+    }
+
+    Ok(Json(ApiResponse::success(fetch_webhook(&state, context.user_id, id).await?)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhook/{id}",
+    responses(
+        (status = 200, description = "Webhook and its delivery log deleted successfully", body = Object),
+        (status = 404, description = "Webhook not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Webhook ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let result = sqlx::query("DELETE FROM webhook WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(context.user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Webhook not found".into()));
+    }
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhook/{id}/deliveries",
+    params(
+        ("id" = i64, Path, description = "Webhook ID"),
+        PageQuery
+    ),
+    responses(
+        (status = 200, description = "List deliveries made to a webhook, most recent first", body = ApiResponse<Page<WebhookDelivery>>),
+        (status = 400, description = "Invalid page size"),
+        (status = 404, description = "Webhook not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<WebhookDelivery>>>, ApiError> {
+    fetch_webhook(&state, context.user_id, id).await?;
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhook_delivery WHERE webhook_id = ?")
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let deliveries = sqlx::query_as::<sqlx::Sqlite, WebhookDelivery>(
+        "SELECT id, webhook_id, event, payload, status_code, success, attempts, error, created_at, finished_at
+        FROM webhook_delivery WHERE webhook_id = ? ORDER BY id DESC LIMIT ? OFFSET ?"
+    )
+        .bind(id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(Page::new(deliveries, total, query.page.unwrap_or(1), size))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook/{id}/test",
+    responses(
+        (status = 200, description = "Send a ping event once, without retries, and return the logged delivery", body = ApiResponse<WebhookDelivery>),
+        (status = 404, description = "Webhook not found")
+    ),
+    params(
+        ("id" = i64, Path, description = "Webhook ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn test_webhook(
+    State(state): State<AppState>,
+    Extension(context): Extension<AuthContext>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<WebhookDelivery>>, ApiError> {
+    let webhook = fetch_webhook(&state, context.user_id, id).await?;
+
+    let delivery = WebhookDispatcher::new(state.pool.clone())
+        .await
+        .ping(&webhook)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(delivery)))
+}
@@ -1,11 +1,13 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use serde::Serialize;
 use crate::state::AppState;
-use crate::utils::response::{ApiResponse, ApiError};
+use crate::utils::pagination::PageQuery;
+use crate::utils::response::{ApiResponse, ApiError, Page};
 use crate::models::Website;
+use crate::sync::strategies::{GenericStrategy, ScraperConfig};
 
 use utoipa::ToSchema;
 
@@ -13,21 +15,36 @@ use utoipa::ToSchema;
     get,
     path = "/website",
     responses(
-        (status = 200, description = "List all websites", body = ApiResponse<Vec<Website>>)
+        (status = 200, description = "List all websites", body = ApiResponse<Page<Website>>),
+        (status = 400, description = "Invalid page size")
     ),
+    params(PageQuery),
     security(
         ("bearer_auth" = [])
     )
 )]
 pub async fn list_websites(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<Website>>>, ApiError> {
-    let websites = sqlx::query_as::<sqlx::Sqlite, Website>("SELECT id, domain FROM website")
+    Query(query): Query<PageQuery>,
+) -> Result<Json<ApiResponse<Page<Website>>>, ApiError> {
+    let (size, offset) = query.bounds()?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM website")
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let websites = sqlx::query_as::<sqlx::Sqlite, Website>(
+        "SELECT id, domain, base_url, chapter_url_template, chapter_selector, chapter_attribute, external_id_regex
+        FROM website ORDER BY domain LIMIT ? OFFSET ?"
+    )
+        .bind(size)
+        .bind(offset)
         .fetch_all(&state.pool)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(websites)))
+    Ok(Json(ApiResponse::success(Page::new(websites, total, query.page.unwrap_or(1), size))))
 }
 
 #[derive(Serialize, ToSchema)]
@@ -63,11 +80,24 @@ pub async fn check_website(
     })))
 }
 
+/// Rejects configs the generic strategy could not run with
+fn validate_config(domain: &str, config: &ScraperConfig) -> Result<(), ApiError> {
+    if config.is_empty() {
+        return Ok(());
+    }
+
+    GenericStrategy::new(domain, config)
+        .map(|_| ())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
 #[utoipa::path(
     post,
     path = "/website/{domain}",
+    request_body(content = Option<ScraperConfig>, description = "Optional scraping configuration"),
     responses(
-        (status = 200, description = "Website registered successfully", body = Object)
+        (status = 200, description = "Website registered successfully", body = Object),
+        (status = 400, description = "Website already exists or invalid scraping configuration")
     ),
     params(
         ("domain" = String, Path, description = "Website domain")
@@ -79,7 +109,11 @@ pub async fn check_website(
 pub async fn create_website(
     State(state): State<AppState>,
     Path(domain): Path<String>,
+    payload: Option<Json<ScraperConfig>>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let config = payload.map(|Json(c)| c).unwrap_or_default();
+    validate_config(&domain, &config)?;
+
     let existing = sqlx::query("SELECT id FROM website WHERE domain = ?")
         .bind(&domain)
         .fetch_optional(&state.pool)
@@ -90,12 +124,64 @@ pub async fn create_website(
         return Err(ApiError::BadRequest("Website already exists".into()));
     }
 
-    sqlx::query("INSERT INTO website (domain) VALUES (?)")
+    sqlx::query(
+        "INSERT INTO website (domain, base_url, chapter_url_template, chapter_selector, chapter_attribute, external_id_regex)
+        VALUES (?, ?, ?, ?, ?, ?)"
+    )
+        .bind(&domain)
+        .bind(&config.base_url)
+        .bind(&config.chapter_url_template)
+        .bind(&config.chapter_selector)
+        .bind(&config.chapter_attribute)
+        .bind(&config.external_id_regex)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success_null()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/website/{domain}",
+    request_body = ScraperConfig,
+    responses(
+        (status = 200, description = "Scraping configuration replaced successfully", body = Object),
+        (status = 400, description = "Invalid scraping configuration"),
+        (status = 404, description = "Website not found")
+    ),
+    params(
+        ("domain" = String, Path, description = "Website domain")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_website_config(
+    State(state): State<AppState>,
+    Path(domain): Path<String>,
+    Json(config): Json<ScraperConfig>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    validate_config(&domain, &config)?;
+
+    let result = sqlx::query(
+        "UPDATE website SET base_url = ?, chapter_url_template = ?, chapter_selector = ?, chapter_attribute = ?, external_id_regex = ?
+        WHERE domain = ?"
+    )
+        .bind(&config.base_url)
+        .bind(&config.chapter_url_template)
+        .bind(&config.chapter_selector)
+        .bind(&config.chapter_attribute)
+        .bind(&config.external_id_regex)
         .bind(&domain)
         .execute(&state.pool)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Website not found".into()));
+    }
+
     Ok(Json(ApiResponse::success_null()))
 }
 
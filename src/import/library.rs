@@ -0,0 +1,462 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use utoipa::ToSchema;
+
+use super::{add_to_category, ensure_category, ImportError};
+use crate::db::metadata::{self, MangaInfo};
+use crate::db::reading::{self, ReadingStatus};
+
+/// Version of the export format, bumped on incompatible changes
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A user's library, with the websites its sources use and the settings
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LibraryExport {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    #[serde(default)]
+    pub websites: Vec<ExportedWebsite>,
+    /// Only exported and imported with an admin key
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+    /// Names of the user's categories, in order
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub manga: Vec<ExportedManga>,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema, PartialEq)]
+pub struct ExportedWebsite {
+    pub domain: String,
+    pub base_url: Option<String>,
+    pub chapter_url_template: Option<String>,
+    pub chapter_selector: Option<String>,
+    pub chapter_attribute: Option<String>,
+    pub external_id_regex: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ExportedManga {
+    pub name: String,
+    pub cover: String,
+    pub cover_small: String,
+    #[serde(flatten)]
+    pub info: MangaInfo,
+    /// Missing from older exports, where a manga with a reading history is
+    /// considered being read
+    #[serde(default)]
+    pub reading_status: Option<ReadingStatus>,
+    #[serde(default)]
+    pub started_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub completed_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub sources: Vec<ExportedSource>,
+    /// Names of the categories the manga is in
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Reading history, oldest first
+    #[serde(default)]
+    pub chapters: Vec<ExportedChapter>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ExportedSource {
+    pub domain: String,
+    pub path: String,
+    pub external_manga_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema, PartialEq, Eq, Hash)]
+pub struct ExportedChapter {
+    pub number: String,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Exports a user's library, and the settings when `include_settings` is set
+pub async fn export_library(pool: &SqlitePool, user_id: i64, include_settings: bool) -> Result<LibraryExport, sqlx::Error> {
+    let manga_rows = sqlx::query("SELECT id, name, cover, cover_small, reading_status, started_at, completed_at FROM manga WHERE user_id = ? ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut sources: HashMap<i64, Vec<ExportedSource>> = HashMap::new();
+    for row in sqlx::query(
+        "SELECT s.manga_id, w.domain, s.path, s.external_manga_id FROM source s
+        JOIN website w ON w.id = s.website_id
+        JOIN manga m ON m.id = s.manga_id
+        WHERE m.user_id = ? ORDER BY w.domain"
+    )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?
+    {
+        sources.entry(row.get("manga_id")).or_default().push(ExportedSource {
+            domain: row.get("domain"),
+            path: row.get("path"),
+            external_manga_id: row.get("external_manga_id"),
+        });
+    }
+
+    let mut chapters: HashMap<i64, Vec<ExportedChapter>> = HashMap::new();
+    for row in sqlx::query(
+        "SELECT c.manga_id, c.number, c.updated_at FROM chapter c
+        JOIN manga m ON m.id = c.manga_id
+        WHERE m.user_id = ? ORDER BY c.updated_at, c.id"
+    )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?
+    {
+        chapters.entry(row.get("manga_id")).or_default().push(ExportedChapter {
+            number: row.get("number"),
+            updated_at: row.get("updated_at"),
+        });
+    }
+
+    let categories: Vec<String> = sqlx::query_scalar("SELECT name FROM category WHERE user_id = ? ORDER BY position, id")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut manga_categories: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in sqlx::query(
+        "SELECT mc.manga_id, c.name FROM manga_category mc
+        JOIN category c ON c.id = mc.category_id
+        WHERE c.user_id = ? ORDER BY c.position, c.id"
+    )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?
+    {
+        manga_categories.entry(row.get("manga_id")).or_default().push(row.get("name"));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let mut manga = Vec::with_capacity(manga_rows.len());
+    for row in manga_rows {
+        let id: i64 = row.get("id");
+        manga.push(ExportedManga {
+            name: row.get("name"),
+            cover: row.get("cover"),
+            cover_small: row.get("cover_small"),
+            info: metadata::load(&mut conn, id).await?,
+            reading_status: row.get("reading_status"),
+            started_at: row.get("started_at"),
+            completed_at: row.get("completed_at"),
+            sources: sources.remove(&id).unwrap_or_default(),
+            categories: manga_categories.remove(&id).unwrap_or_default(),
+            chapters: chapters.remove(&id).unwrap_or_default(),
+        });
+    }
+
+    let websites = sqlx::query_as::<_, ExportedWebsite>(
+        "SELECT domain, base_url, chapter_url_template, chapter_selector, chapter_attribute, external_id_regex
+        FROM website ORDER BY domain"
+    )
+        .fetch_all(pool)
+        .await?;
+
+    let settings = if include_settings {
+        sqlx::query("SELECT key, value FROM setting")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect()
+    } else {
+        BTreeMap::new()
+    };
+
+    Ok(LibraryExport {
+        version: FORMAT_VERSION,
+        exported_at: chrono::Utc::now().naive_utc(),
+        websites,
+        settings,
+        categories,
+        manga,
+    })
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Keep the library and existing values, add what is missing
+    #[default]
+    Merge,
+    /// Delete the library first, and overwrite website and setting values
+    /// when the key is `admin`
+    Replace,
+}
+
+/// Something in the document that was not imported as is
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportConflict {
+    /// `manga`, `source`, `website` or `setting`
+    pub kind: &'static str,
+    pub name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct LibraryImportReport {
+    pub manga_created: usize,
+    /// Manga already in the library that the document was merged into
+    pub manga_merged: usize,
+    pub sources_created: usize,
+    pub chapters_created: usize,
+    pub categories_created: usize,
+    pub websites_created: usize,
+    pub websites_updated: usize,
+    pub settings_updated: usize,
+    pub conflicts: Vec<ImportConflict>,
+    #[serde(skip)]
+    pub created: Vec<(i64, String)>,
+    #[serde(skip)]
+    pub merged: Vec<i64>,
+    #[serde(skip)]
+    pub deleted: Vec<i64>,
+}
+
+impl LibraryImportReport {
+    fn conflict(&mut self, kind: &'static str, name: impl Into<String>, message: &str) {
+        self.conflicts.push(ImportConflict { kind, name: name.into(), message: message.to_string() });
+    }
+}
+
+/// Restores an exported library into a user's library in one transaction.
+/// Websites and settings are shared by every user, so they are only created
+/// or overwritten when `admin` is set, and reported as conflicts otherwise.
+pub async fn import_library(
+    pool: &SqlitePool,
+    user_id: i64,
+    document: &LibraryExport,
+    mode: ImportMode,
+    admin: bool,
+) -> Result<LibraryImportReport, ImportError> {
+    if document.version == 0 || document.version > FORMAT_VERSION {
+        return Err(ImportError::InvalidFile(format!("unsupported export version {}", document.version)));
+    }
+
+    let mut report = LibraryImportReport::default();
+    let mut tx = pool.begin().await?;
+
+    for website in &document.websites {
+        let existing = sqlx::query_as::<_, ExportedWebsite>(
+            "SELECT domain, base_url, chapter_url_template, chapter_selector, chapter_attribute, external_id_regex
+            FROM website WHERE domain = ?"
+        )
+            .bind(&website.domain)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let query = match existing {
+            Some(existing) if existing == *website => continue,
+            None if !admin => {
+                report.conflict("website", &website.domain, "Creating a website requires an admin key, website skipped");
+                continue;
+            }
+            None => {
+                report.websites_created += 1;
+                "INSERT INTO website (base_url, chapter_url_template, chapter_selector, chapter_attribute, external_id_regex, domain)
+                VALUES (?, ?, ?, ?, ?, ?)"
+            }
+            Some(_) if mode == ImportMode::Replace && admin => {
+                report.websites_updated += 1;
+                "UPDATE website SET base_url = ?, chapter_url_template = ?, chapter_selector = ?, chapter_attribute = ?, external_id_regex = ?
+                WHERE domain = ?"
+            }
+            Some(_) => {
+                report.conflict("website", &website.domain, "Scraper configuration differs, kept the existing one");
+                continue;
+            }
+        };
+
+        sqlx::query(query)
+            .bind(&website.base_url)
+            .bind(&website.chapter_url_template)
+            .bind(&website.chapter_selector)
+            .bind(&website.chapter_attribute)
+            .bind(&website.external_id_regex)
+            .bind(&website.domain)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    if !admin && !document.settings.is_empty() {
+        report.conflict("setting", "*", "Settings require an admin key and were not imported");
+    }
+    for (key, value) in document.settings.iter().filter(|_| admin) {
+        let existing: Option<String> = sqlx::query_scalar("SELECT value FROM setting WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        match existing {
+            None => report.conflict("setting", key, "Unknown setting"),
+            Some(existing) if existing == *value => {}
+            Some(_) if mode == ImportMode::Replace => {
+                sqlx::query("UPDATE setting SET value = ? WHERE key = ?")
+                    .bind(value)
+                    .bind(key)
+                    .execute(&mut *tx)
+                    .await?;
+                report.settings_updated += 1;
+            }
+            Some(_) => report.conflict("setting", key, "Value differs, kept the existing one"),
+        }
+    }
+
+    // Listed first so that they keep their order, then as manga refer to them
+    let mut category_ids: HashMap<String, i64> = HashMap::new();
+    let names = document.categories.iter().chain(document.manga.iter().flat_map(|m| &m.categories));
+    for name in names {
+        let name = name.trim();
+        if name.is_empty() || category_ids.contains_key(&name.to_ascii_lowercase()) {
+            continue;
+        }
+        let (id, created) = ensure_category(&mut tx, user_id, name).await?;
+        if created {
+            report.categories_created += 1;
+        }
+        category_ids.insert(name.to_ascii_lowercase(), id);
+    }
+
+    if mode == ImportMode::Replace {
+        report.deleted = sqlx::query_scalar("SELECT id FROM manga WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM manga WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for manga in &document.manga {
+        let name = manga.name.trim();
+        if name.is_empty() {
+            report.conflict("manga", name, "Name is empty, manga skipped");
+            continue;
+        }
+
+        let existing = sqlx::query("SELECT id, cover, cover_small FROM manga WHERE user_id = ? AND name = ?")
+            .bind(user_id)
+            .bind(name)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let mut status_imported = false;
+        let manga_id = match existing {
+            Some(row) => {
+                if row.get::<String, _>("cover") != manga.cover || row.get::<String, _>("cover_small") != manga.cover_small {
+                    report.conflict("manga", name, "Cover differs, kept the existing one");
+                }
+                let id: i64 = row.get("id");
+                if metadata::fill_missing(&mut tx, id, &manga.info).await? {
+                    report.conflict("manga", name, "Metadata differs, kept the existing values");
+                }
+                report.manga_merged += 1;
+                report.merged.push(id);
+                id
+            }
+            None => {
+                let id = sqlx::query("INSERT INTO manga (user_id, name, cover, cover_small) VALUES (?, ?, ?, ?)")
+                    .bind(user_id)
+                    .bind(name)
+                    .bind(&manga.cover)
+                    .bind(&manga.cover_small)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid();
+                metadata::save(&mut tx, id, &manga.info).await?;
+                if let Some(status) = manga.reading_status {
+                    sqlx::query("UPDATE manga SET reading_status = ?, started_at = ?, completed_at = ? WHERE id = ?")
+                        .bind(status)
+                        .bind(manga.started_at)
+                        .bind(manga.completed_at)
+                        .bind(id)
+                        .execute(&mut *tx)
+                        .await?;
+                    status_imported = true;
+                }
+                report.manga_created += 1;
+                report.created.push((id, name.to_string()));
+                id
+            }
+        };
+
+        for source in &manga.sources {
+            let label = format!("{} ({})", name, source.domain);
+            let website_id: Option<i64> = sqlx::query_scalar("SELECT id FROM website WHERE domain = ?")
+                .bind(&source.domain)
+                .fetch_optional(&mut *tx)
+                .await?;
+            let Some(website_id) = website_id else {
+                report.conflict("source", label, "Website not found, source skipped");
+                continue;
+            };
+
+            let path = source.path.trim_end_matches('/');
+            let existing: Option<String> = sqlx::query_scalar("SELECT path FROM source WHERE manga_id = ? AND website_id = ?")
+                .bind(manga_id)
+                .bind(website_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            match existing {
+                Some(existing) if existing == path => {}
+                Some(_) => report.conflict("source", label, "Path differs, kept the existing one"),
+                None => {
+                    sqlx::query("INSERT INTO source (manga_id, website_id, path, external_manga_id) VALUES (?, ?, ?, ?)")
+                        .bind(manga_id)
+                        .bind(website_id)
+                        .bind(path)
+                        .bind(&source.external_manga_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    report.sources_created += 1;
+                }
+            }
+        }
+
+        for name in &manga.categories {
+            if let Some(category_id) = category_ids.get(&name.trim().to_ascii_lowercase()) {
+                add_to_category(&mut tx, manga_id, *category_id).await?;
+            }
+        }
+
+        let mut known: HashSet<ExportedChapter> = sqlx::query_as("SELECT number, updated_at FROM chapter WHERE manga_id = ?")
+            .bind(manga_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+        for chapter in &manga.chapters {
+            let chapter_key = ExportedChapter { number: chapter.number.clone(), updated_at: chapter.updated_at };
+            if !known.insert(chapter_key) {
+                continue;
+            }
+
+            sqlx::query("INSERT INTO chapter (manga_id, number, updated_at) VALUES (?, ?, ?)")
+                .bind(manga_id)
+                .bind(&chapter.number)
+                .bind(chapter.updated_at)
+                .execute(&mut *tx)
+                .await?;
+            report.chapters_created += 1;
+        }
+
+        if !status_imported {
+            reading::start_reading(&mut tx, manga_id).await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(report)
+}
@@ -0,0 +1,481 @@
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use utoipa::ToSchema;
+
+use super::protobuf::Reader;
+use super::{add_to_category, ensure_category, ImportError};
+use crate::db::reading;
+
+/// Contents of a Mihon (formerly Tachiyomi) `.tachibk` backup that we import
+#[derive(Debug, Default)]
+pub struct Backup {
+    pub manga: Vec<BackupManga>,
+    pub categories: Vec<BackupCategory>,
+    pub sources: Vec<BackupSource>,
+}
+
+#[derive(Debug)]
+pub struct BackupManga {
+    /// ID of the Mihon source (extension), see `Backup::sources`
+    pub source: i64,
+    /// URL relative to the source's website, or absolute for some sources
+    pub url: String,
+    pub title: String,
+    pub thumbnail_url: Option<String>,
+    /// Manga removed from the library are kept in backups for their history
+    pub favorite: bool,
+    pub chapters: Vec<BackupChapter>,
+    pub history: Vec<BackupHistory>,
+    /// `order` of the categories the manga is in, see `Backup::categories`
+    pub categories: Vec<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct BackupChapter {
+    pub url: String,
+    pub name: String,
+    pub read: bool,
+    /// Milliseconds since the epoch, 0 if unknown
+    pub date_fetch: i64,
+    /// Negative if the source could not parse it
+    pub chapter_number: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct BackupHistory {
+    pub url: String,
+    /// Milliseconds since the epoch
+    pub last_read: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct BackupCategory {
+    pub name: String,
+    /// Position of the category, which manga refer to it by
+    pub order: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct BackupSource {
+    pub name: String,
+    pub source_id: i64,
+}
+
+/// Largest decompressed backup accepted, so that a small gzip bomb cannot
+/// exhaust the memory
+pub const MAX_DECOMPRESSED_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Parses a backup file, gzip-compressed or not
+pub fn parse_backup(data: &[u8]) -> Result<Backup, ImportError> {
+    let decompressed;
+    let data = if data.starts_with(&[0x1f, 0x8b]) {
+        decompressed = decompress(data, MAX_DECOMPRESSED_SIZE)?;
+        &decompressed[..]
+    } else {
+        data
+    };
+
+    let mut backup = Backup::default();
+    let mut reader = Reader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => backup.manga.push(parse_manga(value.as_bytes())?),
+            2 => backup.categories.push(parse_category(value.as_bytes())?),
+            101 => backup.sources.push(parse_source(value.as_bytes())?),
+            _ => {}
+        }
+    }
+
+    Ok(backup)
+}
+
+/// Decompresses gzip data, rejecting it once it exceeds `limit` bytes
+fn decompress(data: &[u8], limit: u64) -> Result<Vec<u8>, ImportError> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(data)
+        .take(limit + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| ImportError::InvalidFile(e.to_string()))?;
+
+    if decompressed.len() as u64 > limit {
+        return Err(ImportError::InvalidFile(format!(
+            "decompressed backup is larger than {} MB",
+            limit / (1024 * 1024)
+        )));
+    }
+
+    Ok(decompressed)
+}
+
+fn parse_manga(data: &[u8]) -> Result<BackupManga, ImportError> {
+    let mut manga = BackupManga {
+        source: 0,
+        url: String::new(),
+        title: String::new(),
+        thumbnail_url: None,
+        favorite: true,
+        chapters: Vec::new(),
+        history: Vec::new(),
+        categories: Vec::new(),
+    };
+
+    let mut reader = Reader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => manga.source = value.as_i64(),
+            2 => manga.url = value.as_string(),
+            3 => manga.title = value.as_string(),
+            9 => manga.thumbnail_url = Some(value.as_string()),
+            16 => manga.chapters.push(parse_chapter(value.as_bytes())?),
+            17 => manga.categories.extend(value.as_i64s()?),
+            100 => manga.favorite = value.as_bool(),
+            104 => manga.history.push(parse_history(value.as_bytes())?),
+            _ => {}
+        }
+    }
+
+    Ok(manga)
+}
+
+fn parse_chapter(data: &[u8]) -> Result<BackupChapter, ImportError> {
+    let mut chapter = BackupChapter::default();
+    let mut reader = Reader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => chapter.url = value.as_string(),
+            2 => chapter.name = value.as_string(),
+            4 => chapter.read = value.as_bool(),
+            7 => chapter.date_fetch = value.as_i64(),
+            9 => chapter.chapter_number = value.as_f32(),
+            _ => {}
+        }
+    }
+    Ok(chapter)
+}
+
+fn parse_history(data: &[u8]) -> Result<BackupHistory, ImportError> {
+    let mut history = BackupHistory::default();
+    let mut reader = Reader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => history.url = value.as_string(),
+            2 => history.last_read = value.as_i64(),
+            _ => {}
+        }
+    }
+    Ok(history)
+}
+
+fn parse_category(data: &[u8]) -> Result<BackupCategory, ImportError> {
+    let mut category = BackupCategory::default();
+    let mut reader = Reader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => category.name = value.as_string(),
+            2 => category.order = value.as_i64(),
+            _ => {}
+        }
+    }
+    Ok(category)
+}
+
+fn parse_source(data: &[u8]) -> Result<BackupSource, ImportError> {
+    let mut source = BackupSource::default();
+    let mut reader = Reader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => source.name = value.as_string(),
+            2 => source.source_id = value.as_i64(),
+            _ => {}
+        }
+    }
+    Ok(source)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnmatchedSource {
+    pub manga: String,
+    /// Name of the Mihon source
+    pub source: String,
+    pub url: String,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub manga_created: usize,
+    /// Manga already in the library that got a new source or chapters
+    pub manga_updated: usize,
+    pub sources_created: usize,
+    pub chapters_created: usize,
+    pub categories_created: usize,
+    /// Manga whose source could not be mapped onto a website. They are
+    /// imported without a source.
+    pub unmatched: Vec<UnmatchedSource>,
+    #[serde(skip)]
+    pub created: Vec<(i64, String)>,
+    #[serde(skip)]
+    pub updated: Vec<i64>,
+}
+
+struct Website {
+    id: i64,
+    domain: String,
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Finds the website of a manga, from the host of its URL when absolute,
+/// otherwise from the name of its Mihon source ("MangaDex" matches
+/// "mangadex.org"). Returns the website ID and the source path.
+fn match_website(websites: &[Website], source_name: &str, url: &str) -> Option<(i64, String)> {
+    if let Ok(parsed) = reqwest::Url::parse(url)
+        && let Some(host) = parsed.host_str()
+    {
+        let host = host.trim_start_matches("www.");
+        let path = parsed.path().trim_end_matches('/').to_string();
+        return websites
+            .iter()
+            .find(|w| w.domain.trim_start_matches("www.") == host)
+            .map(|w| (w.id, path));
+    }
+
+    // Source names may carry a language suffix, e.g. "MangaDex (EN)"
+    let name = normalize(source_name.split(" (").next().unwrap_or(source_name));
+    if name.is_empty() {
+        return None;
+    }
+
+    let path = url.trim_end_matches('/').to_string();
+    websites
+        .iter()
+        .find(|w| {
+            let domain = w.domain.trim_start_matches("www.");
+            let label = domain.rsplit_once('.').map_or(domain, |(label, _)| label);
+            normalize(label) == name || normalize(domain) == name
+        })
+        .map(|w| (w.id, path))
+}
+
+/// Chapter number as stored in the reading history
+fn chapter_label(chapter: &BackupChapter) -> String {
+    if chapter.chapter_number >= 0.0 {
+        format!("chapter-{}", chapter.chapter_number)
+    } else {
+        chapter.url.trim_end_matches('/').rsplit('/').next().unwrap_or_default().to_string()
+    }
+}
+
+/// Imports the library of a backup into a user's library in one transaction.
+/// Manga already present (by name) are completed rather than duplicated, so
+/// importing the same backup twice changes nothing.
+pub async fn import_backup(pool: &SqlitePool, user_id: i64, backup: &Backup) -> Result<ImportReport, ImportError> {
+    let websites: Vec<Website> = sqlx::query("SELECT id, domain FROM website")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Website { id: row.get("id"), domain: row.get("domain") })
+        .collect();
+
+    let source_names: HashMap<i64, &str> = backup
+        .sources
+        .iter()
+        .map(|s| (s.source_id, s.name.as_str()))
+        .collect();
+
+    let mut report = ImportReport::default();
+    let mut tx = pool.begin().await?;
+
+    // Categories are created in the backup's order, and matched by name
+    let mut categories: Vec<&BackupCategory> = backup.categories.iter().filter(|c| !c.name.trim().is_empty()).collect();
+    categories.sort_by_key(|c| c.order);
+    let mut category_ids: HashMap<i64, i64> = HashMap::new();
+    for category in categories {
+        let (id, created) = ensure_category(&mut tx, user_id, category.name.trim()).await?;
+        if created {
+            report.categories_created += 1;
+        }
+        category_ids.insert(category.order, id);
+    }
+
+    for manga in backup.manga.iter().filter(|m| m.favorite) {
+        let name = manga.title.trim();
+        if name.is_empty() {
+            continue;
+        }
+
+        let existing = sqlx::query("SELECT id FROM manga WHERE user_id = ? AND name = ?")
+            .bind(user_id)
+            .bind(name)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let is_new = existing.is_none();
+        let manga_id = match existing {
+            Some(row) => row.get::<i64, _>("id"),
+            None => {
+                let cover = manga.thumbnail_url.clone().unwrap_or_default();
+                let id = sqlx::query("INSERT INTO manga (user_id, name, cover, cover_small) VALUES (?, ?, ?, ?)")
+                    .bind(user_id)
+                    .bind(name)
+                    .bind(&cover)
+                    .bind(&cover)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid();
+                report.manga_created += 1;
+                report.created.push((id, name.to_string()));
+                id
+            }
+        };
+        let mut changed = false;
+
+        let source_name = source_names
+            .get(&manga.source)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| manga.source.to_string());
+
+        match match_website(&websites, &source_name, &manga.url) {
+            Some((website_id, path)) => {
+                let result = sqlx::query("INSERT OR IGNORE INTO source (manga_id, website_id, path) VALUES (?, ?, ?)")
+                    .bind(manga_id)
+                    .bind(website_id)
+                    .bind(&path)
+                    .execute(&mut *tx)
+                    .await?;
+                if result.rows_affected() > 0 {
+                    report.sources_created += 1;
+                    changed = true;
+                }
+            }
+            None => report.unmatched.push(UnmatchedSource {
+                manga: name.to_string(),
+                source: source_name,
+                url: manga.url.clone(),
+            }),
+        }
+
+        let mut known: HashSet<String> = sqlx::query("SELECT number FROM chapter WHERE manga_id = ?")
+            .bind(manga_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|row| row.get("number"))
+            .collect();
+
+        let last_read: HashMap<&str, i64> = manga
+            .history
+            .iter()
+            .map(|h| (h.url.as_str(), h.last_read))
+            .collect();
+
+        // Oldest reads first so the most recent one becomes the current chapter.
+        // Chapters without a known date are stamped with the import time.
+        let mut read: Vec<(Option<i64>, &BackupChapter)> = manga
+            .chapters
+            .iter()
+            .filter(|c| c.read)
+            .map(|c| {
+                let read_at = last_read.get(c.url.as_str()).copied().filter(|t| *t > 0)
+                    .or((c.date_fetch > 0).then_some(c.date_fetch));
+                (read_at, c)
+            })
+            .collect();
+        read.sort_by(|a, b| {
+            a.0.unwrap_or(i64::MAX).cmp(&b.0.unwrap_or(i64::MAX))
+                .then(a.1.chapter_number.total_cmp(&b.1.chapter_number))
+        });
+
+        for (read_at, chapter) in read {
+            let label = chapter_label(chapter);
+            if label.is_empty() || !known.insert(label.clone()) {
+                continue;
+            }
+
+            sqlx::query(
+                "INSERT INTO chapter (manga_id, number, updated_at)
+                VALUES (?, ?, COALESCE(datetime(? / 1000, 'unixepoch'), CURRENT_TIMESTAMP))"
+            )
+                .bind(manga_id)
+                .bind(&label)
+                .bind(read_at)
+                .execute(&mut *tx)
+                .await?;
+            report.chapters_created += 1;
+            changed = true;
+        }
+
+        if changed {
+            reading::start_reading(&mut tx, manga_id).await?;
+        }
+
+        for order in &manga.categories {
+            if let Some(category_id) = category_ids.get(order)
+                && add_to_category(&mut tx, manga_id, *category_id).await?
+            {
+                changed = true;
+            }
+        }
+
+        if changed && !is_new {
+            report.manga_updated += 1;
+            report.updated.push(manga_id);
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn websites() -> Vec<Website> {
+        vec![
+            Website { id: 1, domain: "mangadex.org".to_string() },
+            Website { id: 2, domain: "www.mangaread.org".to_string() },
+        ]
+    }
+
+    #[test]
+    fn test_match_website() {
+        let websites = websites();
+        assert_eq!(match_website(&websites, "MangaDex", "/title/abc/"), Some((1, "/title/abc".to_string())));
+        assert_eq!(match_website(&websites, "Manga Read (EN)", "/manga/x"), Some((2, "/manga/x".to_string())));
+        assert_eq!(
+            match_website(&websites, "Anything", "https://www.mangaread.org/manga/x/"),
+            Some((2, "/manga/x".to_string()))
+        );
+        assert_eq!(match_website(&websites, "Comick", "/comic/x"), None);
+        assert_eq!(match_website(&websites, "", "/comic/x"), None);
+    }
+
+    #[test]
+    fn test_chapter_label() {
+        let mut chapter = BackupChapter { url: "/chapter/abc-123/".to_string(), chapter_number: 12.0, ..Default::default() };
+        assert_eq!(chapter_label(&chapter), "chapter-12");
+        chapter.chapter_number = 12.5;
+        assert_eq!(chapter_label(&chapter), "chapter-12.5");
+        chapter.chapter_number = -1.0;
+        assert_eq!(chapter_label(&chapter), "abc-123");
+    }
+
+    #[test]
+    fn test_decompress_is_limited() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &[0; 4096]).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decompress(&compressed, 4096).ok().map(|d| d.len()), Some(4096));
+        assert!(decompress(&compressed, 4095).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+pub mod library;
+pub mod mihon;
+mod protobuf;
+
+use sqlx::SqliteConnection;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ImportError {
+    InvalidFile(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::InvalidFile(msg) => write!(f, "Invalid backup file: {}", msg),
+            ImportError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<sqlx::Error> for ImportError {
+    fn from(e: sqlx::Error) -> Self {
+        ImportError::Database(e)
+    }
+}
+
+/// ID of the user's category with this name, created at the end of the list
+/// when missing. Also returns whether it was created.
+async fn ensure_category(conn: &mut SqliteConnection, user_id: i64, name: &str) -> Result<(i64, bool), sqlx::Error> {
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM category WHERE user_id = ? AND name = ?")
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(id) = existing {
+        return Ok((id, false));
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO category (user_id, name, position)
+        SELECT ?, ?, COALESCE(MAX(position) + 1, 0) FROM category WHERE user_id = ?"
+    )
+        .bind(user_id)
+        .bind(name)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+    Ok((id, true))
+}
+
+/// Adds a manga to a category, returning whether it was not in it yet
+async fn add_to_category(conn: &mut SqliteConnection, manga_id: i64, category_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("INSERT OR IGNORE INTO manga_category (manga_id, category_id) VALUES (?, ?)")
+        .bind(manga_id)
+        .bind(category_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
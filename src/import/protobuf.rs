@@ -0,0 +1,158 @@
+use super::ImportError;
+
+pub enum Value<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> Value<'a> {
+    pub fn as_u64(&self) -> u64 {
+        match self {
+            Value::Varint(v) | Value::Fixed64(v) => *v,
+            Value::Fixed32(v) => *v as u64,
+            Value::Bytes(_) => 0,
+        }
+    }
+
+    pub fn as_i64(&self) -> i64 {
+        self.as_u64() as i64
+    }
+
+    pub fn as_bool(&self) -> bool {
+        self.as_u64() != 0
+    }
+
+    pub fn as_f32(&self) -> f32 {
+        match self {
+            Value::Fixed32(v) => f32::from_bits(*v),
+            _ => 0.0,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            Value::Bytes(b) => b,
+            _ => &[],
+        }
+    }
+
+    pub fn as_string(&self) -> String {
+        String::from_utf8_lossy(self.as_bytes()).into_owned()
+    }
+
+    /// Values of a repeated integer field, which are either packed in one
+    /// length-delimited field or sent as one field each
+    pub fn as_i64s(&self) -> Result<Vec<i64>, ImportError> {
+        let Value::Bytes(bytes) = self else {
+            return Ok(vec![self.as_i64()]);
+        };
+
+        let mut reader = Reader::new(bytes);
+        let mut values = Vec::new();
+        while reader.pos < reader.buf.len() {
+            values.push(reader.varint()? as i64);
+        }
+        Ok(values)
+    }
+}
+
+/// Minimal reader for the protobuf wire format, enough to walk the fields of
+/// a message without generated code
+pub struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, ImportError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos).ok_or_else(truncated)?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ImportError::InvalidFile("varint is too long".into()))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ImportError> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.buf.len()).ok_or_else(truncated)?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Next field number and value, `None` at the end of the message
+    pub fn next_field(&mut self) -> Result<Option<(u32, Value<'a>)>, ImportError> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+
+        let key = self.varint()?;
+        let field = (key >> 3) as u32;
+        let value = match key & 0x7 {
+            0 => Value::Varint(self.varint()?),
+            1 => Value::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+            2 => {
+                let len = self.varint()? as usize;
+                Value::Bytes(self.take(len)?)
+            }
+            5 => Value::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+            wire_type => return Err(ImportError::InvalidFile(format!("unsupported wire type {}", wire_type))),
+        };
+
+        Ok(Some((field, value)))
+    }
+}
+
+fn truncated() -> ImportError {
+    ImportError::InvalidFile("message is truncated".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_fields() {
+        // 1: 150, 2: "hi", 3: 1.5f32, 4: -1
+        let mut buf = vec![0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i', 0x1d];
+        buf.extend_from_slice(&1.5f32.to_le_bytes());
+        buf.extend_from_slice(&[0x20, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+
+        let mut reader = Reader::new(&buf);
+        let (field, value) = reader.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_u64()), (1, 150));
+        let (field, value) = reader.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_string()), (2, "hi".to_string()));
+        let (field, value) = reader.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_f32()), (3, 1.5));
+        let (field, value) = reader.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_i64()), (4, -1));
+        assert_eq!(value.as_i64s().ok(), Some(vec![-1]));
+        assert!(reader.next_field().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_packed_values() {
+        // 17: [1, 300] packed
+        let buf = [0x8a, 0x01, 0x03, 0x01, 0xac, 0x02];
+        let mut reader = Reader::new(&buf);
+        let (field, value) = reader.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_i64s().ok()), (17, Some(vec![1, 300])));
+    }
+
+    #[test]
+    fn test_truncated_message() {
+        let mut reader = Reader::new(&[0x12, 0x05, b'h']);
+        assert!(reader.next_field().is_err());
+    }
+}
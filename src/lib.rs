@@ -1,10 +1,15 @@
 pub mod auth;
+pub mod backup;
 pub mod cache;
+pub mod cover;
 pub mod db;
+pub mod events;
 pub mod handlers;
+pub mod import;
 pub mod models;
 pub mod settings;
 pub mod state;
 pub mod sync;
 pub mod utils;
+pub mod webhook;
 pub mod openapi;
@@ -1,5 +1,6 @@
 use axum::{
-    routing::{get, post, patch, delete},
+    extract::DefaultBodyLimit,
+    routing::{get, post, put, patch, delete},
     Router,
     middleware,
 };
@@ -8,11 +9,15 @@ use tower_http::trace::TraceLayer;
 use std::sync::Arc;
 use tokio::signal;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use manga_sync::auth::api_key::DEFAULT_USER_ID;
 use manga_sync::auth::key_manager::KeyManager;
 use manga_sync::auth::middleware::auth_middleware;
 use manga_sync::cache::ChapterCache;
+use manga_sync::events::EventBus;
 use manga_sync::state::AppState;
-use manga_sync::{db, handlers, sync, settings};
+use manga_sync::sync::rate_limit::DomainRateLimiter;
+use manga_sync::import::mihon;
+use manga_sync::{backup, db, handlers, sync, settings};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -27,12 +32,24 @@ async fn main() -> anyhow::Result<()> {
     std::fs::create_dir_all(secret_dir)?;
 
     // Initialize the database first so we can read settings
-    let pool = db::init_db(&format!("sqlite:{}/manga.db", secret_dir)).await?;
+    let db_path = format!("{}/manga.db", secret_dir);
+    if backup::restore_pending(std::path::Path::new(&db_path)).await? {
+        tracing::warn!("Database restored from {}/{}", secret_dir, backup::RESTORE_FILE);
+    }
+
+    let pool = db::init_db(&format!("sqlite:{}", db_path)).await?;
+
+    // `manga-sync import-mihon <backup.tachibk> [user_id]` imports a backup and exits
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("import-mihon") {
+        return import_mihon(&pool, &args[2..]).await;
+    }
 
     // Load settings from the database
     let ttl_warning = settings::get_setting_u64(&pool, "TTL_KEY_WARNING", 90).await?;
     let ttl_limit = settings::get_setting_u64(&pool, "TTL_KEY_LIMIT", 365).await?;
     let cron_sync = settings::get_setting_string(&pool, "CRON_SYNC", "0 0 0 * * *").await?;
+    let cron_backup = settings::get_setting_string(&pool, "CRON_BACKUP", "0 0 3 * * *").await?;
 
     let key_manager = Arc::new(KeyManager::new(
         &format!("{}/key.pub", secret_dir),
@@ -40,13 +57,26 @@ async fn main() -> anyhow::Result<()> {
         ttl_limit,
     )?);
     let cache = Arc::new(ChapterCache::new());
+    let events = EventBus::new();
 
-    let mut scheduler = sync::scheduler::start_scheduler(pool.clone(), cache.clone(), &cron_sync).await?;
+    let interrupted = sync::history::close_interrupted_runs(&pool).await?;
+    if interrupted > 0 {
+        tracing::warn!("Closed {} sync run(s) interrupted by a previous shutdown", interrupted);
+    }
+
+    let limiter = Arc::new(DomainRateLimiter::from_settings(&pool).await);
+
+    let mut scheduler = sync::scheduler::start_scheduler(pool.clone(), cache.clone(), events.clone(), limiter.clone(), &cron_sync).await?;
+    if !cron_backup.is_empty() {
+        backup::add_backup_job(&scheduler, pool.clone(), &cron_backup).await?;
+    }
 
     let state = AppState {
         pool: pool.clone(),
         cache,
         key_manager: key_manager.clone(),
+        events,
+        limiter,
     };
 
     let app = Router::new()
@@ -54,20 +84,51 @@ async fn main() -> anyhow::Result<()> {
         .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga).delete(handlers::manga::delete_manga))
         .route("/manga/{id}/source", get(handlers::manga::get_manga_sources).post(handlers::manga::create_manga_source))
         .route("/manga/{id}/source/{domain}", delete(handlers::manga::delete_manga_source))
+        .route("/manga/{id}/source/{domain}/chapters", get(handlers::manga::get_manga_source_chapters))
         .route("/manga/{id}/history", get(handlers::manga::get_manga_history))
+        .route("/manga/{id}/cover", get(handlers::cover::get_manga_cover))
+        .route("/manga/{id}/cover/small", get(handlers::cover::get_manga_cover_small))
+        .route("/manga/{id}/sync", post(handlers::sync::sync_manga))
         .route("/manga/refresh-unread", post(handlers::manga::refresh_all_unread))
+        .route("/manga/from-url", post(handlers::manga::create_manga_from_url))
+        .route("/manga/duplicates", get(handlers::manga::find_duplicates))
+        .route("/manga/bulk", post(handlers::bulk::bulk_manga))
+        .route("/manga/{id}/merge/{other_id}", post(handlers::manga::merge_manga))
+        .route("/search", get(handlers::search::search_manga))
+        .route("/category", get(handlers::category::list_categories).post(handlers::category::create_category))
+        .route("/category/{id}", patch(handlers::category::update_category).delete(handlers::category::delete_category))
+        .route("/category/{id}/manga/{manga_id}", put(handlers::category::add_category_manga).delete(handlers::category::remove_category_manga))
         .route("/website", get(handlers::website::list_websites))
-        .route("/website/{domain}", get(handlers::website::check_website).post(handlers::website::create_website).delete(handlers::website::delete_website))
+        .route("/website/{domain}", get(handlers::website::check_website).post(handlers::website::create_website).put(handlers::website::update_website_config).delete(handlers::website::delete_website))
         .route("/source", get(handlers::source::list_sources))
+        .route("/source/{id}/sync", post(handlers::sync::sync_source))
+        .route("/sync", post(handlers::sync::sync_all))
+        .route("/sync/runs", get(handlers::sync::list_sync_runs))
+        .route("/sync/runs/{id}", get(handlers::sync::get_sync_run))
+        .route("/events", get(handlers::events::stream_events))
+        .route("/feed.atom", get(handlers::feed::atom_feed))
+        .route("/feed.rss", get(handlers::feed::rss_feed))
+        .route("/backup", get(handlers::backup::list_backups).post(handlers::backup::create_backup))
+        .route("/export", get(handlers::export::export_library))
+        .route("/import", post(handlers::import::import_library).layer(DefaultBodyLimit::max(handlers::import::MAX_BACKUP_SIZE)))
+        .route("/import/mihon", post(handlers::import::import_mihon).layer(DefaultBodyLimit::max(handlers::import::MAX_BACKUP_SIZE)))
+        .route("/webhook", get(handlers::webhook::list_webhooks).post(handlers::webhook::create_webhook))
+        .route("/webhook/{id}", put(handlers::webhook::update_webhook).delete(handlers::webhook::delete_webhook))
+        .route("/webhook/{id}/deliveries", get(handlers::webhook::list_webhook_deliveries))
+        .route("/webhook/{id}/test", post(handlers::webhook::test_webhook))
         .route("/setting", get(handlers::setting::list_settings))
         .route("/setting/{key}", patch(handlers::setting::update_setting))
         .route("/key", get(handlers::key::get_key_age).post(handlers::key::refresh_key))
+        .route("/key/api", get(handlers::key::list_api_keys).post(handlers::key::create_api_key))
+        .route("/key/api/{id}", delete(handlers::key::revoke_api_key))
+        .route("/user", get(handlers::user::list_users).post(handlers::user::create_user))
+        .route("/user/{id}", delete(handlers::user::delete_user))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(tower_http::trace::DefaultMakeSpan::new().level(tracing::Level::INFO))
                 .on_response(tower_http::trace::DefaultOnResponse::new().level(tracing::Level::INFO))
         )
-        .layer(middleware::from_fn_with_state(key_manager.clone(), auth_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
         .with_state(state);
 
     let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 7783));
@@ -110,6 +171,19 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn import_mihon(pool: &sqlx::SqlitePool, args: &[String]) -> anyhow::Result<()> {
+    let path = args
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Usage: manga-sync import-mihon <backup.tachibk> [user_id]"))?;
+    let user_id = args.get(1).map(|id| id.parse::<i64>()).transpose()?.unwrap_or(DEFAULT_USER_ID);
+
+    let backup = mihon::parse_backup(&std::fs::read(path)?)?;
+    let report = mihon::import_backup(pool, user_id, &backup).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -14,6 +14,11 @@ pub struct Manga {
 pub struct Website {
     pub id: i64,
     pub domain: String,
+    pub base_url: Option<String>,
+    pub chapter_url_template: Option<String>,
+    pub chapter_selector: Option<String>,
+    pub chapter_attribute: Option<String>,
+    pub external_id_regex: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
@@ -34,8 +39,106 @@ pub struct Chapter {
     pub updated_at: NaiveDateTime,
 }
 
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct RemoteChapter {
+    pub id: i64,
+    pub source_id: i64,
+    pub href: String,
+    pub volume: Option<i64>,
+    pub number: Option<f64>,
+    pub part: Option<String>,
+    pub title: Option<String>,
+    pub position: i64,
+    pub first_seen_at: NaiveDateTime,
+}
+
 #[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct Setting {
     pub key: String,
     pub value: String,
 }
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct SyncRun {
+    pub id: i64,
+    pub triggered_by: String,
+    pub scope: String,
+    pub scope_id: Option<i64>,
+    /// `running` until every source has been synced, then `finished`
+    pub status: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub total_sources: i64,
+    pub completed_sources: i64,
+    pub success_count: i64,
+    pub error_count: i64,
+    pub new_chapters: i64,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct SyncResultRecord {
+    pub id: i64,
+    pub source_id: Option<i64>,
+    pub manga_name: String,
+    pub domain: String,
+    pub new_chapters: i64,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+    pub has_secret: bool,
+    /// Comma-separated event names the webhook subscribes to
+    pub events: String,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: String,
+    pub status_code: Option<i64>,
+    pub success: bool,
+    pub attempts: i64,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ApiKey {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// `read`, `write` or `admin`
+    pub scope: String,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    /// Place of the category in the user's list, starting at 0
+    pub position: i64,
+    pub manga_count: i64,
+    pub created_at: NaiveDateTime,
+}
@@ -1,6 +1,7 @@
 use utoipa::{OpenApi, Modify, openapi::security::{SecurityScheme, HttpAuthScheme, HttpBuilder}};
 use crate::handlers;
 use crate::models;
+use crate::utils;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -16,17 +17,58 @@ use crate::models;
         handlers::manga::list_manga,
         handlers::manga::get_manga,
         handlers::manga::get_manga_sources,
+        handlers::manga::get_manga_source_chapters,
         handlers::manga::get_manga_history,
+        handlers::cover::get_manga_cover,
+        handlers::cover::get_manga_cover_small,
         handlers::manga::create_manga,
+        handlers::manga::create_manga_from_url,
+        handlers::manga::find_duplicates,
+        handlers::manga::merge_manga,
+        handlers::bulk::bulk_manga,
         handlers::manga::update_manga,
         handlers::manga::delete_manga,
         handlers::manga::delete_manga_source,
+        handlers::search::search_manga,
+        handlers::category::list_categories,
+        handlers::category::create_category,
+        handlers::category::update_category,
+        handlers::category::delete_category,
+        handlers::category::add_category_manga,
+        handlers::category::remove_category_manga,
         handlers::website::list_websites,
         handlers::website::check_website,
         handlers::website::create_website,
+        handlers::website::update_website_config,
         handlers::website::delete_website,
         handlers::source::list_sources,
+        handlers::manga::refresh_all_unread,
+        handlers::sync::sync_all,
+        handlers::sync::sync_manga,
+        handlers::sync::sync_source,
+        handlers::sync::list_sync_runs,
+        handlers::sync::get_sync_run,
+        handlers::events::stream_events,
+        handlers::feed::atom_feed,
+        handlers::feed::rss_feed,
+        handlers::import::import_mihon,
+        handlers::import::import_library,
+        handlers::export::export_library,
+        handlers::backup::create_backup,
+        handlers::backup::list_backups,
+        handlers::webhook::list_webhooks,
+        handlers::webhook::create_webhook,
+        handlers::webhook::update_webhook,
+        handlers::webhook::delete_webhook,
+        handlers::webhook::list_webhook_deliveries,
+        handlers::webhook::test_webhook,
         handlers::setting::list_settings,
+        handlers::key::list_api_keys,
+        handlers::key::create_api_key,
+        handlers::key::revoke_api_key,
+        handlers::user::list_users,
+        handlers::user::create_user,
+        handlers::user::delete_user,
         handlers::setting::update_setting,
     ),
     components(
@@ -35,15 +77,63 @@ use crate::models;
             models::Website,
             models::Source,
             models::Chapter,
+            models::RemoteChapter,
             models::Setting,
+            models::SyncRun,
+            models::SyncResultRecord,
+            models::Webhook,
+            models::ApiKey,
+            models::User,
+            models::WebhookDelivery,
+            models::Category,
             handlers::manga::Pagination,
+            utils::pagination::PageQuery,
             handlers::manga::MangaListItem,
             handlers::manga::MangaDetail,
             handlers::manga::MangaSource,
             handlers::manga::HistoryItem,
             handlers::manga::CreateManga,
             handlers::manga::UpdateManga,
+            handlers::search::SearchResult,
+            handlers::bulk::BulkRequest,
+            handlers::bulk::BulkOperation,
+            handlers::bulk::BulkItemResult,
+            handlers::bulk::BulkReport,
+            handlers::category::CreateCategory,
+            handlers::category::UpdateCategory,
             handlers::website::Existence,
+            handlers::sync::SyncRunDetail,
+            handlers::sync::SyncJob,
+            handlers::webhook::WebhookPayload,
+            crate::import::mihon::ImportReport,
+            crate::import::mihon::UnmatchedSource,
+            crate::import::library::LibraryExport,
+            crate::import::library::ExportedWebsite,
+            crate::import::library::ExportedManga,
+            crate::import::library::ExportedSource,
+            crate::import::library::ExportedChapter,
+            crate::import::library::ImportMode,
+            crate::import::library::ImportConflict,
+            crate::import::library::LibraryImportReport,
+            crate::backup::BackupFile,
+            handlers::feed::FeedQuery,
+            handlers::key::CreateApiKey,
+            handlers::key::CreatedApiKey,
+            crate::auth::api_key::KeyScope,
+            handlers::user::CreateUser,
+            handlers::manga::RefreshResult,
+            handlers::manga::RefreshSummary,
+            handlers::manga::CreateMangaFromUrl,
+            handlers::manga::CreatedManga,
+            crate::sync::metadata::MangaMetadata,
+            crate::sync::metadata::PublicationStatus,
+            crate::db::metadata::MangaInfo,
+            crate::db::reading::ReadingStatus,
+            crate::db::duplicates::Duplicate,
+            crate::db::duplicates::DuplicateManga,
+            crate::db::duplicates::DuplicateReason,
+            crate::db::duplicates::MergeReport,
+            crate::sync::strategies::ScraperConfig,
         )
     ),
     modifiers(&SecurityAddon)
@@ -3,10 +3,15 @@ use std::sync::Arc;
 
 use crate::auth::key_manager::KeyManager;
 use crate::cache::ChapterCache;
+use crate::events::EventBus;
+use crate::sync::rate_limit::DomainRateLimiter;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: SqlitePool,
     pub cache: Arc<ChapterCache>,
     pub key_manager: Arc<KeyManager>,
+    pub events: EventBus,
+    /// Shared by every sync, so concurrent runs respect the same per-domain limits
+    pub limiter: Arc<DomainRateLimiter>,
 }
@@ -0,0 +1,209 @@
+use regex::Regex;
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+static VOLUME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^a-z])vol(?:ume)?[-_. ]*(\d+)").unwrap()
+});
+
+static KEYWORD_CHAPTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[^a-z])(?:chapter|chap|ch|episode|ep)[-_. ]*(\d+)(?:[.,_-](\d+))?([a-z])?(?:[-_. ]*(?:part|pt)[-_. ]*(\d+))?",
+    )
+    .unwrap()
+});
+
+static BARE_CHAPTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d+)(?:[.,](\d+))?([a-z])?(?:[-_. ]*(?:part|pt)[-_. ]*(\d+))?$").unwrap()
+});
+
+static LAST_NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)(?:\.(\d+))?\D*$").unwrap());
+
+/// Chapter identifier parsed from a chapter href or from user input, e.g.
+/// `/manga/test/vol-2-chapter-12-5/`, `chapter-10-part-2`, `12.5` or `12a`.
+///
+/// Chapters are ordered by number then part; the volume is informative only
+/// since sites do not agree on whether to include it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterNumber {
+    pub volume: Option<i64>,
+    pub number: f64,
+    pub part: Option<String>,
+}
+
+impl ChapterNumber {
+    pub fn parse(input: &str) -> Option<Self> {
+        let segment = input
+            .trim()
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or(input)
+            .to_lowercase();
+
+        let volume = VOLUME
+            .captures(&segment)
+            .and_then(|c| c[1].parse().ok());
+        let without_volume = VOLUME.replace(&segment, "");
+
+        let captures = KEYWORD_CHAPTER
+            .captures(&without_volume)
+            .or_else(|| BARE_CHAPTER.captures(&without_volume));
+
+        let (number, part) = match captures {
+            Some(c) => {
+                let number = match c.get(2) {
+                    Some(decimal) => format!("{}.{}", &c[1], decimal.as_str()),
+                    None => c[1].to_string(),
+                };
+                let part = c.get(4).or_else(|| c.get(3)).map(|p| p.as_str().to_string());
+                (number, part)
+            }
+            None => {
+                let c = LAST_NUMBER.captures(&without_volume)?;
+                let number = match c.get(2) {
+                    Some(decimal) => format!("{}.{}", &c[1], decimal.as_str()),
+                    None => c[1].to_string(),
+                };
+                (number, None)
+            }
+        };
+
+        Some(Self {
+            volume,
+            number: number.parse().ok()?,
+            part,
+        })
+    }
+
+    /// Whether both identifiers designate the same chapter
+    pub fn same_chapter(&self, other: &Self) -> bool {
+        self.cmp_order(other) == Ordering::Equal
+    }
+
+    /// Reading order: by number, then chapters without a part before parts
+    pub fn cmp_order(&self, other: &Self) -> Ordering {
+        self.number
+            .total_cmp(&other.number)
+            .then_with(|| match (&self.part, &other.part) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(a), Some(b)) => compare_parts(a, b),
+            })
+    }
+}
+
+fn compare_parts(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u32>(), b.parse::<u32>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Counts the chapters published after `current`, ignoring duplicates of the
+/// same chapter. Returns `None` when `current` is not in the list, so callers
+/// can tell a stale list apart from a reader who is up to date.
+pub fn count_after(chapters: &[ChapterNumber], current: &ChapterNumber) -> Option<usize> {
+    if !chapters.iter().any(|c| c.same_chapter(current)) {
+        return None;
+    }
+
+    Some(count_newer(chapters, current))
+}
+
+/// Counts the distinct chapters ordered after `current`, whether or not
+/// `current` itself is listed (e.g. read on another site)
+pub fn count_newer(chapters: &[ChapterNumber], current: &ChapterNumber) -> usize {
+    let mut newer: Vec<&ChapterNumber> = Vec::new();
+    for chapter in chapters {
+        if chapter.cmp_order(current) == Ordering::Greater
+            && !newer.iter().any(|n| n.same_chapter(chapter))
+        {
+            newer.push(chapter);
+        }
+    }
+
+    newer.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> (Option<i64>, f64, Option<String>) {
+        let c = ChapterNumber::parse(input).unwrap_or_else(|| panic!("failed to parse {}", input));
+        (c.volume, c.number, c.part)
+    }
+
+    #[test]
+    fn test_parse_hrefs() {
+        assert_eq!(parse("https://example.com/manga/test/chapter-12/"), (None, 12.0, None));
+        assert_eq!(parse("/solo-leveling/chapter-110"), (None, 110.0, None));
+        assert_eq!(parse("/manga/test/chapter-12-5/"), (None, 12.5, None));
+        assert_eq!(parse("/manga/test/chapter-12.5"), (None, 12.5, None));
+        assert_eq!(parse("/manga/test/vol-2-chapter-15/"), (Some(2), 15.0, None));
+        assert_eq!(parse("/manga/test/chapter-10-part-2"), (None, 10.0, Some("2".to_string())));
+        assert_eq!(parse("/manga/test/ch.7"), (None, 7.0, None));
+        assert_eq!(parse("/read/test-2/episode-3"), (None, 3.0, None));
+        assert_eq!(parse("/manga/test/episode-3.5"), (None, 3.5, None));
+        assert!(ChapterNumber::parse("/manga/test/prologue").is_none());
+    }
+
+    #[test]
+    fn test_parse_user_input() {
+        assert_eq!(parse("chapter-12"), (None, 12.0, None));
+        assert_eq!(parse("12"), (None, 12.0, None));
+        assert_eq!(parse("12.5"), (None, 12.5, None));
+        assert_eq!(parse("12a"), (None, 12.0, Some("a".to_string())));
+        assert_eq!(parse("Vol.3 Ch.20"), (Some(3), 20.0, None));
+        assert!(ChapterNumber::parse("prologue").is_none());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let c10 = ChapterNumber::parse("chapter-10").unwrap();
+        let c10_part = ChapterNumber::parse("chapter-10-part-2").unwrap();
+        let c10_5 = ChapterNumber::parse("chapter-10.5").unwrap();
+        let c110 = ChapterNumber::parse("chapter-110").unwrap();
+
+        assert_eq!(c10.cmp_order(&c10_part), Ordering::Less);
+        assert_eq!(c10_part.cmp_order(&c10_5), Ordering::Less);
+        assert_eq!(c10_5.cmp_order(&c110), Ordering::Less);
+        assert!(!c10.same_chapter(&c110));
+        assert!(c10.same_chapter(&ChapterNumber::parse("/vol-1-ch-10/").unwrap()));
+    }
+
+    #[test]
+    fn test_count_after() {
+        let chapters: Vec<ChapterNumber> = ["chapter-111", "chapter-110", "chapter-12-5", "chapter-12", "chapter-10"]
+            .iter()
+            .map(|c| ChapterNumber::parse(c).unwrap())
+            .collect();
+
+        let current = ChapterNumber::parse("10").unwrap();
+        assert_eq!(count_after(&chapters, &current), Some(4));
+
+        let current = ChapterNumber::parse("chapter-12").unwrap();
+        assert_eq!(count_after(&chapters, &current), Some(3));
+
+        let current = ChapterNumber::parse("chapter-999").unwrap();
+        assert_eq!(count_after(&chapters, &current), None);
+
+        // Chapter 100 was read elsewhere and is missing from this list
+        let current = ChapterNumber::parse("chapter-100").unwrap();
+        assert_eq!(count_after(&chapters, &current), None);
+        assert_eq!(count_newer(&chapters, &current), 2);
+    }
+
+    #[test]
+    fn test_count_after_ignores_duplicates() {
+        let chapters: Vec<ChapterNumber> = ["/a/chapter-3", "/b/chapter-3", "/a/chapter-2"]
+            .iter()
+            .map(|c| ChapterNumber::parse(c).unwrap())
+            .collect();
+
+        let current = ChapterNumber::parse("chapter-2").unwrap();
+        assert_eq!(count_after(&chapters, &current), Some(1));
+    }
+}
@@ -0,0 +1,140 @@
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use crate::sync::service::SyncResult;
+
+/// What started a sync run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncTrigger {
+    Cron,
+    Manual,
+}
+
+impl SyncTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncTrigger::Cron => "cron",
+            SyncTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// Which sources a sync run covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncScope {
+    All,
+    Manga(i64),
+    Source(i64),
+    /// Every source of a user's library
+    User(i64),
+}
+
+impl SyncScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncScope::All => "all",
+            SyncScope::Manga(_) => "manga",
+            SyncScope::Source(_) => "source",
+            SyncScope::User(_) => "user",
+        }
+    }
+
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            SyncScope::All => None,
+            SyncScope::Manga(id) | SyncScope::Source(id) | SyncScope::User(id) => Some(*id),
+        }
+    }
+}
+
+/// Creates the sync_run row and returns its ID. `users` maps each user the
+/// run is shown to with the number of their sources it covers.
+pub async fn start_run(pool: &SqlitePool, trigger: SyncTrigger, scope: SyncScope, users: &BTreeMap<i64, usize>) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let id = sqlx::query("INSERT INTO sync_run (triggered_by, scope, scope_id, total_sources) VALUES (?, ?, ?, ?)")
+        .bind(trigger.as_str())
+        .bind(scope.as_str())
+        .bind(scope.id())
+        .bind(users.values().sum::<usize>() as i64)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+    for (user_id, total_sources) in users {
+        sqlx::query("INSERT INTO sync_run_user (sync_run_id, user_id, total_sources) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(user_id)
+            .bind(*total_sources as i64)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+pub async fn record_result(pool: &SqlitePool, run_id: i64, result: &SyncResult) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO sync_result (sync_run_id, source_id, user_id, manga_name, domain, new_chapters, error, duration_ms)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+        .bind(run_id)
+        .bind(result.source_id)
+        .bind(result.user_id)
+        .bind(&result.manga_name)
+        .bind(&result.domain)
+        .bind(result.new_chapters as i64)
+        .bind(&result.error)
+        .bind(result.duration_ms as i64)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Unfinished run of `scope` shown to `user_id`, with the number of the
+/// user's sources it covers
+pub async fn find_running(pool: &SqlitePool, scope: SyncScope, user_id: i64) -> Result<Option<(i64, i64)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT r.id, u.total_sources FROM sync_run r JOIN sync_run_user u ON u.sync_run_id = r.id AND u.user_id = ?
+        WHERE r.finished_at IS NULL AND r.scope = ? AND r.scope_id IS ?
+        ORDER BY r.id DESC LIMIT 1"
+    )
+        .bind(user_id)
+        .bind(scope.as_str())
+        .bind(scope.id())
+        .fetch_optional(pool)
+        .await
+}
+
+/// Marks the run as finished and computes its totals from the recorded results
+pub async fn finish_run(pool: &SqlitePool, run_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE sync_run SET
+            finished_at = CURRENT_TIMESTAMP,
+            success_count = (SELECT COUNT(*) FROM sync_result WHERE sync_run_id = sync_run.id AND error IS NULL),
+            error_count = (SELECT COUNT(*) FROM sync_result WHERE sync_run_id = sync_run.id AND error IS NOT NULL),
+            new_chapters = (SELECT COALESCE(SUM(new_chapters), 0) FROM sync_result WHERE sync_run_id = sync_run.id)
+        WHERE id = ?
+        "#
+    )
+        .bind(run_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Finishes runs left unfinished by a previous process, e.g. after a crash
+/// or a restart in the middle of a sync. Returns the number of runs closed.
+pub async fn close_interrupted_runs(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM sync_run WHERE finished_at IS NULL")
+        .fetch_all(pool)
+        .await?;
+
+    for id in &ids {
+        finish_run(pool, *id).await?;
+    }
+
+    Ok(ids.len() as u64)
+}
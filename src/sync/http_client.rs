@@ -1,11 +1,94 @@
-use reqwest::Client;
+use reqwest::{redirect, Client, Url};
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
 pub fn create_client() -> Client {
     Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .user_agent(USER_AGENT)
         .timeout(Duration::from_secs(30))
         .cookie_store(true)
         .build()
         .expect("Failed to create HTTP client")
 }
+
+/// Client connecting to `host` only through `addrs`, checked by the caller,
+/// and leaving redirects to the caller so their targets can be checked too
+pub fn create_pinned_client(host: &str, addrs: &[SocketAddr]) -> Client {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(30))
+        .redirect(redirect::Policy::none())
+        .resolve_to_addrs(host, addrs)
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+/// Resolves the host of a URL, rejecting it when one of its addresses is not
+/// public and `allow_private` is not set
+pub async fn resolve(url: &Url, allow_private: bool) -> anyhow::Result<Vec<SocketAddr>> {
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("{} is not an http(s) URL", url);
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+    let Some(host) = url.host_str() else {
+        anyhow::bail!("{} has no host", url);
+    };
+    // IPv6 literals keep their brackets in the URL
+    let addrs: Vec<SocketAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port)).await?.collect(),
+    };
+
+    if addrs.is_empty() {
+        anyhow::bail!("{} does not resolve", url);
+    }
+    if !allow_private && let Some(addr) = addrs.iter().find(|a| !is_public(a.ip())) {
+        anyhow::bail!("{} resolves to the non-public address {}", url, addr.ip());
+    }
+    Ok(addrs)
+}
+
+/// Whether an address is reachable from the internet, as opposed to the
+/// loopback, private, link-local and other reserved ranges
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                // Shared address space (carrier-grade NAT)
+                || (a == 100 && (64..128).contains(&b))
+                || a == 0)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public(v4.into()),
+            None => !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public() {
+        assert!(is_public("93.184.216.34".parse().unwrap()));
+        assert!(is_public("2606:2800:220:1::".parse().unwrap()));
+
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fe80::1", "fd00::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public(ip.parse().unwrap()), "{} is not public", ip);
+        }
+    }
+}
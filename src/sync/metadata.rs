@@ -0,0 +1,248 @@
+use reqwest::{Client, Url};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::sync::strategy::{element_title, SyncError, SyncResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum PublicationStatus {
+    Ongoing,
+    Completed,
+    Hiatus,
+}
+
+impl PublicationStatus {
+    /// Maps the wording sites use, e.g. `OnGoing`, `Finished` or `On Hold`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+        if text.contains("hiatus") || text.contains("on hold") {
+            Some(Self::Hiatus)
+        } else if text.contains("complete") || text.contains("finished") || text.contains("ended") {
+            Some(Self::Completed)
+        } else if text.contains("ongoing") || text.contains("on going") || text.contains("publishing") || text.contains("releasing") {
+            Some(Self::Ongoing)
+        } else {
+            None
+        }
+    }
+}
+
+/// Details of a manga read from its page on a source site
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct MangaMetadata {
+    pub title: Option<String>,
+    pub alt_titles: Vec<String>,
+    pub cover: Option<String>,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    pub status: Option<PublicationStatus>,
+    pub genres: Vec<String>,
+}
+
+/// Where each field is on a manga page. The title, cover and description fall
+/// back to the page's OpenGraph tags when their selector matches nothing.
+pub struct MetadataSelectors {
+    pub title: &'static str,
+    pub alt_titles: Option<&'static str>,
+    pub cover: &'static str,
+    pub authors: &'static str,
+    pub genres: &'static str,
+    pub description: &'static str,
+    /// `label: value` rows, searched for the status and alternative titles
+    pub info_rows: &'static str,
+}
+
+/// Madara, the WordPress theme used by many manga sites
+pub const MADARA_SELECTORS: MetadataSelectors = MetadataSelectors {
+    title: ".post-title h1",
+    alt_titles: None,
+    cover: ".summary_image img",
+    authors: ".author-content a",
+    genres: ".genres-content a",
+    description: ".summary__content, .description-summary",
+    info_rows: ".post-content_item",
+};
+
+const ALT_TITLE_LABELS: &[&str] = &["alternative", "alt name", "other name", "associated name"];
+const STATUS_LABELS: &[&str] = &["status"];
+
+/// Downloads a manga page and extracts its metadata
+pub async fn fetch_metadata(client: &Client, url: &str, selectors: &MetadataSelectors) -> SyncResult<MangaMetadata> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| SyncError::HttpError(e.to_string()))?;
+
+    let html = response
+        .text()
+        .await
+        .map_err(|e| SyncError::HttpError(e.to_string()))?;
+
+    Ok(parse_metadata(&html, selectors, Url::parse(url).ok().as_ref()))
+}
+
+/// Extracts the metadata of a manga page, resolving a relative cover URL against `page_url`
+pub fn parse_metadata(html: &str, selectors: &MetadataSelectors, page_url: Option<&Url>) -> MangaMetadata {
+    let document = Html::parse_document(html);
+
+    let title = first_text(&document, selectors.title).or_else(|| meta_content(&document, "og:title"));
+
+    let mut alt_titles = selectors
+        .alt_titles
+        .and_then(|s| first_text(&document, s))
+        .or_else(|| info_row(&document, selectors.info_rows, ALT_TITLE_LABELS))
+        .map(|text| split_titles(&text))
+        .unwrap_or_default();
+    alt_titles.retain(|t| Some(t) != title.as_ref());
+
+    let cover = select(&document, selectors.cover)
+        .find_map(|img| ["data-src", "data-lazy-src", "src"].iter().find_map(|attr| img.value().attr(attr)))
+        .map(|src| src.trim().to_string())
+        .filter(|src| !src.is_empty())
+        .or_else(|| meta_content(&document, "og:image"))
+        .map(|src| match page_url.and_then(|base| base.join(&src).ok()) {
+            Some(url) => url.to_string(),
+            None => src,
+        });
+
+    let description = first_text(&document, selectors.description)
+        .or_else(|| meta_content(&document, "og:description"));
+
+    let status = info_row(&document, selectors.info_rows, STATUS_LABELS)
+        .and_then(|text| PublicationStatus::parse(&text));
+
+    MangaMetadata {
+        title,
+        alt_titles,
+        cover,
+        authors: all_texts(&document, selectors.authors),
+        description,
+        status,
+        genres: all_texts(&document, selectors.genres),
+    }
+}
+
+fn select<'a>(document: &'a Html, selector: &str) -> impl Iterator<Item = ElementRef<'a>> {
+    let selector = Selector::parse(selector).ok();
+    selector
+        .into_iter()
+        .flat_map(move |s| document.select(&s).collect::<Vec<_>>())
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    select(document, selector).find_map(|e| element_title(&e))
+}
+
+/// Text of every match, without duplicates
+fn all_texts(document: &Html, selector: &str) -> Vec<String> {
+    let mut texts: Vec<String> = Vec::new();
+    for text in select(document, selector).filter_map(|e| element_title(&e)) {
+        let text = text.trim_end_matches(',').trim().to_string();
+        if !text.is_empty() && !texts.contains(&text) {
+            texts.push(text);
+        }
+    }
+    texts
+}
+
+fn meta_content(document: &Html, property: &str) -> Option<String> {
+    select(document, &format!("meta[property=\"{}\"]", property))
+        .find_map(|e| e.value().attr("content"))
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// Value of the first row starting with one of `labels`, e.g. `Status : Ongoing`
+fn info_row(document: &Html, selector: &str, labels: &[&str]) -> Option<String> {
+    select(document, selector).find_map(|row| {
+        let text = element_title(&row)?;
+        let lower = text.to_lowercase();
+        let label = labels.iter().find(|label| lower.starts_with(*label))?;
+        // The label is ASCII, so its length is the same in the original text
+        let value = text[label.len()..]
+            .trim_start_matches(|c: char| c.is_alphabetic() || c == '(' || c == ')')
+            .trim_start_matches([' ', ':'])
+            .trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn split_titles(text: &str) -> Vec<String> {
+    let mut titles: Vec<String> = Vec::new();
+    for title in text.split([',', ';', '/']).map(str::trim).filter(|t| !t.is_empty()) {
+        if !titles.iter().any(|t| t == title) {
+            titles.push(title.to_string());
+        }
+    }
+    titles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MADARA_PAGE: &str = r#"
+        <html><head>
+            <meta property="og:title" content="Test Manga - Read Online" />
+            <meta property="og:description" content="Short description" />
+        </head><body>
+            <div class="post-title"><h1> Test   Manga </h1></div>
+            <div class="summary_image"><img data-src="/covers/test.jpg" src="placeholder.gif" /></div>
+            <div class="post-content_item">
+                <div class="summary-heading"><h5>Alternative</h5></div>
+                <div class="summary-content">Test Manhwa, テスト ; Test Manga</div>
+            </div>
+            <div class="post-content_item">
+                <div class="author-content"><a href="/author/a">Author A</a>, <a href="/author/b">Author B</a></div>
+            </div>
+            <div class="post-content_item">
+                <div class="genres-content"><a>Action</a>, <a>Fantasy</a>, <a>Action</a></div>
+            </div>
+            <div class="post-status"><div class="post-content_item">
+                <div class="summary-heading"><h5>Status</h5></div>
+                <div class="summary-content">OnGoing</div>
+            </div></div>
+            <div class="summary__content"><p>A long</p><p>description.</p></div>
+        </body></html>
+    "#;
+
+    #[test]
+    fn test_parse_madara_page() {
+        let base = Url::parse("https://example.org/manga/test/").unwrap();
+        let metadata = parse_metadata(MADARA_PAGE, &MADARA_SELECTORS, Some(&base));
+
+        assert_eq!(metadata.title.as_deref(), Some("Test Manga"));
+        assert_eq!(metadata.alt_titles, vec!["Test Manhwa", "テスト"]);
+        assert_eq!(metadata.cover.as_deref(), Some("https://example.org/covers/test.jpg"));
+        assert_eq!(metadata.authors, vec!["Author A", "Author B"]);
+        assert_eq!(metadata.genres, vec!["Action", "Fantasy"]);
+        assert_eq!(metadata.status, Some(PublicationStatus::Ongoing));
+        assert_eq!(metadata.description.as_deref(), Some("A long description."));
+    }
+
+    #[test]
+    fn test_parse_falls_back_to_opengraph() {
+        let html = r#"<head>
+            <meta property="og:title" content="Other Manga" />
+            <meta property="og:image" content="https://cdn.example.org/other.png" />
+        </head>"#;
+        let metadata = parse_metadata(html, &MADARA_SELECTORS, None);
+
+        assert_eq!(metadata.title.as_deref(), Some("Other Manga"));
+        assert_eq!(metadata.cover.as_deref(), Some("https://cdn.example.org/other.png"));
+        assert!(metadata.authors.is_empty());
+        assert_eq!(metadata.status, None);
+    }
+
+    #[test]
+    fn test_publication_status() {
+        assert_eq!(PublicationStatus::parse("Completed"), Some(PublicationStatus::Completed));
+        assert_eq!(PublicationStatus::parse("On Hold"), Some(PublicationStatus::Hiatus));
+        assert_eq!(PublicationStatus::parse("2021"), None);
+    }
+}
@@ -1,4 +1,10 @@
+pub mod chapter_number;
+pub mod history;
 pub mod http_client;
+pub mod metadata;
+pub mod rate_limit;
+pub mod reconcile;
+pub mod remote_chapters;
 pub mod scheduler;
 pub mod service;
 pub mod strategies;
@@ -0,0 +1,132 @@
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::settings;
+
+const DEFAULT_MAX_IN_FLIGHT: u64 = 1;
+const DEFAULT_MIN_DELAY_MS: u64 = 1000;
+
+struct DomainLimit {
+    semaphore: Arc<Semaphore>,
+    next_request_at: tokio::sync::Mutex<Instant>,
+}
+
+/// Politeness limiter shared by every request made to the scraped websites,
+/// whichever sync run or refresh makes them.
+///
+/// Each domain gets at most `max_in_flight` concurrent requests, and two
+/// requests to the same domain are started at least `min_delay` apart.
+pub struct DomainRateLimiter {
+    max_in_flight: usize,
+    min_delay: Duration,
+    domains: Mutex<HashMap<String, Arc<DomainLimit>>>,
+}
+
+/// Held for the duration of a request; dropping it frees the domain slot
+pub struct DomainPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl DomainRateLimiter {
+    pub fn new(max_in_flight: usize, min_delay: Duration) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            min_delay,
+            domains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limiter configured by the `SYNC_DOMAIN_CONCURRENCY` and
+    /// `SYNC_DOMAIN_DELAY_MS` settings
+    pub async fn from_settings(pool: &SqlitePool) -> Self {
+        let max_in_flight = settings::get_setting_u64(pool, "SYNC_DOMAIN_CONCURRENCY", DEFAULT_MAX_IN_FLIGHT)
+            .await
+            .unwrap_or(DEFAULT_MAX_IN_FLIGHT);
+        let min_delay_ms = settings::get_setting_u64(pool, "SYNC_DOMAIN_DELAY_MS", DEFAULT_MIN_DELAY_MS)
+            .await
+            .unwrap_or(DEFAULT_MIN_DELAY_MS);
+
+        Self::new(max_in_flight as usize, Duration::from_millis(min_delay_ms))
+    }
+
+    fn limit_for(&self, domain: &str) -> Arc<DomainLimit> {
+        let mut domains = self.domains.lock().unwrap();
+        domains
+            .entry(domain.to_string())
+            .or_insert_with(|| {
+                Arc::new(DomainLimit {
+                    semaphore: Arc::new(Semaphore::new(self.max_in_flight)),
+                    next_request_at: tokio::sync::Mutex::new(Instant::now()),
+                })
+            })
+            .clone()
+    }
+
+    /// Waits until a request to `domain` is allowed
+    pub async fn acquire(&self, domain: &str) -> DomainPermit {
+        let limit = self.limit_for(domain);
+
+        let permit = limit
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("domain semaphore is never closed");
+
+        let mut next_request_at = limit.next_request_at.lock().await;
+        tokio::time::sleep_until(*next_request_at).await;
+        *next_request_at = Instant::now() + self.min_delay;
+
+        DomainPermit { _permit: permit }
+    }
+}
+
+impl Default for DomainRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IN_FLIGHT as usize, Duration::from_millis(DEFAULT_MIN_DELAY_MS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_delay_between_requests_to_same_domain() {
+        let limiter = DomainRateLimiter::new(2, Duration::from_millis(50));
+        let start = Instant::now();
+
+        let _first = limiter.acquire("example.com").await;
+        let _second = limiter.acquire("example.com").await;
+
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_domains_are_independent() {
+        let limiter = DomainRateLimiter::new(1, Duration::from_millis(500));
+        let start = Instant::now();
+
+        let _first = limiter.acquire("example.com").await;
+        let _second = limiter.acquire("example.org").await;
+
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        let limiter = DomainRateLimiter::new(1, Duration::ZERO);
+
+        let first = limiter.acquire("example.com").await;
+        let blocked = tokio::time::timeout(Duration::from_millis(50), limiter.acquire("example.com")).await;
+        assert!(blocked.is_err(), "second request should wait for the first one");
+
+        drop(first);
+        let released = tokio::time::timeout(Duration::from_millis(50), limiter.acquire("example.com")).await;
+        assert!(released.is_ok());
+    }
+}
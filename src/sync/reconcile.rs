@@ -0,0 +1,146 @@
+use sqlx::SqlitePool;
+
+use crate::sync::chapter_number::{self, ChapterNumber};
+use crate::sync::strategy::{self, ChapterLink};
+
+#[derive(sqlx::FromRow)]
+struct StoredChapter {
+    href: String,
+    volume: Option<i64>,
+    number: Option<f64>,
+    part: Option<String>,
+    title: Option<String>,
+}
+
+/// Counts the unread chapters of one stored chapter list.
+///
+/// The reading position is manga-wide, so the current chapter may have been
+/// read on another site and be missing from this list: when both sides parse,
+/// every chapter ordered after it is unread. Otherwise this falls back to the
+/// href matching of [`strategy::count_new_chapters`].
+fn count_unread(chapters: &[StoredChapter], current_chapter: &str) -> Option<usize> {
+    if let Some(current) = ChapterNumber::parse(current_chapter) {
+        let parsed: Vec<ChapterNumber> = chapters
+            .iter()
+            .filter_map(|c| {
+                c.number.map(|number| ChapterNumber {
+                    volume: c.volume,
+                    number,
+                    part: c.part.clone(),
+                })
+            })
+            .collect();
+
+        if !parsed.is_empty() {
+            return Some(chapter_number::count_newer(&parsed, &current));
+        }
+    }
+
+    let links: Vec<ChapterLink> = chapters
+        .iter()
+        .map(|c| ChapterLink {
+            href: c.href.clone(),
+            title: c.title.clone(),
+        })
+        .collect();
+
+    strategy::count_new_chapters(&links, current_chapter).ok()
+}
+
+/// Counts the unread chapters of a freshly scraped list the same way as the
+/// stored lists, so a chapter read on another site is also reconciled when
+/// syncing. Returns `None` when the current chapter cannot be placed.
+pub fn count_unread_links(chapters: &[ChapterLink], current_chapter: &str) -> Option<usize> {
+    let chapters: Vec<StoredChapter> = chapters
+        .iter()
+        .map(|c| {
+            let parsed = ChapterNumber::parse(&c.href);
+            StoredChapter {
+                href: c.href.clone(),
+                volume: parsed.as_ref().and_then(|p| p.volume),
+                number: parsed.as_ref().map(|p| p.number),
+                part: parsed.and_then(|p| p.part),
+                title: c.title.clone(),
+            }
+        })
+        .collect();
+
+    count_unread(&chapters, current_chapter)
+}
+
+/// Recomputes the unread count of every source of a manga from the stored
+/// chapter lists, so that a chapter read on one site counts as read on all
+/// of them. Sources that were never scraped are left untouched.
+///
+/// Returns the number of sources updated.
+pub async fn reconcile_manga(pool: &SqlitePool, manga_id: i64, current_chapter: &str) -> Result<usize, sqlx::Error> {
+    let source_ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM source WHERE manga_id = ?")
+        .bind(manga_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut updated = 0;
+    for source_id in source_ids {
+        let chapters = sqlx::query_as::<sqlx::Sqlite, StoredChapter>(
+            "SELECT rc.href, rc.volume, rc.number, rc.part, rc.title
+            FROM remote_chapter rc JOIN source s ON s.website_id = rc.website_id AND s.path = rc.path
+            WHERE s.id = ? ORDER BY rc.position"
+        )
+            .bind(source_id)
+            .fetch_all(pool)
+            .await?;
+
+        if chapters.is_empty() {
+            continue;
+        }
+
+        match count_unread(&chapters, current_chapter) {
+            Some(count) => {
+                sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
+                    .bind(count as i64)
+                    .bind(source_id)
+                    .execute(pool)
+                    .await?;
+                updated += 1;
+            }
+            None => {
+                tracing::warn!(
+                    "Source #{} has no chapter matching {}, unread count left unchanged",
+                    source_id,
+                    current_chapter
+                );
+            }
+        }
+    }
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(hrefs: &[&str]) -> Vec<ChapterLink> {
+        hrefs
+            .iter()
+            .map(|href| ChapterLink {
+                href: href.to_string(),
+                title: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_count_unread_links() {
+        let chapters = links(&["/manga/test/chapter-7", "/manga/test/chapter-6", "/manga/test/chapter-4"]);
+
+        // chapter-5 was read on another site that published it first
+        assert_eq!(count_unread_links(&chapters, "chapter-5"), Some(2));
+        assert_eq!(count_unread_links(&chapters, "chapter-7"), Some(0));
+
+        // Unparsed lists fall back to matching the href
+        let chapters = links(&["/manga/test/finale", "/manga/test/prologue"]);
+        assert_eq!(count_unread_links(&chapters, "prologue"), Some(1));
+        assert_eq!(count_unread_links(&chapters, "epilogue"), None);
+    }
+}
@@ -0,0 +1,76 @@
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+use crate::sync::chapter_number::ChapterNumber;
+use crate::sync::strategy::ChapterLink;
+
+/// Replaces the stored chapter list of the page a source follows with the
+/// freshly scraped one. The list is shared by every source following the
+/// same path on the same website.
+///
+/// Chapters already known keep their `first_seen_at`, new ones are inserted
+/// and chapters the site no longer lists are removed.
+pub async fn store_chapters(pool: &SqlitePool, source_id: i64, chapters: &[ChapterLink]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let Some((website_id, path)) = sqlx::query_as::<_, (i64, String)>("SELECT website_id, path FROM source WHERE id = ?")
+        .bind(source_id)
+        .fetch_optional(&mut *tx)
+        .await?
+    else {
+        // Deleted while it was being scraped
+        return Ok(());
+    };
+
+    let existing: Vec<(i64, String)> = sqlx::query_as("SELECT id, href FROM remote_chapter WHERE website_id = ? AND path = ?")
+        .bind(website_id)
+        .bind(&path)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    // One timestamp for the whole list, so a source's first scrape can be
+    // told apart from the chapters released after it
+    let seen_at: String = sqlx::query_scalar("SELECT CURRENT_TIMESTAMP")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    for (position, chapter) in chapters.iter().enumerate() {
+        let parsed = ChapterNumber::parse(&chapter.href);
+        sqlx::query(
+            r#"
+            INSERT INTO remote_chapter (website_id, path, href, volume, number, part, title, position, first_seen_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (website_id, path, href) DO UPDATE SET
+                volume = excluded.volume,
+                number = excluded.number,
+                part = excluded.part,
+                title = excluded.title,
+                position = excluded.position
+            "#
+        )
+            .bind(website_id)
+            .bind(&path)
+            .bind(&chapter.href)
+            .bind(parsed.as_ref().and_then(|p| p.volume))
+            .bind(parsed.as_ref().map(|p| p.number))
+            .bind(parsed.as_ref().and_then(|p| p.part.clone()))
+            .bind(&chapter.title)
+            .bind(position as i64)
+            .bind(&seen_at)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let listed: HashSet<&str> = chapters.iter().map(|c| c.href.as_str()).collect();
+    for (id, href) in existing {
+        if !listed.contains(href.as_str()) {
+            sqlx::query("DELETE FROM remote_chapter WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
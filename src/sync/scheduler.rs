@@ -3,9 +3,12 @@ use std::sync::Arc;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 use crate::cache::ChapterCache;
+use crate::events::EventBus;
+use crate::sync::rate_limit::DomainRateLimiter;
+use crate::sync::history::{SyncScope, SyncTrigger};
 use crate::sync::service::SyncService;
 
-pub async fn start_scheduler(pool: SqlitePool, cache: Arc<ChapterCache>, cron_expression: &str) -> anyhow::Result<JobScheduler> {
+pub async fn start_scheduler(pool: SqlitePool, cache: Arc<ChapterCache>, events: EventBus, limiter: Arc<DomainRateLimiter>, cron_expression: &str) -> anyhow::Result<JobScheduler> {
     let scheduler = JobScheduler::new().await?;
 
     let pool = Arc::new(pool);
@@ -13,18 +16,27 @@ pub async fn start_scheduler(pool: SqlitePool, cache: Arc<ChapterCache>, cron_ex
     let job = Job::new_async(cron_expression, move |_uuid, _lock| {
         let pool = Arc::clone(&pool);
         let cache = Arc::clone(&cache);
+        let events = events.clone();
+        let limiter = Arc::clone(&limiter);
         Box::pin(async move {
             tracing::info!("Starting daily manga sync job");
 
-            let service = SyncService::new((*pool).clone(), cache);
-            let results = service.sync_all().await;
+            let service = SyncService::new((*pool).clone(), cache, events, limiter).await;
+            let (run_id, results) = match service.run(SyncTrigger::Cron, SyncScope::All, None).await {
+                Ok(run) => run,
+                Err(e) => {
+                    tracing::error!("Sync job failed: {}", e);
+                    return;
+                }
+            };
 
             let success_count = results.iter().filter(|r| r.error.is_none()).count();
             let error_count = results.iter().filter(|r| r.error.is_some()).count();
             let total_new_chapters: usize = results.iter().map(|r| r.new_chapters).sum();
 
             tracing::info!(
-                "Sync job completed: {} sources synced, {} errors, {} new chapters total",
+                "Sync job #{} completed: {} sources synced, {} errors, {} new chapters total",
+                run_id,
                 success_count,
                 error_count,
                 total_new_chapters
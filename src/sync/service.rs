@@ -1,45 +1,92 @@
 use reqwest::Client;
 use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
 
 use crate::cache::ChapterCache;
+use crate::events::{EventBus, LibraryEvent};
+use crate::settings;
+use crate::sync::history::{self, SyncScope, SyncTrigger};
 use crate::sync::http_client::create_client;
+use crate::sync::rate_limit::DomainRateLimiter;
+use crate::sync::reconcile;
+use crate::sync::remote_chapters;
 use crate::sync::strategies::StrategyRegistry;
+use crate::sync::strategy::{ChapterLink, SyncError};
+use crate::webhook::{self, NewChaptersEvent, SyncFinishedEvent, WebhookDispatcher};
 
 pub struct SyncService {
     pool: SqlitePool,
     client: Client,
     registry: StrategyRegistry,
     cache: Arc<ChapterCache>,
+    concurrency: usize,
+    limiter: Arc<DomainRateLimiter>,
+    webhooks: WebhookDispatcher,
+    events: EventBus,
 }
 
 #[derive(Debug)]
 pub struct SyncSourceInfo {
     pub source_id: i64,
     pub manga_id: i64,
+    /// Owner of the manga
+    pub user_id: i64,
     pub manga_name: String,
     pub domain: String,
     pub path: String,
     pub external_manga_id: Option<String>,
     pub current_chapter: Option<String>,
+    pub number_unread_chapter: Option<i64>,
 }
 
 #[derive(Debug)]
 pub struct SyncResult {
     pub source_id: i64,
+    pub manga_id: i64,
+    pub user_id: i64,
     pub manga_name: String,
     pub domain: String,
     pub new_chapters: usize,
     pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// A recorded sync run whose sources have not been synced yet
+pub struct PendingRun {
+    pub id: i64,
+    /// Number of sources of each user the run is shown to
+    users: BTreeMap<i64, usize>,
+    sources: Vec<SyncSourceInfo>,
+}
+
+impl PendingRun {
+    pub fn total_sources(&self) -> usize {
+        self.sources.len()
+    }
 }
 
 impl SyncService {
-    pub fn new(pool: SqlitePool, cache: Arc<ChapterCache>) -> Self {
+    /// `limiter` is shared with every other sync so that concurrent runs
+    /// do not multiply the requests made to a website
+    pub async fn new(pool: SqlitePool, cache: Arc<ChapterCache>, events: EventBus, limiter: Arc<DomainRateLimiter>) -> Self {
+        let registry = StrategyRegistry::load_or_default(&pool).await;
+
+        let concurrency = settings::get_setting_u64(&pool, "SYNC_CONCURRENCY", 8).await.unwrap_or(8);
+
+        let webhooks = WebhookDispatcher::new(pool.clone()).await;
+
         Self {
             pool,
             client: create_client(),
-            registry: StrategyRegistry::new(),
+            registry,
             cache,
+            concurrency: (concurrency as usize).max(1),
+            limiter,
+            webhooks,
+            events,
         }
     }
 
@@ -56,7 +103,7 @@ impl SyncService {
     }
 
     pub async fn sync_all(&self) -> Vec<SyncResult> {
-        let sources = match self.get_sources_to_sync().await {
+        let sources = match self.get_sources_to_sync(SyncScope::All).await {
             Ok(sources) => sources,
             Err(e) => {
                 tracing::error!("Failed to fetch sources to sync: {}", e);
@@ -64,17 +111,103 @@ impl SyncService {
             }
         };
 
-        let mut results = Vec::new();
+        self.sync_sources(sources, None).await
+    }
 
-        for source in sources {
-            let result = self.sync_source(&source).await;
-            results.push(result);
+    /// Syncs the supported sources in `scope` and records the run and each
+    /// result in the sync history. Returns the run ID along with the results.
+    /// `user_id` is the user starting the run, if any, who is shown the run
+    /// even when it covers none of their sources.
+    pub async fn run(&self, trigger: SyncTrigger, scope: SyncScope, user_id: Option<i64>) -> Result<(i64, Vec<SyncResult>), sqlx::Error> {
+        let run = self.start_run(trigger, scope, user_id).await?;
+        let run_id = run.id;
+        let results = self.execute_run(run).await?;
+        Ok((run_id, results))
+    }
+
+    /// Records a new run for the sources in `scope` without syncing them, so
+    /// the run ID can be handed out before the work starts
+    pub async fn start_run(&self, trigger: SyncTrigger, scope: SyncScope, user_id: Option<i64>) -> Result<PendingRun, sqlx::Error> {
+        let sources = self.get_sources_to_sync(scope).await?;
+
+        let mut users: BTreeMap<i64, usize> = user_id.map(|id| (id, 0)).into_iter().collect();
+        for source in &sources {
+            *users.entry(source.user_id).or_default() += 1;
         }
 
-        results
+        let id = history::start_run(&self.pool, trigger, scope, &users).await?;
+
+        for (user_id, total_sources) in &users {
+            self.events.publish(*user_id, LibraryEvent::SyncStarted {
+                run_id: id,
+                scope: scope.as_str().to_string(),
+                scope_id: scope.id(),
+                total_sources: *total_sources,
+            });
+        }
+
+        Ok(PendingRun { id, users, sources })
     }
 
-    async fn get_sources_to_sync(&self) -> Result<Vec<SyncSourceInfo>, sqlx::Error> {
+    /// Syncs the sources of a run started with [`SyncService::start_run`].
+    /// Each user is notified of the results of their own sources.
+    pub async fn execute_run(&self, run: PendingRun) -> Result<Vec<SyncResult>, sqlx::Error> {
+        let results = self.sync_sources(run.sources, Some(run.id)).await;
+        history::finish_run(&self.pool, run.id).await?;
+
+        for user_id in run.users.keys() {
+            let user_results: Vec<&SyncResult> = results.iter().filter(|r| r.user_id == *user_id).collect();
+            let error_count = user_results.iter().filter(|r| r.error.is_some()).count();
+            let new_chapters = user_results.iter().map(|r| r.new_chapters).sum();
+
+            self.events.publish(*user_id, LibraryEvent::SyncFinished {
+                run_id: run.id,
+                success_count: user_results.len() - error_count,
+                error_count,
+                new_chapters,
+            });
+
+            self.webhooks
+                .dispatch(*user_id, webhook::EVENT_SYNC_FINISHED, &SyncFinishedEvent {
+                    run_id: run.id,
+                    total_sources: user_results.len(),
+                    success_count: user_results.len() - error_count,
+                    error_count,
+                    new_chapters,
+                })
+                .await;
+        }
+
+        Ok(results)
+    }
+
+    /// Syncs the given sources concurrently, recording each result as soon as
+    /// it is available when a run ID is given
+    async fn sync_sources(&self, sources: Vec<SyncSourceInfo>, run_id: Option<i64>) -> Vec<SyncResult> {
+        // Spread domains over the queue so a site with many sources does not
+        // hold every concurrency slot while waiting on its own rate limit
+        let pages = group_by_page(interleave_by_domain(sources));
+        let semaphore = Semaphore::new(self.concurrency);
+
+        let tasks = pages.iter().map(|sources| async {
+            let _permit = semaphore.acquire().await.expect("sync semaphore is never closed");
+            let results = self.sync_page(sources).await;
+
+            if let Some(run_id) = run_id {
+                for result in &results {
+                    if let Err(e) = history::record_result(&self.pool, run_id, result).await {
+                        tracing::warn!("Failed to record sync result for source {}: {}", result.source_id, e);
+                    }
+                }
+            }
+
+            results
+        });
+
+        futures::future::join_all(tasks).await.into_iter().flatten().collect()
+    }
+
+    async fn get_sources_to_sync(&self, scope: SyncScope) -> Result<Vec<SyncSourceInfo>, sqlx::Error> {
         let domains = self.registry.supported_domains();
 
         if domains.is_empty() {
@@ -91,6 +224,7 @@ impl SyncService {
             SELECT
                 s.id as source_id,
                 s.manga_id,
+                m.user_id,
                 m.name as manga_name,
                 w.domain,
                 s.path,
@@ -101,36 +235,49 @@ impl SyncService {
                     WHERE c.manga_id = s.manga_id
                     ORDER BY c.updated_at DESC
                     LIMIT 1
-                ) as current_chapter
+                ) as current_chapter,
+                s.number_unread_chapter
             FROM source s
             JOIN manga m ON m.id = s.manga_id
             JOIN website w ON w.id = s.website_id
-            WHERE w.domain IN ({})
+            WHERE w.domain IN ({}) {}
             "#,
-            placeholder_str
+            placeholder_str,
+            match scope {
+                SyncScope::All => "",
+                SyncScope::Manga(_) => "AND s.manga_id = ?",
+                SyncScope::Source(_) => "AND s.id = ?",
+                SyncScope::User(_) => "AND m.user_id = ?",
+            }
         );
 
         // Types of the query's response
-        let mut query_builder = sqlx::query_as::<_, (i64, i64, String, String, String, Option<String>, Option<String>)>(&query);
+        let mut query_builder = sqlx::query_as::<_, (i64, i64, i64, String, String, String, Option<String>, Option<String>, Option<i64>)>(&query);
 
         // Bind each domain parameter to the query (securely replace the placeholders)
         for domain in &domains {
             query_builder = query_builder.bind(*domain);
         }
 
+        if let Some(id) = scope.id() {
+            query_builder = query_builder.bind(id);
+        }
+
         let rows = query_builder.fetch_all(&self.pool).await?;
 
         let sources = rows
             .into_iter()
-            .map(|(source_id, manga_id, manga_name, domain, path, external_manga_id, current_chapter)| {
+            .map(|(source_id, manga_id, user_id, manga_name, domain, path, external_manga_id, current_chapter, number_unread_chapter)| {
                 SyncSourceInfo {
                     source_id,
                     manga_id,
+                    user_id,
                     manga_name,
                     domain,
                     path,
                     external_manga_id,
                     current_chapter,
+                    number_unread_chapter,
                 }
             })
             .collect();
@@ -138,82 +285,102 @@ impl SyncService {
         Ok(sources)
     }
 
-    async fn sync_source(&self, source: &SyncSourceInfo) -> SyncResult {
-        let strategy = match self.registry.get(&source.domain) {
-            Some(s) => s,
-            None => {
-                return SyncResult {
+    /// Syncs sources following the same page, which is scraped only once
+    async fn sync_page(&self, sources: &[SyncSourceInfo]) -> Vec<SyncResult> {
+        let started = Instant::now();
+        let fetched = self.fetch_page(sources).await;
+
+        let mut results = Vec::with_capacity(sources.len());
+        for source in sources {
+            let mut result = match &fetched {
+                Ok(chapters) => self.count_unread(source, chapters).await,
+                Err(e) => SyncResult {
                     source_id: source.source_id,
+                    manga_id: source.manga_id,
+                    user_id: source.user_id,
                     manga_name: source.manga_name.clone(),
                     domain: source.domain.clone(),
                     new_chapters: 0,
-                    error: Some(format!("No strategy for domain: {}", source.domain)),
-                }
-            }
-        };
+                    error: Some(e.clone()),
+                    duration_ms: 0,
+                },
+            };
+            result.duration_ms = started.elapsed().as_millis() as u64;
+            results.push(result);
+        }
+        results
+    }
+
+    /// Scrapes the chapter list of the page followed by `sources`, which all
+    /// share the same domain and path, and stores it
+    async fn fetch_page(&self, sources: &[SyncSourceInfo]) -> Result<Vec<ChapterLink>, String> {
+        let source = &sources[0];
+        let strategy = self
+            .registry
+            .get(&source.domain)
+            .ok_or_else(|| format!("No strategy for domain: {}", source.domain))?;
 
         // Extract external_id if not already stored
-        let extracted_id: Option<String> = match &source.external_manga_id {
-            Some(_) => None,
-            None => {
-                match strategy.extract_external_id(&self.client, &source.path).await {
-                    Ok(Some(id)) => {
-                        if let Err(e) = self.update_external_id(source.source_id, &id).await {
-                            tracing::warn!(
-                                "Failed to save external_manga_id for source {}: {}",
-                                source.source_id,
-                                e
-                            );
-                        }
-                        Some(id)
-                    }
-                    Ok(None) => None,
-                    Err(e) => {
-                        return SyncResult {
-                            source_id: source.source_id,
-                            manga_name: source.manga_name.clone(),
-                            domain: source.domain.clone(),
-                            new_chapters: 0,
-                            error: Some(format!("Failed to extract external ID: {}", e)),
-                        }
-                    }
+        let mut external_id = sources.iter().find_map(|s| s.external_manga_id.clone());
+        if external_id.is_none() {
+            let _permit = self.limiter.acquire(&source.domain).await;
+            external_id = strategy
+                .extract_external_id(&self.client, &source.path)
+                .await
+                .map_err(|e| format!("Failed to extract external ID: {}", e))?;
+        }
+
+        if let Some(id) = &external_id {
+            for source in sources.iter().filter(|s| s.external_manga_id.is_none()) {
+                if let Err(e) = self.update_external_id(source.source_id, id).await {
+                    tracing::warn!(
+                        "Failed to save external_manga_id for source {}: {}",
+                        source.source_id,
+                        e
+                    );
                 }
             }
-        };
+        }
 
-        let external_id_ref = source.external_manga_id.as_deref().or(extracted_id.as_deref());
+        let permit = self.limiter.acquire(&source.domain).await;
+        let fetched = strategy.fetch_chapters(&self.client, &source.path, external_id.as_deref()).await;
+        drop(permit);
 
-        let chapters = match strategy.fetch_chapters(&self.client, &source.path, external_id_ref).await {
-            Ok(c) => {
-                // Cache the chapters after fetching
-                self.cache.set(&source.domain, &source.path, c.clone()).await;
-                c
-            }
-            Err(e) => {
-                return SyncResult {
-                    source_id: source.source_id,
-                    manga_name: source.manga_name.clone(),
-                    domain: source.domain.clone(),
-                    new_chapters: 0,
-                    error: Some(format!("Failed to fetch chapters: {}", e)),
-                }
-            }
-        };
+        let chapters = fetched.map_err(|e| format!("Failed to fetch chapters: {}", e))?;
+
+        // Cache the chapters after fetching
+        self.cache.set(&source.domain, &source.path, chapters.clone()).await;
+        if let Err(e) = remote_chapters::store_chapters(&self.pool, source.source_id, &chapters).await {
+            tracing::warn!(
+                "Failed to store chapter list for source {}: {}",
+                source.source_id,
+                e
+            );
+        }
 
+        Ok(chapters)
+    }
+
+    /// Counts the chapters of the scraped list not read yet on the source. The
+    /// reading position is manga-wide, so the current chapter may have been
+    /// read on another site and be missing from the list.
+    async fn count_unread(&self, source: &SyncSourceInfo, chapters: &[ChapterLink]) -> SyncResult {
         // If no chapter has been read yet, all available chapters are considered unread
         let count_result = match &source.current_chapter {
-            Some(current_chapter) => strategy.count_new_chapters(&chapters, current_chapter),
+            Some(current_chapter) => reconcile::count_unread_links(chapters, current_chapter)
+                .ok_or_else(|| SyncError::ChapterNotFound(current_chapter.clone())),
             None => Ok(chapters.len()),
         };
 
         match count_result {
             Ok(count) => {
-                if let Err(e) = self.update_unread_count(source.source_id, count).await {
-                    tracing::warn!(
+                match self.update_unread_count(source.source_id, count).await {
+                    Ok(()) => self.notify_unread_count(source, count).await,
+                    Err(e) => tracing::warn!(
                         "Failed to save number_unread_chapter for source {}: {}",
                         source.source_id,
                         e
-                    );
+                    ),
                 }
 
                 tracing::info!(
@@ -224,18 +391,24 @@ impl SyncService {
                 );
                 SyncResult {
                     source_id: source.source_id,
+                    manga_id: source.manga_id,
+                    user_id: source.user_id,
                     manga_name: source.manga_name.clone(),
                     domain: source.domain.clone(),
                     new_chapters: count,
                     error: None,
+                    duration_ms: 0,
                 }
             }
             Err(e) => SyncResult {
                 source_id: source.source_id,
+                manga_id: source.manga_id,
+                user_id: source.user_id,
                 manga_name: source.manga_name.clone(),
                 domain: source.domain.clone(),
                 new_chapters: 0,
                 error: Some(format!("Failed to count new chapters: {}", e)),
+                duration_ms: 0,
             },
         }
     }
@@ -249,6 +422,36 @@ impl SyncService {
         Ok(())
     }
 
+    /// Notifies clients when the unread count changed and webhooks when it
+    /// went up. Sources synced for the first time have no previous count and
+    /// do not notify webhooks.
+    async fn notify_unread_count(&self, source: &SyncSourceInfo, count: usize) {
+        if source.number_unread_chapter != Some(count as i64) {
+            self.events.publish(source.user_id, LibraryEvent::UnreadCountChanged {
+                manga_id: source.manga_id,
+                source_id: source.source_id,
+                number_unread_chapter: count as i64,
+            });
+        }
+
+        let Some(previous) = source.number_unread_chapter else {
+            return;
+        };
+
+        if count as i64 > previous {
+            self.webhooks
+                .dispatch(source.user_id, webhook::EVENT_NEW_CHAPTERS, &NewChaptersEvent {
+                    manga_id: source.manga_id,
+                    manga_name: source.manga_name.clone(),
+                    source_id: source.source_id,
+                    domain: source.domain.clone(),
+                    previous_unread: previous,
+                    number_unread_chapter: count as i64,
+                })
+                .await;
+        }
+    }
+
     async fn update_unread_count(&self, source_id: i64, count: usize) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE source SET number_unread_chapter = ? WHERE id = ?")
             .bind(count as i64)
@@ -258,3 +461,99 @@ impl SyncService {
         Ok(())
     }
 }
+
+/// Groups sources following the same path on the same website, in the order
+/// of their first source
+fn group_by_page(sources: Vec<SyncSourceInfo>) -> Vec<Vec<SyncSourceInfo>> {
+    let mut pages: Vec<Vec<SyncSourceInfo>> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+
+    for source in sources {
+        match index.get(&(source.domain.clone(), source.path.clone())) {
+            Some(&i) => pages[i].push(source),
+            None => {
+                index.insert((source.domain.clone(), source.path.clone()), pages.len());
+                pages.push(vec![source]);
+            }
+        }
+    }
+
+    pages
+}
+
+/// Reorders sources round-robin by domain, keeping the relative order of
+/// sources within the same domain
+fn interleave_by_domain(sources: Vec<SyncSourceInfo>) -> Vec<SyncSourceInfo> {
+    let mut domains: Vec<String> = Vec::new();
+    let mut queues: HashMap<String, VecDeque<SyncSourceInfo>> = HashMap::new();
+
+    for source in sources {
+        if !queues.contains_key(&source.domain) {
+            domains.push(source.domain.clone());
+        }
+        queues.entry(source.domain.clone()).or_default().push_back(source);
+    }
+
+    let mut interleaved = Vec::new();
+    loop {
+        let mut progressed = false;
+        for domain in &domains {
+            if let Some(source) = queues.get_mut(domain).and_then(|q| q.pop_front()) {
+                interleaved.push(source);
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    interleaved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(source_id: i64, domain: &str) -> SyncSourceInfo {
+        SyncSourceInfo {
+            source_id,
+            manga_id: source_id,
+            user_id: 1,
+            manga_name: format!("Manga {}", source_id),
+            domain: domain.to_string(),
+            path: format!("/manga/{}", source_id),
+            external_manga_id: None,
+            current_chapter: None,
+            number_unread_chapter: None,
+        }
+    }
+
+    #[test]
+    fn test_interleave_by_domain() {
+        let sources = vec![
+            source(1, "a.com"),
+            source(2, "a.com"),
+            source(3, "a.com"),
+            source(4, "b.com"),
+            source(5, "c.com"),
+            source(6, "b.com"),
+        ];
+
+        let ids: Vec<i64> = interleave_by_domain(sources).iter().map(|s| s.source_id).collect();
+        assert_eq!(ids, vec![1, 4, 5, 2, 6, 3]);
+    }
+
+    #[test]
+    fn test_group_by_page() {
+        let mut shared = source(3, "a.com");
+        shared.path = "/manga/1".to_string();
+        let sources = vec![source(1, "a.com"), source(2, "a.com"), shared, source(1, "b.com")];
+
+        let pages: Vec<Vec<i64>> = group_by_page(sources)
+            .iter()
+            .map(|page| page.iter().map(|s| s.source_id).collect())
+            .collect();
+        assert_eq!(pages, vec![vec![1, 3], vec![2], vec![1]]);
+    }
+}
@@ -0,0 +1,384 @@
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::{Client, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::sync::metadata::{self, MangaMetadata, MADARA_SELECTORS};
+use crate::sync::strategy::{element_title, ChapterLink, SyncError, SyncResult, SyncStrategy};
+
+const DEFAULT_CHAPTER_URL_TEMPLATE: &str = "{base_url}{path}";
+const DEFAULT_CHAPTER_ATTRIBUTE: &str = "href";
+
+/// Scraping configuration stored on a `website` row.
+///
+/// `chapter_url_template` supports the `{base_url}`, `{path}` and
+/// `{external_id}` placeholders and defaults to `{base_url}{path}`.
+/// `external_id_regex` is matched against the manga page and its first
+/// capture group (or the whole match) is stored as the external id.
+/// `base_url` and the chapter URLs must be on the website's domain or one of
+/// its subdomains, so a configuration cannot send requests to other hosts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ScraperConfig {
+    pub base_url: Option<String>,
+    pub chapter_url_template: Option<String>,
+    pub chapter_selector: Option<String>,
+    pub chapter_attribute: Option<String>,
+    pub external_id_regex: Option<String>,
+}
+
+impl ScraperConfig {
+    pub fn is_empty(&self) -> bool {
+        self.base_url.is_none()
+            && self.chapter_url_template.is_none()
+            && self.chapter_selector.is_none()
+            && self.chapter_attribute.is_none()
+            && self.external_id_regex.is_none()
+    }
+}
+
+/// Strategy driven entirely by a [`ScraperConfig`], for sites that follow a
+/// common layout (e.g. Madara/WordPress themes) and need no custom code.
+pub struct GenericStrategy {
+    domain: String,
+    base_url: String,
+    chapter_url_template: String,
+    chapter_selector: String,
+    chapter_attribute: String,
+    external_id_regex: Option<Regex>,
+}
+
+impl GenericStrategy {
+    /// Builds the strategy, validating the selector, regex, template and hosts
+    pub fn new(domain: &str, config: &ScraperConfig) -> SyncResult<Self> {
+        let chapter_selector = config
+            .chapter_selector
+            .clone()
+            .ok_or_else(|| SyncError::ParseError("chapter_selector is required".to_string()))?;
+
+        Selector::parse(&chapter_selector)
+            .map_err(|e| SyncError::ParseError(format!("Invalid selector: {:?}", e)))?;
+
+        let external_id_regex = match &config.external_id_regex {
+            Some(pattern) => Some(
+                Regex::new(pattern)
+                    .map_err(|e| SyncError::ParseError(format!("Invalid external_id_regex: {}", e)))?,
+            ),
+            None => None,
+        };
+
+        let chapter_url_template = config
+            .chapter_url_template
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CHAPTER_URL_TEMPLATE.to_string());
+
+        if chapter_url_template.contains("{external_id}") && external_id_regex.is_none() {
+            return Err(SyncError::ParseError(
+                "chapter_url_template uses {external_id} but no external_id_regex is set".to_string(),
+            ));
+        }
+
+        let base_url = config
+            .base_url
+            .clone()
+            .unwrap_or_else(|| format!("https://{}", domain))
+            .trim_end_matches('/')
+            .to_string();
+
+        check_host(domain, &base_url)?;
+        // The placeholders are checked with sample values, and again with the
+        // real ones whenever a URL is built
+        let sample_url = chapter_url_template
+            .replace("{base_url}", &base_url)
+            .replace("{path}", "/")
+            .replace("{external_id}", "0");
+        check_host(domain, &sample_url)?;
+
+        Ok(Self {
+            domain: domain.to_string(),
+            base_url,
+            chapter_url_template,
+            chapter_selector,
+            chapter_attribute: config
+                .chapter_attribute
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CHAPTER_ATTRIBUTE.to_string()),
+            external_id_regex,
+        })
+    }
+
+    fn chapter_url(&self, path: &str, external_id: Option<&str>) -> SyncResult<String> {
+        let mut url = self
+            .chapter_url_template
+            .replace("{base_url}", &self.base_url)
+            .replace("{path}", path);
+
+        if url.contains("{external_id}") {
+            let external_id = external_id.ok_or_else(|| {
+                SyncError::ParseError(format!("external_manga_id is required for {}", self.domain))
+            })?;
+            url = url.replace("{external_id}", external_id);
+        }
+
+        check_host(&self.domain, &url)?;
+        Ok(url)
+    }
+
+    fn page_url(&self, path: &str) -> SyncResult<String> {
+        let url = format!("{}{}", self.base_url, path);
+        check_host(&self.domain, &url)?;
+        Ok(url)
+    }
+
+    fn parse_chapters(&self, html: &str) -> SyncResult<Vec<ChapterLink>> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(&self.chapter_selector)
+            .map_err(|e| SyncError::ParseError(format!("Invalid selector: {:?}", e)))?;
+
+        let chapters: Vec<ChapterLink> = document
+            .select(&selector)
+            .filter_map(|element| {
+                element
+                    .value()
+                    .attr(&self.chapter_attribute)
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| ChapterLink {
+                        href: value.to_string(),
+                        title: element_title(&element),
+                    })
+            })
+            .collect();
+
+        if chapters.is_empty() {
+            return Err(SyncError::ParseError(
+                "No chapters found on page".to_string(),
+            ));
+        }
+
+        Ok(chapters)
+    }
+}
+
+#[async_trait]
+impl SyncStrategy for GenericStrategy {
+    fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    async fn fetch_chapters(
+        &self,
+        client: &Client,
+        path: &str,
+        external_id: Option<&str>,
+    ) -> SyncResult<Vec<ChapterLink>> {
+        let url = self.chapter_url(path, external_id)?;
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| SyncError::HttpError(e.to_string()))?;
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| SyncError::HttpError(e.to_string()))?;
+
+        self.parse_chapters(&html)
+    }
+
+    async fn extract_external_id(
+        &self,
+        client: &Client,
+        path: &str,
+    ) -> SyncResult<Option<String>> {
+        let re = match &self.external_id_regex {
+            Some(re) => re,
+            None => return Ok(None),
+        };
+
+        let url = self.page_url(path)?;
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| SyncError::HttpError(e.to_string()))?;
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| SyncError::HttpError(e.to_string()))?;
+
+        if let Some(captures) = re.captures(&html) {
+            let id = captures.get(1).or_else(|| captures.get(0));
+            if let Some(id) = id {
+                return Ok(Some(id.as_str().to_string()));
+            }
+        }
+
+        Err(SyncError::ParseError(
+            "Could not find external id in page".to_string(),
+        ))
+    }
+
+    /// Reads the page with the Madara layout, falling back to its OpenGraph tags
+    async fn fetch_metadata(
+        &self,
+        client: &Client,
+        path: &str,
+    ) -> SyncResult<Option<MangaMetadata>> {
+        let url = self.page_url(path)?;
+        metadata::fetch_metadata(client, &url, &MADARA_SELECTORS).await.map(Some)
+    }
+}
+
+/// Rejects URLs that are not http(s) on `domain` or one of its subdomains.
+/// A leading `www.` of the domain is ignored, so `www.example.org` also
+/// accepts `example.org` and `api.example.org`.
+fn check_host(domain: &str, url: &str) -> SyncResult<()> {
+    let parsed = Url::parse(url).map_err(|e| SyncError::ParseError(format!("Invalid URL {}: {}", url, e)))?;
+    let site = domain.trim_start_matches("www.").to_ascii_lowercase();
+
+    let on_site = matches!(parsed.scheme(), "http" | "https")
+        && parsed
+            .host_str()
+            .is_some_and(|host| host == site || host.ends_with(&format!(".{}", site)));
+
+    if !on_site {
+        return Err(SyncError::ParseError(format!("{} is not on {}", url, domain)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn madara_config() -> ScraperConfig {
+        ScraperConfig {
+            chapter_selector: Some("li.wp-manga-chapter > a".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chapter_url_defaults() {
+        let strategy = GenericStrategy::new("example.org", &madara_config()).unwrap();
+
+        assert_eq!(strategy.domain(), "example.org");
+        assert_eq!(
+            strategy.chapter_url("/manga/test/", None).unwrap(),
+            "https://example.org/manga/test/"
+        );
+    }
+
+    #[test]
+    fn test_chapter_url_with_external_id() {
+        let config = ScraperConfig {
+            base_url: Some("https://api.example.org/".to_string()),
+            chapter_url_template: Some("{base_url}/book/{external_id}/chapters".to_string()),
+            external_id_regex: Some(r"bookId\s*=\s*(\d+)".to_string()),
+            ..madara_config()
+        };
+        let strategy = GenericStrategy::new("example.org", &config).unwrap();
+
+        assert_eq!(
+            strategy.chapter_url("/manga/test", Some("42")).unwrap(),
+            "https://api.example.org/book/42/chapters"
+        );
+        assert!(strategy.chapter_url("/manga/test", None).is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(GenericStrategy::new("example.org", &ScraperConfig::default()).is_err());
+
+        let bad_selector = ScraperConfig {
+            chapter_selector: Some("li >> a[".to_string()),
+            ..Default::default()
+        };
+        assert!(GenericStrategy::new("example.org", &bad_selector).is_err());
+
+        let bad_regex = ScraperConfig {
+            external_id_regex: Some("(unclosed".to_string()),
+            ..madara_config()
+        };
+        assert!(GenericStrategy::new("example.org", &bad_regex).is_err());
+
+        let missing_regex = ScraperConfig {
+            chapter_url_template: Some("{base_url}/api/{external_id}".to_string()),
+            ..madara_config()
+        };
+        assert!(GenericStrategy::new("example.org", &missing_regex).is_err());
+    }
+
+    #[test]
+    fn test_urls_must_stay_on_the_domain() {
+        for base_url in ["http://169.254.169.254", "https://example.org.evil.com", "https://evilexample.org", "file:///etc"] {
+            let config = ScraperConfig {
+                base_url: Some(base_url.to_string()),
+                ..madara_config()
+            };
+            assert!(GenericStrategy::new("example.org", &config).is_err(), "{}", base_url);
+        }
+
+        for template in ["http://127.0.0.1{path}", "{base_url}@evil.com{path}"] {
+            let config = ScraperConfig {
+                chapter_url_template: Some(template.to_string()),
+                ..madara_config()
+            };
+            assert!(GenericStrategy::new("example.org", &config).is_err(), "{}", template);
+        }
+
+        let config = ScraperConfig {
+            base_url: Some("https://mangaread.org".to_string()),
+            ..madara_config()
+        };
+        assert!(GenericStrategy::new("www.mangaread.org", &config).is_ok());
+
+        // Source paths are checked when the URL is built
+        let strategy = GenericStrategy::new("example.org", &madara_config()).unwrap();
+        assert!(strategy.chapter_url("@evil.com/manga/test", None).is_err());
+        assert!(strategy.page_url("@evil.com/manga/test").is_err());
+    }
+
+    #[test]
+    fn test_parse_chapters() {
+        let strategy = GenericStrategy::new("example.org", &madara_config()).unwrap();
+
+        let html = r#"
+            <ul>
+                <li class="wp-manga-chapter"><a href="https://example.org/manga/test/chapter-2/">Chapter 2</a></li>
+                <li class="wp-manga-chapter"><a href="https://example.org/manga/test/chapter-1/">Chapter 1</a></li>
+                <li class="other"><a href="https://example.org/unrelated/">Other</a></li>
+            </ul>
+        "#;
+
+        let chapters = strategy.parse_chapters(html).unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].href, "https://example.org/manga/test/chapter-2/");
+        assert_eq!(chapters[0].title.as_deref(), Some("Chapter 2"));
+        assert_eq!(strategy.count_new_chapters(&chapters, "chapter-1").unwrap(), 1);
+
+        assert!(strategy.parse_chapters("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_chapters_custom_attribute() {
+        let config = ScraperConfig {
+            chapter_selector: Some("#chapter-list option".to_string()),
+            chapter_attribute: Some("value".to_string()),
+            ..Default::default()
+        };
+        let strategy = GenericStrategy::new("example.org", &config).unwrap();
+
+        let html = r#"<select id="chapter-list"><option value="/test/chapter-3">3</option></select>"#;
+
+        let chapters = strategy.parse_chapters(html).unwrap();
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].href, "/test/chapter-3");
+    }
+}
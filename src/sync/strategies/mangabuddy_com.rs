@@ -3,7 +3,18 @@ use regex::Regex;
 use reqwest::Client;
 use scraper::{Html, Selector};
 
-use crate::sync::strategy::{ChapterLink, SyncError, SyncResult, SyncStrategy};
+use crate::sync::metadata::{self, MangaMetadata, MetadataSelectors};
+use crate::sync::strategy::{element_title, ChapterLink, SyncError, SyncResult, SyncStrategy};
+
+const SELECTORS: MetadataSelectors = MetadataSelectors {
+    title: ".detail .name h1",
+    alt_titles: Some(".detail .name h2"),
+    cover: "#cover img",
+    authors: ".detail .meta a[href*='/authors/']",
+    genres: ".detail .meta a[href*='/genres/']",
+    description: ".summary .content",
+    info_rows: ".detail .meta p",
+};
 
 pub struct WebsiteMangabuddyCom;
 
@@ -21,7 +32,7 @@ impl Default for WebsiteMangabuddyCom {
 
 #[async_trait]
 impl SyncStrategy for WebsiteMangabuddyCom {
-    fn domain(&self) -> &'static str {
+    fn domain(&self) -> &str {
         "mangabuddy.com"
     }
 
@@ -62,6 +73,7 @@ impl SyncStrategy for WebsiteMangabuddyCom {
             .filter_map(|element| {
                 element.value().attr("value").map(|value| ChapterLink {
                     href: value.to_string(),
+                    title: element_title(&element),
                 })
             })
             .collect();
@@ -96,16 +108,23 @@ impl SyncStrategy for WebsiteMangabuddyCom {
         let re = Regex::new(r"var\s+bookId\s*=\s*(\d+);")
             .map_err(|e| SyncError::ParseError(e.to_string()))?;
 
-        if let Some(captures) = re.captures(&html) {
-            if let Some(book_id) = captures.get(1) {
-                return Ok(Some(book_id.as_str().to_string()));
-            }
+        if let Some(book_id) = re.captures(&html).and_then(|captures| captures.get(1)) {
+            return Ok(Some(book_id.as_str().to_string()));
         }
 
         Err(SyncError::ParseError(
             "Could not find bookId in page".to_string(),
         ))
     }
+
+    async fn fetch_metadata(
+        &self,
+        client: &Client,
+        path: &str,
+    ) -> SyncResult<Option<MangaMetadata>> {
+        let url = format!("https://mangabuddy.com{}", path);
+        metadata::fetch_metadata(client, &url, &SELECTORS).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -119,15 +138,19 @@ mod tests {
         let chapters = vec![
             ChapterLink {
                 href: "/solo-leveling/chapter-200".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "/solo-leveling/chapter-199".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "/solo-leveling/chapter-198".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "/solo-leveling/chapter-1".to_string(),
+                title: None,
             },
         ];
 
@@ -152,9 +175,11 @@ mod tests {
         let chapters = vec![
             ChapterLink {
                 href: "/solo-leveling/chapter-2".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "/solo-leveling/chapter-1".to_string(),
+                title: None,
             },
         ];
 
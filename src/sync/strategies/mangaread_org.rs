@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use reqwest::Client;
 use scraper::{Html, Selector};
 
-use crate::sync::strategy::{ChapterLink, SyncError, SyncResult, SyncStrategy};
+use crate::sync::metadata::{self, MangaMetadata, MADARA_SELECTORS};
+use crate::sync::strategy::{element_title, ChapterLink, SyncError, SyncResult, SyncStrategy};
 
 pub struct WebsiteMangareadOrg;
 
@@ -20,7 +21,7 @@ impl Default for WebsiteMangareadOrg {
 
 #[async_trait]
 impl SyncStrategy for WebsiteMangareadOrg {
-    fn domain(&self) -> &'static str {
+    fn domain(&self) -> &str {
         "www.mangaread.org"
     }
 
@@ -52,6 +53,7 @@ impl SyncStrategy for WebsiteMangareadOrg {
             .filter_map(|element| {
                 element.value().attr("href").map(|href| ChapterLink {
                     href: href.to_string(),
+                    title: element_title(&element),
                 })
             })
             .collect();
@@ -72,6 +74,15 @@ impl SyncStrategy for WebsiteMangareadOrg {
     ) -> SyncResult<Option<String>> {
         Ok(None)
     }
+
+    async fn fetch_metadata(
+        &self,
+        client: &Client,
+        path: &str,
+    ) -> SyncResult<Option<MangaMetadata>> {
+        let url = format!("https://www.mangaread.org{}", path);
+        metadata::fetch_metadata(client, &url, &MADARA_SELECTORS).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -85,18 +96,23 @@ mod tests {
         let chapters = vec![
             ChapterLink {
                 href: "https://example.com/manga/chapter-5/".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "https://example.com/manga/chapter-4/".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "https://example.com/manga/chapter-3/".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "https://example.com/manga/chapter-2/".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "https://example.com/manga/chapter-1/".to_string(),
+                title: None,
             },
         ];
 
@@ -112,9 +128,11 @@ mod tests {
         let chapters = vec![
             ChapterLink {
                 href: "https://example.com/manga/chapter-2/".to_string(),
+                title: None,
             },
             ChapterLink {
                 href: "https://example.com/manga/chapter-1/".to_string(),
+                title: None,
             },
         ];
 
@@ -1,37 +1,90 @@
+pub mod generic;
 pub mod mangabuddy_com;
 pub mod mangaread_org;
 
+use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::sync::strategy::SyncStrategy;
 
+pub use generic::{GenericStrategy, ScraperConfig};
 pub use mangabuddy_com::WebsiteMangabuddyCom;
 pub use mangaread_org::WebsiteMangareadOrg;
 
 pub struct StrategyRegistry {
-    strategies: HashMap<&'static str, Arc<dyn SyncStrategy>>,
+    strategies: HashMap<String, Arc<dyn SyncStrategy>>,
 }
 
 impl StrategyRegistry {
+    /// Registry containing only the built-in strategies
     pub fn new() -> Self {
-        let mut strategies: HashMap<&'static str, Arc<dyn SyncStrategy>> = HashMap::new();
+        let mut registry = Self {
+            strategies: HashMap::new(),
+        };
 
-        let mangaread = Arc::new(WebsiteMangareadOrg::new());
-        strategies.insert(mangaread.domain(), mangaread);
+        registry.register(Arc::new(WebsiteMangareadOrg::new()));
+        registry.register(Arc::new(WebsiteMangabuddyCom::new()));
 
-        let mangabuddy = Arc::new(WebsiteMangabuddyCom::new());
-        strategies.insert(mangabuddy.domain(), mangabuddy);
+        registry
+    }
+
+    /// Registry containing the built-in strategies plus a generic strategy for
+    /// every website with a scraping configuration. A configured website
+    /// overrides the built-in strategy for the same domain.
+    pub async fn load(pool: &SqlitePool) -> Result<Self, sqlx::Error> {
+        let mut registry = Self::new();
+
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
+            r#"
+            SELECT domain, chapter_selector, base_url, chapter_url_template, chapter_attribute, external_id_regex
+            FROM website
+            WHERE chapter_selector IS NOT NULL
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for (domain, chapter_selector, base_url, chapter_url_template, chapter_attribute, external_id_regex) in rows {
+            let config = ScraperConfig {
+                base_url,
+                chapter_url_template,
+                chapter_selector: Some(chapter_selector),
+                chapter_attribute,
+                external_id_regex,
+            };
+
+            match GenericStrategy::new(&domain, &config) {
+                Ok(strategy) => registry.register(Arc::new(strategy)),
+                Err(e) => tracing::warn!("Ignoring invalid scraper config for '{}': {}", domain, e),
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Like [`Self::load`], but falls back to the built-in strategies when the
+    /// scraping configurations cannot be read
+    pub async fn load_or_default(pool: &SqlitePool) -> Self {
+        match Self::load(pool).await {
+            Ok(registry) => registry,
+            Err(e) => {
+                tracing::warn!("Failed to load scraper configs, using built-in strategies only: {}", e);
+                Self::new()
+            }
+        }
+    }
 
-        Self { strategies }
+    pub fn register(&mut self, strategy: Arc<dyn SyncStrategy>) {
+        self.strategies.insert(strategy.domain().to_string(), strategy);
     }
 
     pub fn get(&self, domain: &str) -> Option<Arc<dyn SyncStrategy>> {
         self.strategies.get(domain).cloned()
     }
 
-    pub fn supported_domains(&self) -> Vec<&'static str> {
-        self.strategies.keys().copied().collect()
+    pub fn supported_domains(&self) -> Vec<&str> {
+        self.strategies.keys().map(String::as_str).collect()
     }
 }
 
@@ -2,9 +2,20 @@ use async_trait::async_trait;
 use reqwest::Client;
 use std::fmt;
 
+use crate::sync::chapter_number::{self, ChapterNumber};
+use crate::sync::metadata::MangaMetadata;
+
 #[derive(Debug, Clone)]
 pub struct ChapterLink {
     pub href: String,
+    pub title: Option<String>,
+}
+
+/// Text content of a chapter link element, or `None` when it is blank
+pub fn element_title(element: &scraper::ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let title = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
 }
 
 #[derive(Debug)]
@@ -30,7 +41,7 @@ pub type SyncResult<T> = Result<T, SyncError>;
 
 #[async_trait]
 pub trait SyncStrategy: Send + Sync {
-    fn domain(&self) -> &'static str;
+    fn domain(&self) -> &str;
 
     async fn fetch_chapters(
         &self,
@@ -45,18 +56,46 @@ pub trait SyncStrategy: Send + Sync {
         path: &str,
     ) -> SyncResult<Option<String>>;
 
+    /// Reads the title, cover and other details from the manga page, or
+    /// returns `None` when the strategy cannot extract them
+    async fn fetch_metadata(
+        &self,
+        _client: &Client,
+        _path: &str,
+    ) -> SyncResult<Option<MangaMetadata>> {
+        Ok(None)
+    }
+
     fn count_new_chapters(
         &self,
         chapters: &[ChapterLink],
         current_chapter: &str,
     ) -> SyncResult<usize> {
-        for (index, chapter) in chapters.iter().enumerate() {
-            if chapter.href.ends_with(&format!("{}/", current_chapter))
-                || chapter.href.ends_with(current_chapter)
-            {
-                return Ok(index);
-            }
+        count_new_chapters(chapters, current_chapter)
+    }
+}
+
+/// Counts the chapters newer than `current_chapter` by comparing parsed
+/// chapter numbers, falling back to matching the href suffix when the
+/// current chapter or the listed chapters cannot be parsed
+pub fn count_new_chapters(chapters: &[ChapterLink], current_chapter: &str) -> SyncResult<usize> {
+    if let Some(current) = ChapterNumber::parse(current_chapter) {
+        let parsed: Vec<ChapterNumber> = chapters
+            .iter()
+            .filter_map(|c| ChapterNumber::parse(&c.href))
+            .collect();
+
+        if let Some(count) = chapter_number::count_after(&parsed, &current) {
+            return Ok(count);
+        }
+    }
+
+    for (index, chapter) in chapters.iter().enumerate() {
+        if chapter.href.ends_with(&format!("{}/", current_chapter))
+            || chapter.href.ends_with(current_chapter)
+        {
+            return Ok(index);
         }
-        Err(SyncError::ChapterNotFound(current_chapter.to_string()))
     }
+    Err(SyncError::ChapterNotFound(current_chapter.to_string()))
 }
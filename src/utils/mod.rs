@@ -1 +1,3 @@
+pub mod nullable;
+pub mod pagination;
 pub mod response;
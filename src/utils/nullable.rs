@@ -0,0 +1,32 @@
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes a field that can be left out, set, or cleared with `null`.
+/// Use with `#[serde(default, deserialize_with = "nullable::deserialize")]`
+/// on an `Option<Option<T>>`: a missing field is `None` and `null` is
+/// `Some(None)`.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Patch {
+        #[serde(default, deserialize_with = "super::deserialize")]
+        year: Option<Option<i64>>,
+    }
+
+    #[test]
+    fn test_missing_null_and_value() {
+        let parse = |json: &str| serde_json::from_str::<Patch>(json).unwrap().year;
+        assert_eq!(parse("{}"), None);
+        assert_eq!(parse(r#"{"year": null}"#), Some(None));
+        assert_eq!(parse(r#"{"year": 2020}"#), Some(Some(2020)));
+    }
+}
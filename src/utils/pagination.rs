@@ -0,0 +1,145 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::utils::response::{ApiError, Page};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Query of the paginated list endpoints
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    pub size: Option<i64>,
+    pub page: Option<i64>,
+    /// `next_cursor` of the previous page, to continue after its last item
+    /// instead of using `page`. Only on lists ordered by a stable key.
+    pub cursor: Option<String>,
+}
+
+/// Validates a page size and number, and returns the size and the offset
+pub fn page_bounds(size: Option<i64>, page: Option<i64>) -> Result<(i64, i64), ApiError> {
+    let size = size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(1..=MAX_PAGE_SIZE).contains(&size) {
+        return Err(ApiError::BadRequest(format!("size must be between 1 and {}", MAX_PAGE_SIZE)));
+    }
+    let page = page.unwrap_or(1);
+    if page < 1 {
+        return Err(ApiError::BadRequest("page must be at least 1".into()));
+    }
+    Ok((size, (page - 1) * size))
+}
+
+impl PageQuery {
+    /// Size and offset of the page, for lists without a cursor
+    pub fn bounds(&self) -> Result<(i64, i64), ApiError> {
+        if self.cursor.is_some() {
+            return Err(ApiError::BadRequest("This list does not support cursors".into()));
+        }
+        page_bounds(self.size, self.page)
+    }
+
+    /// Size and offset of the page, and the position decoded from the cursor
+    /// when there is one. The offset is 0 with a cursor.
+    pub fn keyset<K: DeserializeOwned>(&self) -> Result<(i64, i64, Option<K>), ApiError> {
+        let Some(cursor) = &self.cursor else {
+            let (size, offset) = page_bounds(self.size, self.page)?;
+            return Ok((size, offset, None));
+        };
+        if self.page.is_some() {
+            return Err(ApiError::BadRequest("page and cursor cannot be combined".into()));
+        }
+
+        let (size, _) = page_bounds(self.size, None)?;
+        let position = hex::decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| ApiError::BadRequest("Invalid cursor".into()))?;
+        Ok((size, 0, Some(position)))
+    }
+
+    /// Page number to report, missing when the page was requested with a cursor
+    pub fn page_number(&self) -> Option<i64> {
+        match self.cursor {
+            Some(_) => None,
+            None => Some(self.page.unwrap_or(1)),
+        }
+    }
+}
+
+/// Opaque cursor pointing after an item, from the key the list is ordered by
+pub fn encode_cursor<K: Serialize>(position: &K) -> String {
+    hex::encode(serde_json::to_vec(position).unwrap_or_default())
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, page: i64, size: i64) -> Self {
+        Page { items, total, page: Some(page), size, next_cursor: None }
+    }
+
+    /// Builds a page out of a list computed in full, for lists that are not
+    /// read from a single query
+    pub fn from_all(items: Vec<T>, page: i64, size: i64) -> Self {
+        let total = items.len() as i64;
+        let items = items.into_iter().skip(((page - 1) * size) as usize).take(size as usize).collect();
+        Page::new(items, total, page, size)
+    }
+
+    /// Builds a page from up to `size + 1` items, the extra one only telling
+    /// that there is a next page
+    pub fn keyset<K: Serialize>(
+        mut items: Vec<T>,
+        total: i64,
+        query: &PageQuery,
+        size: i64,
+        position: impl Fn(&T) -> K,
+    ) -> Self {
+        let mut next_cursor = None;
+        if items.len() as i64 > size {
+            items.truncate(size as usize);
+            next_cursor = items.last().map(|item| encode_cursor(&position(item)));
+        }
+        Page { items, total, page: query.page_number(), size, next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(page: Option<i64>, cursor: Option<String>) -> PageQuery {
+        PageQuery { size: Some(2), page, cursor }
+    }
+
+    #[test]
+    fn test_keyset_page() {
+        let page = Page::keyset(vec![5, 4, 3], 10, &query(None, None), 2, |n| *n);
+        assert_eq!(page.items, vec![5, 4]);
+        assert_eq!(page.page, Some(1));
+
+        let next = query(None, page.next_cursor.clone());
+        assert_eq!(next.keyset::<i64>().ok(), Some((2, 0, Some(4))));
+
+        let last = Page::keyset(vec![3], 10, &next, 2, |n| *n);
+        assert_eq!(last.page, None);
+        assert!(last.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_page_from_all() {
+        let page = Page::from_all(vec![1, 2, 3, 4, 5], 2, 2);
+        assert_eq!(page.items, vec![3, 4]);
+        assert_eq!(page.total, 5);
+        assert!(Page::from_all(vec![1, 2], 3, 2).items.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        assert!(query(Some(2), Some(encode_cursor(&1))).keyset::<i64>().is_err());
+        assert!(query(None, Some("zz".into())).keyset::<i64>().is_err());
+        assert!(query(None, Some(encode_cursor(&1))).bounds().is_err());
+        assert!(page_bounds(Some(0), None).is_err());
+        assert!(page_bounds(None, Some(0)).is_err());
+        assert_eq!(page_bounds(Some(10), Some(3)).ok(), Some((10, 20)));
+    }
+}
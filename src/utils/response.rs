@@ -13,6 +13,18 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
 }
 
+/// One page of a list, with the number of items on every page
+#[derive(Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    /// Missing when the page was requested with a cursor
+    pub page: Option<i64>,
+    pub size: i64,
+    /// Pass as `cursor` to get the items after this page, missing on the last page
+    pub next_cursor: Option<String>,
+}
+
 impl ApiResponse<()> {
     pub fn success_null() -> Self {
         ApiResponse {
@@ -49,15 +61,22 @@ pub enum ApiError {
     Internal(String),
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status, message) = match &self {
+impl ApiError {
+    /// Status code and message of the error response
+    pub fn parts(&self) -> (StatusCode, String) {
+        match self {
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
             ApiError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string()),
             ApiError::NotFound(m) => (StatusCode::NOT_FOUND, m.clone()),
             ApiError::BadRequest(m) => (StatusCode::BAD_REQUEST, m.clone()),
             ApiError::Internal(m) => (StatusCode::INTERNAL_SERVER_ERROR, m.clone()),
-        };
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = self.parts();
 
         let body = Json(ApiResponse::<()>::error(&message));
         (status, body).into_response()
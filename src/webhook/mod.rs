@@ -0,0 +1,327 @@
+use hmac::{Hmac, Mac};
+use reqwest::{redirect, Client, Url};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::models::{Webhook, WebhookDelivery};
+use crate::settings;
+use crate::sync::http_client;
+
+/// A source's unread count increased during a sync
+pub const EVENT_NEW_CHAPTERS: &str = "new_chapters";
+/// A sync run finished
+pub const EVENT_SYNC_FINISHED: &str = "sync_finished";
+/// Sent by `POST /webhook/{id}/test`, whatever the webhook subscribes to
+pub const EVENT_PING: &str = "ping";
+
+/// Events a webhook can subscribe to
+pub const EVENTS: &[&str] = &[EVENT_NEW_CHAPTERS, EVENT_SYNC_FINISHED];
+
+pub const SIGNATURE_HEADER: &str = "X-MangaSync-Signature";
+pub const EVENT_HEADER: &str = "X-MangaSync-Event";
+pub const DELIVERY_HEADER: &str = "X-MangaSync-Delivery";
+
+/// Upper bounds of the `WEBHOOK_MAX_ATTEMPTS` setting and of the delay
+/// between two attempts, so a delivery always finishes in bounded time
+pub const MAX_ATTEMPTS: u64 = 10;
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(3600);
+
+pub const WEBHOOK_COLUMNS: &str = "id, url, secret, secret IS NOT NULL as has_secret, events, enabled, created_at";
+
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    event: &'a str,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    data: &'a T,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewChaptersEvent {
+    pub manga_id: i64,
+    pub manga_name: String,
+    pub source_id: i64,
+    pub domain: String,
+    pub previous_unread: i64,
+    pub number_unread_chapter: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncFinishedEvent {
+    pub run_id: i64,
+    pub total_sources: usize,
+    pub success_count: usize,
+    pub error_count: usize,
+    pub new_chapters: usize,
+}
+
+/// Parses a comma-separated event list, rejecting unknown or missing events
+pub fn parse_events(events: &str) -> Result<Vec<String>, String> {
+    let parsed: Vec<String> = events
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if parsed.is_empty() {
+        return Err("At least one event is required".to_string());
+    }
+
+    if let Some(unknown) = parsed.iter().find(|e| !EVENTS.contains(&e.as_str())) {
+        return Err(format!("Unknown event: {} (expected one of {})", unknown, EVENTS.join(", ")));
+    }
+
+    Ok(parsed)
+}
+
+/// Whether webhooks may target loopback, private and link-local addresses
+pub async fn allow_private_hosts(pool: &SqlitePool) -> bool {
+    settings::get_setting_string(pool, "WEBHOOK_ALLOW_PRIVATE_HOSTS", "false")
+        .await
+        .is_ok_and(|value| value == "true")
+}
+
+/// Resolves the host of a webhook URL, rejecting non-public addresses
+/// unless `allow_private` is set
+pub async fn resolve(url: &str, allow_private: bool) -> anyhow::Result<(Url, Vec<SocketAddr>)> {
+    let url = Url::parse(url)?;
+    let addrs = http_client::resolve(&url, allow_private).await?;
+    Ok((url, addrs))
+}
+
+/// Client connecting to `host` only through `addrs`. Redirects are not
+/// followed, since their targets would escape the address check.
+fn create_client(host: &str, addrs: &[SocketAddr]) -> Client {
+    Client::builder()
+        .user_agent(concat!("manga-sync/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(10))
+        .redirect(redirect::Policy::none())
+        .resolve_to_addrs(host, addrs)
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+/// `sha256=<hex>` HMAC of the request body, sent in [`SIGNATURE_HEADER`]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Sends events to the webhooks subscribed to them, retrying failed
+/// deliveries with an exponential backoff and logging each delivery.
+/// The target is resolved again on every attempt, so a host whose DNS now
+/// points to a non-public address is rejected.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    pool: SqlitePool,
+    max_attempts: u32,
+    retry_delay: Duration,
+    allow_private: bool,
+}
+
+impl WebhookDispatcher {
+    pub async fn new(pool: SqlitePool) -> Self {
+        let max_attempts = settings::get_setting_u64(&pool, "WEBHOOK_MAX_ATTEMPTS", 3).await.unwrap_or(3);
+        let retry_delay_ms = settings::get_setting_u64(&pool, "WEBHOOK_RETRY_DELAY_MS", 5000).await.unwrap_or(5000);
+        let allow_private = allow_private_hosts(&pool).await;
+
+        Self {
+            pool,
+            max_attempts: max_attempts.clamp(1, MAX_ATTEMPTS) as u32,
+            retry_delay: Duration::from_millis(retry_delay_ms),
+            allow_private,
+        }
+    }
+
+    /// Delivers `event` in the background to every enabled webhook of
+    /// `user_id` subscribed to it
+    pub async fn dispatch<T: Serialize>(&self, user_id: i64, event: &'static str, data: &T) {
+        let webhooks = match sqlx::query_as::<sqlx::Sqlite, Webhook>(
+            &format!("SELECT {} FROM webhook WHERE enabled = 1 AND user_id = ?", WEBHOOK_COLUMNS)
+        )
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+        {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::warn!("Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+
+        let subscribed: Vec<Webhook> = webhooks
+            .into_iter()
+            .filter(|w| w.events.split(',').any(|e| e.trim() == event))
+            .collect();
+
+        if subscribed.is_empty() {
+            return;
+        }
+
+        let payload = match serialize(event, data) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!("Failed to serialize {} webhook payload: {}", event, e);
+                return;
+            }
+        };
+
+        for webhook in subscribed {
+            let dispatcher = self.clone();
+            let payload = payload.clone();
+            tokio::spawn(async move {
+                if let Err(e) = dispatcher.deliver(&webhook, event, payload, dispatcher.max_attempts).await {
+                    tracing::warn!("Failed to log delivery to webhook #{}: {}", webhook.id, e);
+                }
+            });
+        }
+    }
+
+    /// Sends a ping to a single webhook and waits for the delivery to finish.
+    /// It is attempted once, since the caller waits for it.
+    pub async fn ping(&self, webhook: &Webhook) -> Result<WebhookDelivery, sqlx::Error> {
+        let data = serde_json::json!({ "webhook_id": webhook.id });
+        let payload = serialize(EVENT_PING, &data).expect("ping payload is valid JSON");
+        self.deliver(webhook, EVENT_PING, payload, 1).await
+    }
+
+    /// Delivers a payload in at most `max_attempts` attempts and logs it
+    async fn deliver(&self, webhook: &Webhook, event: &str, payload: String, max_attempts: u32) -> Result<WebhookDelivery, sqlx::Error> {
+        let delivery_id = sqlx::query("INSERT INTO webhook_delivery (webhook_id, event, payload) VALUES (?, ?, ?)")
+            .bind(webhook.id)
+            .bind(event)
+            .bind(&payload)
+            .execute(&self.pool)
+            .await?
+            .last_insert_rowid();
+
+        let mut attempts = 0;
+        let mut status_code: Option<u16> = None;
+        let mut error: Option<String> = None;
+
+        while attempts < max_attempts {
+            if attempts > 0 {
+                tokio::time::sleep(backoff(self.retry_delay, attempts)).await;
+            }
+            attempts += 1;
+
+            match self.send(webhook, event, delivery_id, &payload).await {
+                Ok(response) if response.status().is_success() => {
+                    status_code = Some(response.status().as_u16());
+                    error = None;
+                    break;
+                }
+                Ok(response) => {
+                    status_code = Some(response.status().as_u16());
+                    error = Some(format!("Unexpected status {}", response.status()));
+                }
+                Err(e) => {
+                    status_code = None;
+                    error = Some(e.to_string());
+                }
+            }
+
+            tracing::warn!(
+                "Webhook #{} delivery #{} attempt {}/{} failed: {}",
+                webhook.id,
+                delivery_id,
+                attempts,
+                max_attempts,
+                error.as_deref().unwrap_or_default()
+            );
+        }
+
+        sqlx::query(
+            "UPDATE webhook_delivery SET status_code = ?, success = ?, attempts = ?, error = ?, finished_at = CURRENT_TIMESTAMP
+            WHERE id = ?"
+        )
+            .bind(status_code.map(i64::from))
+            .bind(error.is_none())
+            .bind(attempts as i64)
+            .bind(&error)
+            .bind(delivery_id)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query_as::<sqlx::Sqlite, WebhookDelivery>(
+            "SELECT id, webhook_id, event, payload, status_code, success, attempts, error, created_at, finished_at
+            FROM webhook_delivery WHERE id = ?"
+        )
+            .bind(delivery_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Makes one attempt at a delivery, to the addresses the webhook's host
+    /// resolves to now
+    async fn send(&self, webhook: &Webhook, event: &str, delivery_id: i64, payload: &str) -> anyhow::Result<reqwest::Response> {
+        let (url, addrs) = resolve(&webhook.url, self.allow_private).await?;
+
+        let mut request = create_client(url.host_str().unwrap_or_default(), &addrs)
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header(EVENT_HEADER, event)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .body(payload.to_string());
+
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, payload.as_bytes()));
+        }
+
+        Ok(request.send().await?)
+    }
+}
+
+fn serialize<T: Serialize>(event: &str, data: &T) -> serde_json::Result<String> {
+    serde_json::to_string(&Envelope {
+        event,
+        timestamp: chrono::Utc::now(),
+        data,
+    })
+}
+
+/// Delay before the attempt following the `attempts` first ones: the retry
+/// delay doubled on every retry, up to [`MAX_RETRY_DELAY`]
+fn backoff(retry_delay: Duration, attempts: u32) -> Duration {
+    2u32.checked_pow(attempts.saturating_sub(1))
+        .map_or(MAX_RETRY_DELAY, |factor| retry_delay.saturating_mul(factor))
+        .min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // Reference value from `echo -n 'hello' | openssl dgst -sha256 -hmac secret`
+        assert_eq!(
+            sign("secret", b"hello"),
+            "sha256=88aab3ede8d3adf94d26ab90d3bafd4a2083070c3bcce9c014ee04a443847c0b"
+        );
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(
+            parse_events("new_chapters, sync_finished").unwrap(),
+            vec!["new_chapters".to_string(), "sync_finished".to_string()]
+        );
+        assert!(parse_events("").is_err());
+        assert!(parse_events("new_chapters,unknown").is_err());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let delay = Duration::from_secs(5);
+        assert_eq!(backoff(delay, 1), delay);
+        assert_eq!(backoff(delay, 3), Duration::from_secs(20));
+        assert_eq!(backoff(delay, 40), MAX_RETRY_DELAY);
+        assert_eq!(backoff(Duration::from_millis(u64::MAX), 2), MAX_RETRY_DELAY);
+    }
+}
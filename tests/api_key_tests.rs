@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        middleware,
+        Router,
+        routing::{delete, get},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::auth::middleware::auth_middleware;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+
+    async fn setup_app() -> (Router, SqlitePool, String) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_api_key.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+        let legacy_key = km.refresh_key().unwrap();
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga", get(handlers::manga::list_manga).post(handlers::manga::create_manga))
+            .route("/setting", get(handlers::setting::list_settings))
+            .route("/key/api", get(handlers::key::list_api_keys).post(handlers::key::create_api_key))
+            .route("/key/api/{id}", delete(handlers::key::revoke_api_key))
+            .route("/website/{domain}", get(handlers::website::check_website).delete(handlers::website::delete_website))
+            .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
+            .with_state(state);
+
+        (app, pool, legacy_key)
+    }
+
+    async fn send(app: &Router, method: &str, uri: &str, token: &str, body: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            builder = builder.header("content-type", "application/json");
+        }
+        let request = builder
+            .body(body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty))
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    async fn create_key(app: &Router, admin: &str, name: &str, scope: &str) -> (i64, String) {
+        let body = format!(r#"{{"name": "{}", "scope": "{}"}}"#, name, scope);
+        let (status, json) = send(app, "POST", "/key/api", admin, Some(&body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["name"], name);
+        assert_eq!(json["data"]["scope"], scope);
+        (json["data"]["id"].as_i64().unwrap(), json["data"]["key"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_api_key_scopes() {
+        let (app, _pool, legacy_key) = setup_app().await;
+
+        let (_, read_key) = create_key(&app, &legacy_key, "phone", "read").await;
+        let (_, write_key) = create_key(&app, &legacy_key, "tablet", "write").await;
+        let (_, admin_key) = create_key(&app, &legacy_key, "browser-extension", "admin").await;
+
+        let manga = Some(r#"{"name": "Test Manga", "cover": "c.jpg", "cover_small": "cs.jpg"}"#);
+
+        // Read keys can only GET
+        assert_eq!(send(&app, "GET", "/manga", &read_key, None).await.0, StatusCode::OK);
+        assert_eq!(send(&app, "POST", "/manga", &read_key, manga).await.0, StatusCode::FORBIDDEN);
+
+        // Write keys cannot manage keys or settings
+        assert_eq!(send(&app, "POST", "/manga", &write_key, manga).await.0, StatusCode::OK);
+        assert_eq!(send(&app, "GET", "/setting", &write_key, None).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(send(&app, "GET", "/key/api", &write_key, None).await.0, StatusCode::FORBIDDEN);
+
+        // Websites are shared by all users, so only admin keys can change them
+        assert_eq!(send(&app, "DELETE", "/website/example.com", &write_key, None).await.0, StatusCode::FORBIDDEN);
+
+        // Admin keys can do everything
+        assert_eq!(send(&app, "GET", "/setting", &admin_key, None).await.0, StatusCode::OK);
+        let (status, json) = send(&app, "GET", "/key/api", &admin_key, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["items"].as_array().unwrap().len(), 3);
+        assert!(json["data"]["items"][0].get("key").is_none());
+        assert!(json["data"]["items"][0].get("key_hash").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_api_key_revocation_and_usage() {
+        let (app, _pool, legacy_key) = setup_app().await;
+
+        let (id, key) = create_key(&app, &legacy_key, "phone", "read").await;
+
+        let (_, json) = send(&app, "GET", "/key/api", &legacy_key, None).await;
+        assert!(json["data"]["items"][0]["last_used_at"].is_null());
+
+        assert_eq!(send(&app, "GET", "/manga", &key, None).await.0, StatusCode::OK);
+
+        let (_, json) = send(&app, "GET", "/key/api", &legacy_key, None).await;
+        assert!(!json["data"]["items"][0]["last_used_at"].is_null());
+
+        // Names are unique among active keys only
+        let body = Some(r#"{"name": "phone", "scope": "write"}"#);
+        assert_eq!(send(&app, "POST", "/key/api", &legacy_key, body).await.0, StatusCode::BAD_REQUEST);
+
+        let uri = format!("/key/api/{}", id);
+        assert_eq!(send(&app, "DELETE", &uri, &legacy_key, None).await.0, StatusCode::OK);
+        assert_eq!(send(&app, "DELETE", &uri, &legacy_key, None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(send(&app, "GET", "/manga", &key, None).await.0, StatusCode::FORBIDDEN);
+
+        let (_, json) = send(&app, "GET", "/key/api", &legacy_key, None).await;
+        assert!(!json["data"]["items"][0]["revoked_at"].is_null());
+
+        create_key(&app, &legacy_key, "phone", "write").await;
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::backup;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("manga-sync-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn setup_app_no_auth(backup_dir: &Path) -> (Router, SqlitePool) {
+        // `VACUUM INTO` from an in-memory database writes an in-memory copy
+        let options = SqliteConnectOptions::new()
+            .filename(backup_dir.join("source.db"))
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("UPDATE setting SET value = ? WHERE key = 'BACKUP_DIR'")
+            .bind(backup_dir.to_string_lossy().into_owned())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE setting SET value = '2' WHERE key = 'BACKUP_RETENTION'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let key_path = "test_key_backup.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/backup", get(handlers::backup::list_backups).post(handlers::backup::create_backup))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn request(app: &Router, method: &str) -> serde_json::Value {
+        let response = app.clone()
+            .oneshot(Request::builder().method(method).uri("/backup").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backup_snapshots_and_retention() {
+        let dir = temp_dir("backups");
+        let (app, pool) = setup_app_no_auth(&dir).await;
+        sqlx::query("INSERT INTO manga (name, cover, cover_small) VALUES ('Test Manga', 'cover.jpg', 'cover_small.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut names = Vec::new();
+        for _ in 0..3 {
+            let json = request(&app, "POST").await;
+            names.push(json["data"]["name"].as_str().unwrap().to_string());
+        }
+        assert!(names[0].starts_with("manga-") && names[0].ends_with(".db"));
+
+        // Only the two most recent snapshots are kept
+        let json = request(&app, "GET").await;
+        let listed: Vec<&str> = json["data"]["items"].as_array().unwrap().iter().map(|b| b["name"].as_str().unwrap()).collect();
+        assert_eq!(listed.len(), 2);
+        assert!(!listed.contains(&names[0].as_str()));
+
+        let snapshot = SqlitePool::connect_with(SqliteConnectOptions::new().filename(dir.join(&names[2])))
+            .await
+            .unwrap();
+        let name: String = sqlx::query_scalar("SELECT name FROM manga").fetch_one(&snapshot).await.unwrap();
+        assert_eq!(name, "Test Manga");
+        snapshot.close().await;
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_restore_pending() {
+        let dir = temp_dir("restore");
+        let db_path = dir.join("manga.db");
+
+        // Nothing to restore
+        assert!(!backup::restore_pending(&db_path).await.unwrap());
+
+        std::fs::write(&db_path, b"current").unwrap();
+
+        // A file that is not a database is refused and the current one kept
+        std::fs::write(dir.join(backup::RESTORE_FILE), b"not a database").unwrap();
+        assert!(backup::restore_pending(&db_path).await.is_err());
+        assert_eq!(std::fs::read(&db_path).unwrap(), b"current");
+
+        std::fs::remove_file(dir.join(backup::RESTORE_FILE)).unwrap();
+        let (_, pool) = setup_app_no_auth(&dir).await;
+        sqlx::query("VACUUM INTO ?")
+            .bind(dir.join(backup::RESTORE_FILE).to_string_lossy().into_owned())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(backup::restore_pending(&db_path).await.unwrap());
+        assert!(!dir.join(backup::RESTORE_FILE).exists());
+        let kept = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().ends_with(".bak"))
+            .count();
+        assert_eq!(kept, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
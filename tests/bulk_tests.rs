@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+    use serde_json::json;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for query in [
+            "INSERT INTO website (id, domain) VALUES (1, 'example.com')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Berserk', 'c', 'c')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (2, 'Vagabond', 'c', 'c')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (3, 'Monster', 'c', 'c')",
+            "INSERT INTO category (id, user_id, name, position) VALUES (1, 1, 'Favorites', 0)",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        let key_path = "test_key_bulk.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/bulk", post(handlers::bulk::bulk_manga))
+            .route("/manga/{id}", get(handlers::manga::get_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn request(app: &Router, method: &str, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_bulk_reports_each_operation() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let (status, json) = request(&app, "POST", "/manga/bulk", Some(json!({
+            "atomic": false,
+            "operations": [
+                {"op": "update", "manga_id": 1, "chapter_number": "12", "status": "completed"},
+                {"op": "add_category", "manga_id": 1, "category_id": 1},
+                {"op": "add_source", "manga_id": 2, "website_id": 1, "path": "/vagabond/"},
+                {"op": "delete", "manga_id": 3},
+                // Renames the manga before failing on the domain, which must be undone
+                {"op": "update", "manga_id": 2, "name": "Renamed", "website_domain": "unknown.com"},
+                {"op": "update", "manga_id": 2, "year": 12},
+                {"op": "add_source", "manga_id": 2, "website_id": 1, "path": "/vagabond"},
+                {"op": "delete", "manga_id": 99},
+            ]
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let report = &json["data"];
+        assert_eq!(report["committed"], true);
+        assert_eq!(report["succeeded"], 4);
+        assert_eq!(report["failed"], 4);
+        let statuses: Vec<i64> = report["results"].as_array().unwrap().iter().map(|r| r["status"].as_i64().unwrap()).collect();
+        assert_eq!(statuses, vec![200, 200, 200, 200, 400, 400, 400, 404]);
+        assert_eq!(report["results"][4]["error"], "Website domain does not exist");
+        assert_eq!(report["results"][5]["error"], "Invalid year");
+        assert_eq!(report["results"][7]["manga_id"], 99);
+
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["current_chapter"], "12");
+        assert_eq!(json["data"]["status"], "completed");
+        assert_eq!(json["data"]["reading_status"], "reading");
+        assert_eq!(json["data"]["categories"], json!(["Favorites"]));
+
+        let (_, json) = request(&app, "GET", "/manga/2", None).await;
+        assert_eq!(json["data"]["name"], "Vagabond");
+        let path: String = sqlx::query_scalar("SELECT path FROM source WHERE manga_id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(path, "/vagabond");
+
+        assert_eq!(request(&app, "GET", "/manga/3", None).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_atomic_bulk_saves_nothing_on_failure() {
+        let (app, _) = setup_app_no_auth().await;
+
+        // Requests are atomic unless told otherwise
+        let (status, json) = request(&app, "POST", "/manga/bulk", Some(json!({
+            "operations": [
+                {"op": "delete", "manga_id": 1},
+                {"op": "remove_category", "manga_id": 2, "category_id": 42},
+            ]
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["committed"], false);
+        assert_eq!(json["data"]["results"][0]["success"], true);
+        assert_eq!(json["data"]["results"][1]["error"], "Category not found");
+        assert_eq!(request(&app, "GET", "/manga/1", None).await.0, StatusCode::OK);
+
+        let (status, _) = request(&app, "POST", "/manga/bulk", Some(json!({"operations": []}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = request(&app, "POST", "/manga/bulk", Some(json!({
+            "operations": [{"op": "archive", "manga_id": 1}]
+        }))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::{Query, State},
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::{get, patch, put},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::handlers::manga::Pagination;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth() -> (Router, AppState) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for name in ["Solo Leveling", "Berserk"] {
+            sqlx::query("INSERT INTO manga (name, cover, cover_small) VALUES (?, 'cover.jpg', 'cover.jpg')")
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let key_path = "test_key_category.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool,
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/{id}", get(handlers::manga::get_manga))
+            .route("/category", get(handlers::category::list_categories).post(handlers::category::create_category))
+            .route("/category/{id}", patch(handlers::category::update_category).delete(handlers::category::delete_category))
+            .route("/category/{id}/manga/{manga_id}", put(handlers::category::add_category_manga).delete(handlers::category::remove_category_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state.clone());
+
+        (app, state)
+    }
+
+    async fn request(app: &Router, method: &str, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    async fn category_names(app: &Router) -> Vec<String> {
+        let (_, json) = request(app, "GET", "/category", None).await;
+        json["data"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    async fn list_names(state: &AppState, category: &str) -> Vec<String> {
+        let pagination = Pagination {
+            size: None,
+            page: None,
+            filter: Some(vec![HashMap::from([("CATEGORY".to_string(), category.to_string())])]),
+        };
+        let response = handlers::manga::list_manga(State(state.clone()), Extension(AuthContext::legacy()), Query(pagination))
+            .await
+            .unwrap_or_else(|_| panic!("listing {} failed", category));
+        response.0.data.unwrap().items.into_iter().map(|m| m.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_category_crud_and_ordering() {
+        let (app, _) = setup_app_no_auth().await;
+
+        for name in ["Reading", "Plan to read", "Dropped"] {
+            let (status, _) = request(&app, "POST", "/category", Some(serde_json::json!({"name": name}))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (status, json) = request(&app, "POST", "/category", Some(serde_json::json!({"name": "Favourites", "position": 0}))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["position"], 0);
+        assert_eq!(category_names(&app).await, vec!["Favourites", "Reading", "Plan to read", "Dropped"]);
+
+        let (status, _) = request(&app, "POST", "/category", Some(serde_json::json!({"name": "reading"}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = request(&app, "POST", "/category", Some(serde_json::json!({"name": "  "}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Moving "Dropped" (id 3) up shifts the categories in between down
+        let (status, json) = request(&app, "PATCH", "/category/3", Some(serde_json::json!({"position": 1, "name": "On hold"}))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["name"], "On hold");
+        assert_eq!(category_names(&app).await, vec!["Favourites", "On hold", "Reading", "Plan to read"]);
+
+        let (status, _) = request(&app, "DELETE", "/category/4", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, json) = request(&app, "GET", "/category", None).await;
+        let positions: Vec<i64> = json["data"]["items"].as_array().unwrap().iter().map(|c| c["position"].as_i64().unwrap()).collect();
+        assert_eq!(positions, vec![0, 1, 2]);
+
+        let (status, _) = request(&app, "DELETE", "/category/4", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = request(&app, "PATCH", "/category/4", Some(serde_json::json!({"name": "Gone"}))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_category_membership_and_filter() {
+        let (app, state) = setup_app_no_auth().await;
+
+        request(&app, "POST", "/category", Some(serde_json::json!({"name": "Reading"}))).await;
+        request(&app, "POST", "/category", Some(serde_json::json!({"name": "Favourites"}))).await;
+
+        for uri in ["/category/1/manga/1", "/category/2/manga/1", "/category/1/manga/2", "/category/1/manga/2"] {
+            let (status, _) = request(&app, "PUT", uri, None).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (status, _) = request(&app, "PUT", "/category/1/manga/99", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["categories"], serde_json::json!(["Reading", "Favourites"]));
+        let (_, json) = request(&app, "GET", "/category", None).await;
+        assert_eq!(json["data"]["items"][0]["manga_count"], 2);
+
+        assert_eq!(list_names(&state, "Reading").await.len(), 2);
+        assert_eq!(list_names(&state, "favourites").await, vec!["Solo Leveling"]);
+
+        let (status, _) = request(&app, "DELETE", "/category/2/manga/1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(list_names(&state, "Favourites").await.is_empty());
+
+        // Deleting a category keeps its manga
+        request(&app, "DELETE", "/category/1", None).await;
+        let (status, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["categories"], serde_json::json!([]));
+    }
+}
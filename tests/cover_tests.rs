@@ -0,0 +1,276 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::Path as UrlPath,
+        http::{header, HeaderMap, Request, StatusCode},
+        Extension,
+        Router,
+        response::Redirect,
+        routing::get,
+    };
+    use image::{DynamicImage, GenericImageView, ImageFormat, RgbaImage};
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::io::Cursor;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+            .write_to(&mut out, ImageFormat::Png)
+            .unwrap();
+        out.into_inner()
+    }
+
+    /// Serves `/{width}x{height}.png`, refusing requests without a Referer like
+    /// hot-link protected sites do
+    async fn serve_image(UrlPath(name): UrlPath<String>, headers: HeaderMap) -> Result<Vec<u8>, StatusCode> {
+        if !headers.contains_key(header::REFERER) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        let (width, height) = name.trim_end_matches(".png").split_once('x').ok_or(StatusCode::NOT_FOUND)?;
+        Ok(png(width.parse().unwrap(), height.parse().unwrap()))
+    }
+
+    /// Redirects `/redirect/{name}` to `/{name}`
+    async fn redirect_image(UrlPath(name): UrlPath<String>) -> Redirect {
+        Redirect::temporary(&format!("/{}", name))
+    }
+
+    async fn start_image_server() -> String {
+        let app = Router::new()
+            .route("/{name}", get(serve_image))
+            .route("/redirect/{name}", get(redirect_image));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("manga-sync-covers-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    async fn setup_app_no_auth(cover_dir: &Path) -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("UPDATE setting SET value = ? WHERE key = 'COVER_DIR'")
+            .bind(cover_dir.to_string_lossy().into_owned())
+            .execute(&pool)
+            .await
+            .unwrap();
+        // The image server listens on the loopback
+        sqlx::query("UPDATE setting SET value = 'true' WHERE key = 'COVER_ALLOW_PRIVATE_HOSTS'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let key_path = "test_key_cover.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga", get(handlers::manga::list_manga).post(handlers::manga::create_manga))
+            .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga).delete(handlers::manga::delete_manga))
+            .route("/manga/{id}/cover", get(handlers::cover::get_manga_cover))
+            .route("/manga/{id}/cover/small", get(handlers::cover::get_manga_cover_small))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    fn json_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn get_cover(app: &Router, uri: &str, etag: Option<&str>) -> (StatusCode, HeaderMap, Vec<u8>) {
+        let mut request = Request::builder().uri(uri);
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, headers, bytes.to_vec())
+    }
+
+    fn cached_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    #[tokio::test]
+    async fn test_cover_is_cached_with_generated_thumbnail() {
+        let images = start_image_server().await;
+        let dir = temp_dir();
+        let (app, _pool) = setup_app_no_auth(&dir).await;
+
+        let cover = format!("{}/600x900.png", images);
+        let response = app.clone()
+            .oneshot(json_request("POST", "/manga", serde_json::json!({"name": "Test Manga", "cover": cover})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(cached_files(&dir), 2);
+
+        let (status, headers, bytes) = get_cover(&app, "/manga/1/cover", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[header::CONTENT_TYPE], "image/png");
+        assert_eq!(bytes, png(600, 900));
+        let etag = headers[header::ETAG].to_str().unwrap().to_string();
+
+        let (status, _, bytes) = get_cover(&app, "/manga/1/cover", Some(&etag)).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert!(bytes.is_empty());
+
+        let (status, headers, bytes) = get_cover(&app, "/manga/1/cover/small", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[header::CONTENT_TYPE], "image/jpeg");
+        assert_eq!(image::load_from_memory(&bytes).unwrap().dimensions(), (300, 450));
+
+        // The generated small cover follows a new cover, and the old files are deleted
+        let cover = format!("{}/200x300.png", images);
+        app.clone()
+            .oneshot(json_request("PATCH", "/manga/1", serde_json::json!({"cover": cover})))
+            .await
+            .unwrap();
+        let (status, headers, _) = get_cover(&app, "/manga/1/cover", Some(&etag)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(headers[header::ETAG].to_str().unwrap(), etag);
+        let (_, _, bytes) = get_cover(&app, "/manga/1/cover/small", None).await;
+        assert_eq!(image::load_from_memory(&bytes).unwrap().dimensions(), (200, 300));
+        assert_eq!(cached_files(&dir), 2);
+
+        app.clone()
+            .oneshot(Request::builder().method("DELETE").uri("/manga/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(cached_files(&dir), 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cover_downloaded_on_first_access() {
+        let images = start_image_server().await;
+        let dir = temp_dir().with_extension("lazy");
+        let (app, pool) = setup_app_no_auth(&dir).await;
+
+        // As left by an import, which does not download covers
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', ?, ?)")
+            .bind(format!("{}/600x900.png", images))
+            .bind(format!("{}/100x150.png", images))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (2, 'Local', 'cover.jpg', 'cover.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (status, _, bytes) = get_cover(&app, "/manga/1/cover/small", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(bytes, png(100, 150));
+
+        let (status, _, _) = get_cover(&app, "/manga/2/cover", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _, _) = get_cover(&app, "/manga/3/cover", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cover_failure_is_not_retried_on_every_access() {
+        let images = start_image_server().await;
+        let dir = temp_dir().with_extension("failed");
+        let (app, pool) = setup_app_no_auth(&dir).await;
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', ?, '')")
+            .bind(format!("{}/missing.png", images))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (status, _, _) = get_cover(&app, "/manga/1/cover", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let failed: Option<String> = sqlx::query_scalar("SELECT cover_failed_at FROM manga WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(failed.is_some());
+
+        // The cover is fixed, but the failure is too recent to try again
+        sqlx::query("UPDATE manga SET cover = ? WHERE id = 1")
+            .bind(format!("{}/redirect/600x900.png", images))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (status, _, _) = get_cover(&app, "/manga/1/cover", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        sqlx::query("UPDATE manga SET cover_failed_at = datetime('now', '-2 hours') WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (status, _, bytes) = get_cover(&app, "/manga/1/cover", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(bytes, png(600, 900));
+        let failed: Option<String> = sqlx::query_scalar("SELECT cover_failed_at FROM manga WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(failed.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cover_on_private_host_is_rejected() {
+        let images = start_image_server().await;
+        let dir = temp_dir().with_extension("private");
+        let (app, pool) = setup_app_no_auth(&dir).await;
+        sqlx::query("UPDATE setting SET value = 'false' WHERE key = 'COVER_ALLOW_PRIVATE_HOSTS'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', ?, '')")
+            .bind(format!("{}/600x900.png", images))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (status, _, _) = get_cover(&app, "/manga/1/cover", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(!dir.exists() || cached_files(&dir) == 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -3,6 +3,7 @@ mod tests {
     use axum::{
         body::Body,
         http::{Request, StatusCode},
+        Extension,
         Router,
         routing::{get},
     };
@@ -12,6 +13,9 @@ mod tests {
     use manga_sync::handlers;
     use manga_sync::cache::ChapterCache;
     use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
     use manga_sync::auth::key_manager::KeyManager;
 
     // Test without auth middleware to verify logic
@@ -27,6 +31,8 @@ mod tests {
             pool: pool.clone(),
             cache: Arc::new(ChapterCache::new()),
             key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
         };
 
         let app = Router::new()
@@ -35,6 +41,7 @@ mod tests {
             .route("/manga/{id}/source", get(handlers::manga::get_manga_sources))
             .route("/manga/{id}/source/{domain}", axum::routing::delete(handlers::manga::delete_manga_source))
             .route("/website/{domain}", get(handlers::website::check_website).post(handlers::website::create_website).delete(handlers::website::delete_website))
+            .layer(Extension(AuthContext::legacy()))
             .with_state(state);
 
         (app, pool)
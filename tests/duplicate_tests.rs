@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    /// "Solo Leveling" and "Solo Levelling" follow the same path on
+    /// site-a.com, and "Solo Levelling" and "Ore dake Level Up na Ken" the
+    /// same manga ID on site-b.com. "One Piece" has nothing in common.
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for query in [
+            "INSERT INTO website (id, domain) VALUES (1, 'site-a.com'), (2, 'site-b.com')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Solo Leveling', 'c', 'c')",
+            "INSERT INTO manga (id, name, cover, cover_small, description, reading_status) VALUES (2, 'Solo Levelling', 'c', 'c', 'Hunters', 'reading')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (3, 'Ore dake Level Up na Ken', 'c', 'c')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (4, 'One Piece', 'c', 'c')",
+            "INSERT INTO source (id, manga_id, website_id, path) VALUES (1, 1, 1, '/solo')",
+            "INSERT INTO source (id, manga_id, website_id, path) VALUES (2, 2, 1, '/solo')",
+            "INSERT INTO source (id, manga_id, website_id, path, external_manga_id) VALUES (3, 2, 2, '/solo-levelling', '42')",
+            "INSERT INTO source (id, manga_id, website_id, path, external_manga_id) VALUES (4, 3, 2, '/ore-dake', '42')",
+            "INSERT INTO source (id, manga_id, website_id, path) VALUES (5, 4, 2, '/one-piece')",
+            "INSERT INTO manga_alt_title (manga_id, title) VALUES (2, 'Na Honjaman')",
+            "INSERT INTO category (id, user_id, name, position) VALUES (1, 1, 'Favorites', 0)",
+            "INSERT INTO manga_category (manga_id, category_id) VALUES (2, 1)",
+            "INSERT INTO chapter (manga_id, number, updated_at) VALUES (1, '10', '2026-01-01 00:00:00')",
+            "INSERT INTO chapter (manga_id, number, updated_at) VALUES (2, '12', '2026-01-02 00:00:00')",
+            "INSERT INTO sync_run (id, triggered_by) VALUES (1, 'manual')",
+            "INSERT INTO sync_result (sync_run_id, source_id, manga_name, domain) VALUES (1, 2, 'Solo Levelling', 'site-a.com')",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        let key_path = "test_key_duplicate.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/duplicates", get(handlers::manga::find_duplicates))
+            .route("/manga/{id}", get(handlers::manga::get_manga))
+            .route("/manga/{id}/merge/{other_id}", post(handlers::manga::merge_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn request(app: &Router, method: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(Request::builder().method(method).uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    fn kinds(duplicate: &serde_json::Value) -> Vec<&str> {
+        duplicate["reasons"].as_array().unwrap().iter().map(|r| r["kind"].as_str().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicates() {
+        let (app, _) = setup_app_no_auth().await;
+
+        let (status, json) = request(&app, "GET", "/manga/duplicates").await;
+        assert_eq!(status, StatusCode::OK);
+        let duplicates = json["data"]["items"].as_array().unwrap();
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(json["data"]["total"], 2);
+
+        assert_eq!((duplicates[0]["manga"]["id"].as_i64(), duplicates[0]["other"]["id"].as_i64()), (Some(1), Some(2)));
+        assert_eq!(kinds(&duplicates[0]), vec!["same_path", "similar_title"]);
+        assert_eq!(duplicates[0]["reasons"][0]["path"], "/solo");
+        assert_eq!(duplicates[0]["reasons"][1]["other_title"], "Solo Levelling");
+
+        assert_eq!((duplicates[1]["manga"]["id"].as_i64(), duplicates[1]["other"]["id"].as_i64()), (Some(2), Some(3)));
+        assert_eq!(kinds(&duplicates[1]), vec!["same_external_id"]);
+
+        // Every title is alike at the lowest similarity
+        let (_, json) = request(&app, "GET", "/manga/duplicates?similarity=0").await;
+        assert_eq!(json["data"]["total"], 6);
+        let (_, json) = request(&app, "GET", "/manga/duplicates?similarity=0&size=4&page=2").await;
+        assert_eq!(json["data"]["items"].as_array().unwrap().len(), 2);
+
+        let (status, _) = request(&app, "GET", "/manga/duplicates?similarity=1.5").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_merge_manga() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let (status, json) = request(&app, "POST", "/manga/1/merge/2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["moved_sources"], 1);
+        assert_eq!(json["data"]["dropped_sources"], 1);
+        assert_eq!(json["data"]["moved_chapters"], 1);
+
+        let (status, _) = request(&app, "GET", "/manga/2").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, json) = request(&app, "GET", "/manga/1").await;
+        let manga = &json["data"];
+        assert_eq!(manga["name"], "Solo Leveling");
+        assert_eq!(manga["current_chapter"], "12");
+        assert_eq!(manga["description"], "Hunters");
+        assert_eq!(manga["reading_status"], "reading");
+        assert_eq!(manga["alt_titles"], serde_json::json!(["Solo Levelling", "Na Honjaman"]));
+        assert_eq!(manga["categories"], serde_json::json!(["Favorites"]));
+
+        let sources: Vec<i64> = sqlx::query_scalar("SELECT id FROM source WHERE manga_id = 1 ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(sources, vec![1, 3]);
+        let result_source: Option<i64> = sqlx::query_scalar("SELECT source_id FROM sync_result")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(result_source, Some(1));
+
+        // The merged name is now found through the kept manga
+        let (_, json) = request(&app, "GET", "/manga/duplicates").await;
+        assert_eq!(json["data"]["items"][0]["reasons"][0]["kind"], "same_external_id");
+        assert_eq!(json["data"]["items"][0]["manga"]["id"], 1);
+
+        assert_eq!(request(&app, "POST", "/manga/1/merge/1").await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(request(&app, "POST", "/manga/1/merge/2").await.0, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::get,
+    };
+    use futures::StreamExt;
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_events.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/events", get(handlers::events::stream_events))
+            .route("/manga", get(handlers::manga::list_manga).post(handlers::manga::create_manga))
+            .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga).delete(handlers::manga::delete_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    fn json_request(method: &str, uri: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    /// Reads SSE frames until `count` events were received
+    async fn read_events(stream: &mut (impl futures::Stream<Item = Result<axum::body::Bytes, axum::Error>> + Unpin), count: usize) -> Vec<(String, serde_json::Value)> {
+        let mut buffer = String::new();
+        let mut events = Vec::new();
+
+        while events.len() < count {
+            let chunk = tokio::time::timeout(Duration::from_secs(2), stream.next())
+                .await
+                .expect("timed out waiting for event")
+                .unwrap()
+                .unwrap();
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+
+            while let Some(end) = buffer.find("\n\n") {
+                let frame: String = buffer.drain(..end + 2).collect();
+                let name = frame.lines().find_map(|l| l.strip_prefix("event: ")).map(str::to_string);
+                let data = frame.lines().find_map(|l| l.strip_prefix("data: "));
+                if let (Some(name), Some(data)) = (name, data) {
+                    events.push((name, serde_json::from_str(data).unwrap()));
+                }
+            }
+        }
+
+        events
+    }
+
+    #[tokio::test]
+    async fn test_events_stream_library_changes() {
+        let (app, _pool) = setup_app_no_auth().await;
+
+        let response = app.clone()
+            .oneshot(Request::builder().uri("/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut stream = response.into_body().into_data_stream();
+
+        let response = app.clone()
+            .oneshot(json_request("POST", "/manga", r#"{"name": "Test Manga", "cover": "c.jpg", "cover_small": "cs.jpg"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.clone()
+            .oneshot(json_request("PATCH", "/manga/1", r#"{"name": "Renamed", "chapter_number": "chapter-3"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(Request::builder().method("DELETE").uri("/manga/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let events = read_events(&mut stream, 4).await;
+        let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["manga_created", "manga_updated", "chapter_read", "manga_deleted"]);
+
+        assert_eq!(events[0].1["type"], "manga_created");
+        assert_eq!(events[0].1["manga_id"], 1);
+        assert_eq!(events[0].1["name"], "Test Manga");
+        assert_eq!(events[2].1["chapter_number"], "chapter-3");
+    }
+}
@@ -0,0 +1,226 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::{AuthContext, KeyScope};
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth(context: AuthContext) -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_export.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/export", get(handlers::export::export_library))
+            .route("/import", post(handlers::import::import_library))
+            .layer(Extension(context))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn seed(pool: &SqlitePool) {
+        for query in [
+            "INSERT INTO website (id, domain, base_url) VALUES (1, 'example.com', 'https://example.com')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', 'cover.jpg', 'cover_small.jpg')",
+            "INSERT INTO source (manga_id, website_id, path, external_manga_id) VALUES (1, 1, '/manga/test', 'ext-1')",
+            "INSERT INTO chapter (manga_id, number, updated_at) VALUES (1, 'chapter-1', '2026-01-01 10:00:00')",
+            "INSERT INTO chapter (manga_id, number, updated_at) VALUES (1, 'chapter-2', '2026-01-02 10:00:00')",
+            "UPDATE setting SET value = '3' WHERE key = 'SYNC_CONCURRENCY'",
+            "UPDATE manga SET description = 'A test', status = 'ongoing' WHERE id = 1",
+            "INSERT INTO genre (id, name) VALUES (1, 'Action')",
+            "INSERT INTO manga_genre (manga_id, genre_id, position) VALUES (1, 1, 0)",
+            "UPDATE manga SET reading_status = 'on_hold', started_at = '2026-01-01 10:00:00' WHERE id = 1",
+            "INSERT INTO category (id, user_id, name, position) VALUES (1, 1, 'Favourites', 1), (2, 1, 'Empty', 0)",
+            "INSERT INTO manga_category (manga_id, category_id) VALUES (1, 1)",
+        ] {
+            sqlx::query(query).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn export(app: &Router) -> serde_json::Value {
+        let response = app.clone()
+            .oneshot(Request::builder().uri("/export").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()["content-disposition"].to_str().unwrap().starts_with("attachment"));
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    async fn import(app: &Router, mode: &str, document: &serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/import?mode={}", mode))
+                    .header("content-type", "application/json")
+                    .body(Body::from(document.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_into_another_instance() {
+        let (source_app, source_pool) = setup_app_no_auth(AuthContext::legacy()).await;
+        seed(&source_pool).await;
+
+        let document = export(&source_app).await;
+        assert_eq!(document["version"], 1);
+        assert_eq!(document["settings"]["SYNC_CONCURRENCY"], "3");
+        let manga = &document["manga"][0];
+        assert_eq!(manga["name"], "Test Manga");
+        assert_eq!(manga["status"], "ongoing");
+        assert_eq!(manga["genres"][0], "Action");
+        assert_eq!(manga["reading_status"], "on_hold");
+        assert_eq!(manga["sources"][0]["domain"], "example.com");
+        assert_eq!(manga["sources"][0]["external_manga_id"], "ext-1");
+        assert_eq!(manga["chapters"].as_array().unwrap().len(), 2);
+        assert_eq!(manga["chapters"][1]["number"], "chapter-2");
+        assert_eq!(document["categories"], serde_json::json!(["Empty", "Favourites"]));
+        assert_eq!(manga["categories"], serde_json::json!(["Favourites"]));
+
+        let (target_app, target_pool) = setup_app_no_auth(AuthContext::legacy()).await;
+
+        let (status, json) = import(&target_app, "merge", &document).await;
+        assert_eq!(status, StatusCode::OK);
+        let report = &json["data"];
+        assert_eq!(report["manga_created"], 1);
+        assert_eq!(report["sources_created"], 1);
+        assert_eq!(report["chapters_created"], 2);
+        assert_eq!(report["websites_created"], 1);
+        assert_eq!(report["categories_created"], 2);
+        // Merging keeps the target's existing setting value
+        assert_eq!(report["conflicts"][0]["kind"], "setting");
+        assert_eq!(report["conflicts"][0]["name"], "SYNC_CONCURRENCY");
+
+        let current: String = sqlx::query_scalar("SELECT number FROM chapter ORDER BY updated_at DESC LIMIT 1")
+            .fetch_one(&target_pool)
+            .await
+            .unwrap();
+        assert_eq!(current, "chapter-2");
+        let genre: String = sqlx::query_scalar("SELECT g.name FROM genre g JOIN manga_genre mg ON mg.genre_id = g.id")
+            .fetch_one(&target_pool)
+            .await
+            .unwrap();
+        assert_eq!(genre, "Action");
+        let reading_status: String = sqlx::query_scalar("SELECT reading_status FROM manga").fetch_one(&target_pool).await.unwrap();
+        assert_eq!(reading_status, "on_hold");
+        let categories: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT c.name, c.position, COUNT(mc.manga_id) FROM category c
+            LEFT JOIN manga_category mc ON mc.category_id = c.id GROUP BY c.id ORDER BY c.position"
+        )
+            .fetch_all(&target_pool)
+            .await
+            .unwrap();
+        assert_eq!(categories, vec![("Empty".to_string(), 0, 0), ("Favourites".to_string(), 1, 1)]);
+
+        // Importing the same document again adds nothing
+        let (_, json) = import(&target_app, "merge", &document).await;
+        assert_eq!(json["data"]["manga_created"], 0);
+        assert_eq!(json["data"]["manga_merged"], 1);
+        assert_eq!(json["data"]["chapters_created"], 0);
+        assert_eq!(json["data"]["categories_created"], 0);
+
+        // Replacing overwrites the setting and recreates the library
+        let (_, json) = import(&target_app, "replace", &document).await;
+        assert_eq!(json["data"]["manga_created"], 1);
+        assert_eq!(json["data"]["settings_updated"], 1);
+        assert!(json["data"]["conflicts"].as_array().unwrap().is_empty());
+
+        let concurrency: String = sqlx::query_scalar("SELECT value FROM setting WHERE key = 'SYNC_CONCURRENCY'")
+            .fetch_one(&target_pool)
+            .await
+            .unwrap();
+        assert_eq!(concurrency, "3");
+        let chapters: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM chapter").fetch_one(&target_pool).await.unwrap();
+        assert_eq!(chapters, 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_conflicts() {
+        let context = AuthContext { key_id: Some(1), name: "tablet".to_string(), scope: KeyScope::Write, user_id: 1 };
+        let (app, pool) = setup_app_no_auth(context).await;
+        seed(&pool).await;
+
+        // Settings are left out for non-admin keys
+        let mut document = export(&app).await;
+        assert!(document["settings"].as_object().unwrap().is_empty());
+
+        document["settings"]["SYNC_CONCURRENCY"] = "2".into();
+        document["manga"][0]["cover"] = "other.jpg".into();
+        document["manga"][0]["sources"][0]["path"] = "/manga/other".into();
+        document["manga"][0]["sources"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({"domain": "unknown.com", "path": "/x", "external_manga_id": null}));
+
+        let (status, json) = import(&app, "merge", &document).await;
+        assert_eq!(status, StatusCode::OK);
+        let kinds: Vec<&str> = json["data"]["conflicts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, vec!["setting", "manga", "source", "source"]);
+
+        // Websites are shared by every user, so only admin keys create or overwrite them
+        document["websites"][0]["chapter_selector"] = "a.other".into();
+        document["websites"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({"domain": "new.com", "base_url": "https://new.com"}));
+        let (status, json) = import(&app, "replace", &document).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["websites_created"], 0);
+        assert_eq!(json["data"]["websites_updated"], 0);
+        let websites: Vec<&str> = json["data"]["conflicts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|c| c["kind"] == "website")
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(websites, vec!["example.com", "new.com"]);
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM website WHERE domain = 'new.com' OR chapter_selector = 'a.other'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+
+        document["version"] = 99.into();
+        let (status, _) = import(&app, "merge", &document).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        middleware,
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::auth::middleware::auth_middleware;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::sync::remote_chapters::store_chapters;
+    use manga_sync::sync::strategy::ChapterLink;
+
+    async fn setup_app() -> (Router, SqlitePool, String) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_feed.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+        let token = km.refresh_key().unwrap();
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km.clone(),
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga", get(handlers::manga::list_manga))
+            .route("/feed.atom", get(handlers::feed::atom_feed))
+            .route("/feed.rss", get(handlers::feed::rss_feed))
+            .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
+            .with_state(state);
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Tom & Jerry', 'cover.jpg', 'cover_small.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO website (id, domain) VALUES (1, 'example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (1, 1, 1, '/manga/test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        (app, pool, token)
+    }
+
+    fn chapter(href: &str) -> ChapterLink {
+        ChapterLink {
+            href: href.to_string(),
+            title: None,
+        }
+    }
+
+    fn percent_encode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    async fn get_body(app: &Router, uri: &str, token: &str) -> (StatusCode, String) {
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    /// Stores an initial scrape, then a later one with a new chapter
+    async fn sync_twice(pool: &SqlitePool) {
+        store_chapters(pool, 1, &[chapter("/manga/test/chapter-2"), chapter("/manga/test/chapter-1")])
+            .await
+            .unwrap();
+        sqlx::query("UPDATE remote_chapter SET first_seen_at = '2020-01-01 00:00:00'")
+            .execute(pool)
+            .await
+            .unwrap();
+        store_chapters(pool, 1, &[
+            chapter("/manga/test/chapter-3"),
+            chapter("/manga/test/chapter-2"),
+            chapter("/manga/test/chapter-1"),
+        ])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_atom_feed_lists_new_chapters_only() {
+        let (app, pool, token) = setup_app().await;
+
+        let (status, body) = get_body(&app, "/feed.atom", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!body.contains("<entry>"));
+
+        sync_twice(&pool).await;
+
+        let (status, body) = get_body(&app, "/feed.atom", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.matches("<entry>").count(), 1);
+        assert!(body.contains("<title>Tom &amp; Jerry - Chapter 3</title>"));
+        assert!(body.contains("<link href=\"https://example.com/manga/test/chapter-3\"/>"));
+        assert!(!body.contains("chapter-2"));
+
+        let (_, body) = get_body(&app, "/feed.atom?manga_id=2", &token).await;
+        assert!(!body.contains("<entry>"));
+    }
+
+    #[tokio::test]
+    async fn test_rss_feed() {
+        let (app, pool, token) = setup_app().await;
+        sync_twice(&pool).await;
+
+        let (status, body) = get_body(&app, "/feed.rss", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("<rss version=\"2.0\">"));
+        assert_eq!(body.matches("<item>").count(), 1);
+        assert!(body.contains("<link>https://example.com/manga/test/chapter-3</link>"));
+    }
+
+    #[tokio::test]
+    async fn test_feed_accepts_query_token() {
+        let (app, _pool, token) = setup_app().await;
+
+        let request = |uri: String| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(request("/feed.atom".to_string())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.clone().oneshot(request(format!("/feed.atom?token={}", percent_encode(&token)))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/atom+xml; charset=utf-8");
+
+        let response = app.clone().oneshot(request("/feed.rss?token=wrong".to_string())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Other endpoints still require the header
+        let response = app.oneshot(request(format!("/manga?token={}", percent_encode(&token)))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::post,
+    };
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_import.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/import/mihon", post(handlers::import::import_mihon))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    /// Minimal protobuf encoder for building backups
+    #[derive(Default)]
+    struct Message(Vec<u8>);
+
+    impl Message {
+        fn varint(mut self, field: u32, value: u64) -> Self {
+            self.key(field, 0);
+            self.raw_varint(value);
+            self
+        }
+
+        fn string(self, field: u32, value: &str) -> Self {
+            self.bytes(field, value.as_bytes())
+        }
+
+        fn message(self, field: u32, value: Message) -> Self {
+            self.bytes(field, &value.0)
+        }
+
+        fn float(mut self, field: u32, value: f32) -> Self {
+            self.key(field, 5);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn bytes(mut self, field: u32, value: &[u8]) -> Self {
+            self.key(field, 2);
+            self.raw_varint(value.len() as u64);
+            self.0.extend_from_slice(value);
+            self
+        }
+
+        fn key(&mut self, field: u32, wire_type: u64) {
+            self.raw_varint(((field as u64) << 3) | wire_type);
+        }
+
+        fn raw_varint(&mut self, mut value: u64) {
+            while value >= 0x80 {
+                self.0.push((value as u8) | 0x80);
+                value >>= 7;
+            }
+            self.0.push(value as u8);
+        }
+    }
+
+    fn chapter(url: &str, number: f32, read: bool) -> Message {
+        Message::default()
+            .string(1, url)
+            .string(2, &format!("Chapter {}", number))
+            .varint(4, read as u64)
+            .float(9, number)
+    }
+
+    fn backup() -> Vec<u8> {
+        let matched = Message::default()
+            .varint(1, 1001)
+            .string(2, "/title/solo/")
+            .string(3, "Solo Leveling")
+            .string(9, "https://example.com/cover.jpg")
+            .message(16, chapter("/chapter/a", 1.0, true))
+            .message(16, chapter("/chapter/b", 2.0, true))
+            .message(16, chapter("/chapter/c", 3.0, false))
+            // Chapter 1 was read last
+            .message(104, Message::default().string(1, "/chapter/a").varint(2, 1_700_000_100_000))
+            .message(104, Message::default().string(1, "/chapter/b").varint(2, 1_700_000_000_000))
+            // Categories are referred to by their order, here packed
+            .bytes(17, &[0x00, 0x02]);
+        let unmatched = Message::default()
+            .varint(1, 2002)
+            .string(2, "/comic/x")
+            .string(3, "Unknown Site Manga")
+            .message(16, chapter("/chapter/x", 7.0, true))
+            .varint(17, 2);
+        let removed = Message::default()
+            .varint(1, 1001)
+            .string(2, "/title/removed")
+            .string(3, "Removed Manga")
+            .varint(100, 0);
+
+        let message = Message::default()
+            .message(1, matched)
+            .message(1, unmatched)
+            .message(1, removed)
+            .message(2, Message::default().string(1, "Favourites").varint(2, 2))
+            .message(2, Message::default().string(1, "Reading").varint(2, 0))
+            .message(101, Message::default().string(1, "MangaDex").varint(2, 1001))
+            .message(101, Message::default().string(1, "Comick").varint(2, 2002));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&message.0).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn import(app: &Router, body: Vec<u8>) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/import/mihon")
+                    .header("content-type", "application/octet-stream")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_import_mihon_backup() {
+        let (app, pool) = setup_app_no_auth().await;
+        sqlx::query("INSERT INTO website (id, domain) VALUES (1, 'mangadex.org')").execute(&pool).await.unwrap();
+
+        let (status, json) = import(&app, backup()).await;
+        assert_eq!(status, StatusCode::OK);
+        let report = &json["data"];
+        assert_eq!(report["manga_created"], 2);
+        assert_eq!(report["sources_created"], 1);
+        assert_eq!(report["chapters_created"], 3);
+        assert_eq!(report["unmatched"].as_array().unwrap().len(), 1);
+        assert_eq!(report["unmatched"][0]["manga"], "Unknown Site Manga");
+        assert_eq!(report["unmatched"][0]["source"], "Comick");
+        assert_eq!(report["categories_created"], 2);
+
+        let categories: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT c.name, COUNT(mc.manga_id) FROM category c
+            LEFT JOIN manga_category mc ON mc.category_id = c.id GROUP BY c.id ORDER BY c.position"
+        )
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(categories, vec![("Reading".to_string(), 1), ("Favourites".to_string(), 2)]);
+
+        let source: (i64, String) = sqlx::query_as(
+            "SELECT s.website_id, s.path FROM source s JOIN manga m ON m.id = s.manga_id WHERE m.name = 'Solo Leveling'"
+        )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(source, (1, "/title/solo".to_string()));
+
+        let current: String = sqlx::query_scalar(
+            "SELECT c.number FROM chapter c JOIN manga m ON m.id = c.manga_id
+            WHERE m.name = 'Solo Leveling' ORDER BY c.updated_at DESC LIMIT 1"
+        )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(current, "chapter-1");
+
+        // Importing again adds nothing
+        let (status, json) = import(&app, backup()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["manga_created"], 0);
+        assert_eq!(json["data"]["manga_updated"], 0);
+        assert_eq!(json["data"]["chapters_created"], 0);
+        assert_eq!(json["data"]["categories_created"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_import_invalid_backup() {
+        let (app, _pool) = setup_app_no_auth().await;
+
+        let (status, _) = import(&app, vec![0x1f, 0x8b, 0x00, 0x01]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = import(&app, vec![0x0a, 0x10, 0x01]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
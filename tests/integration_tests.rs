@@ -12,6 +12,8 @@ mod tests {
     use manga_sync::handlers;
     use manga_sync::cache::ChapterCache;
     use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
     use axum::{Router, routing::{get, post}, middleware};
 
     async fn setup_app() -> (Router, String) {
@@ -28,6 +30,8 @@ mod tests {
             pool,
             cache: Arc::new(ChapterCache::new()),
             key_manager: km.clone(),
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
         };
 
         // Since we can't easily get the plaintext key from KM after it's hashed and KM doesn't expose it
@@ -37,7 +41,7 @@ mod tests {
         let app = Router::new()
             .route("/manga", get(handlers::manga::list_manga).post(handlers::manga::create_manga))
             .route("/website/{domain}", post(handlers::website::create_website))
-            .layer(middleware::from_fn_with_state(km.clone(), auth_middleware))
+            .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
             .with_state(state);
 
         // We'll need a way to validate in tests.
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    /// Berserk has 5 unread chapters and was synced last, Vagabond has 1,
+    /// and One Piece has no source
+    async fn setup_app_no_auth() -> Router {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for query in [
+            "INSERT INTO website (id, domain) VALUES (1, 'example.com')",
+            "INSERT INTO manga (id, name, cover, cover_small, created_at) VALUES (1, 'Berserk', 'c', 'c', '2026-01-03 00:00:00')",
+            "INSERT INTO manga (id, name, cover, cover_small, created_at) VALUES (2, 'vagabond', 'c', 'c', '2026-01-01 00:00:00')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (3, 'One Piece', 'c', 'c')",
+            "INSERT INTO source (id, manga_id, website_id, path, number_unread_chapter) VALUES (1, 1, 1, '/berserk', 5)",
+            "INSERT INTO source (id, manga_id, website_id, path, number_unread_chapter) VALUES (2, 2, 1, '/vagabond', 1)",
+            "INSERT INTO sync_run (id, triggered_by) VALUES (1, 'manual')",
+            "INSERT INTO sync_result (sync_run_id, source_id, manga_name, domain, created_at) VALUES (1, 1, 'Berserk', 'example.com', '2026-02-02 00:00:00')",
+            "INSERT INTO sync_result (sync_run_id, source_id, manga_name, domain, created_at) VALUES (1, 2, 'vagabond', 'example.com', '2026-02-01 00:00:00')",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        let key_path = "test_key_manga_list.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool,
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        Router::new()
+            .route("/manga", get(handlers::manga::list_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state)
+    }
+
+    async fn list(app: &Router, query: &str) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(Request::builder().uri(format!("/manga?{}", query)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    async fn names(app: &Router, query: &str) -> Vec<String> {
+        let (status, json) = list(app, query).await;
+        assert_eq!(status, StatusCode::OK, "{}", query);
+        json["data"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sort_keys() {
+        let app = setup_app_no_auth().await;
+
+        assert_eq!(names(&app, "filter[NAME]=ASC").await, vec!["Berserk", "One Piece", "vagabond"]);
+        assert_eq!(names(&app, "filter[0][UNREAD]=DESC").await, vec!["Berserk", "vagabond", "One Piece"]);
+        assert_eq!(names(&app, "filter[ADDED]=ASC").await, vec!["vagabond", "Berserk", "One Piece"]);
+        assert_eq!(names(&app, "filter[LAST_SYNC]=ASC").await, vec!["vagabond", "Berserk", "One Piece"]);
+
+        let (status, json) = list(&app, "filter[NAME]=UP").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["message"], "Invalid NAME value");
+    }
+
+    #[tokio::test]
+    async fn test_filters() {
+        let app = setup_app_no_auth().await;
+
+        assert_eq!(names(&app, "filter[HAS_UNREAD]=true&filter[NAME]=ASC").await, vec!["Berserk", "vagabond"]);
+        assert_eq!(names(&app, "filter[HAS_UNREAD]=false").await, vec!["One Piece"]);
+        assert_eq!(names(&app, "filter[UNREAD_MIN]=2").await, vec!["Berserk"]);
+        assert_eq!(names(&app, "filter[NO_SOURCE]=true").await, vec!["One Piece"]);
+        assert_eq!(names(&app, "filter[0][TEXT]=VAGA&filter[1][WEBSITE]=example.com&filter[NAME]=DESC").await, vec!["vagabond"]);
+
+        // Values are bound, not interpolated
+        assert!(names(&app, "filter[TEXT]=%27%20OR%201%3D1%20--").await.is_empty());
+        assert!(names(&app, "filter[TEXT]=%25").await.is_empty());
+
+        let (status, _) = list(&app, "filter[SOMETHING]=1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_pagination_and_total() {
+        let app = setup_app_no_auth().await;
+
+        let (status, json) = list(&app, "size=2&page=2&filter[NAME]=ASC").await;
+        assert_eq!(status, StatusCode::OK);
+        let data = &json["data"];
+        assert_eq!(data["total"], 3);
+        assert_eq!(data["page"], 2);
+        assert_eq!(data["size"], 2);
+        assert_eq!(data["items"].as_array().unwrap().len(), 1);
+        assert_eq!(data["items"][0]["name"], "vagabond");
+
+        let (_, json) = list(&app, "filter[HAS_UNREAD]=true&size=1").await;
+        assert_eq!(json["data"]["total"], 2);
+
+        for query in ["size=0", "size=101", "page=0", "size=abc"] {
+            let (status, _) = list(&app, query).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+        }
+    }
+}
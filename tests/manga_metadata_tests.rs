@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::{Query, State},
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::handlers::manga::Pagination;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth() -> (Router, AppState) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for name in ["Solo Leveling", "Berserk"] {
+            sqlx::query("INSERT INTO manga (name, cover, cover_small) VALUES (?, 'cover.jpg', 'cover.jpg')")
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let key_path = "test_key_manga_metadata.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool,
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state.clone());
+
+        (app, state)
+    }
+
+    async fn request(app: &Router, method: &str, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    async fn list_names(state: &AppState, key: &str, value: &str) -> Vec<String> {
+        let pagination = Pagination {
+            size: None,
+            page: None,
+            filter: Some(vec![HashMap::from([(key.to_string(), value.to_string())])]),
+        };
+        let response = handlers::manga::list_manga(State(state.clone()), Extension(AuthContext::legacy()), Query(pagination))
+            .await
+            .unwrap_or_else(|_| panic!("listing with {} failed", key));
+        response.0.data.unwrap().items.into_iter().map(|m| m.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_update_and_get_metadata() {
+        let (app, _) = setup_app_no_auth().await;
+
+        let (status, _) = request(&app, "PATCH", "/manga/1", Some(serde_json::json!({
+            "description": "E-rank hunter.",
+            "status": "completed",
+            "year": 2018,
+            "authors": ["Chugong", "DUBU", "chugong"],
+            "genres": ["Action", "Fantasy"],
+            "alt_titles": ["Only I Level Up", "나 혼자만 레벨업"]
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        let manga = &json["data"];
+        assert_eq!(manga["description"], "E-rank hunter.");
+        assert_eq!(manga["status"], "completed");
+        assert_eq!(manga["year"], 2018);
+        assert_eq!(manga["authors"], serde_json::json!(["Chugong", "DUBU"]));
+        assert_eq!(manga["genres"], serde_json::json!(["Action", "Fantasy"]));
+        assert_eq!(manga["alt_titles"][1], "나 혼자만 레벨업");
+
+        // Lists are replaced as a whole, an empty description clears it
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"genres": ["Fantasy"], "description": ""}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["genres"], serde_json::json!(["Fantasy"]));
+        assert!(json["data"]["description"].is_null());
+        assert_eq!(json["data"]["authors"].as_array().unwrap().len(), 2);
+
+        // null clears the status and year, leaving them out keeps them
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"status": null}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert!(json["data"]["status"].is_null());
+        assert_eq!(json["data"]["year"], 2018);
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"year": null}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert!(json["data"]["year"].is_null());
+
+        let (status, _) = request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"year": 12}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"status": "dropped"}))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_metadata() {
+        let (app, state) = setup_app_no_auth().await;
+
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({
+            "status": "completed", "year": 2018, "authors": ["Chugong"], "genres": ["Action", "Fantasy"]
+        }))).await;
+        request(&app, "PATCH", "/manga/2", Some(serde_json::json!({
+            "status": "hiatus", "year": 1989, "authors": ["Kentaro Miura"], "genres": ["Action", "Horror"]
+        }))).await;
+
+        assert_eq!(list_names(&state, "AUTHOR", "chugong").await, vec!["Solo Leveling"]);
+        assert_eq!(list_names(&state, "GENRE", "Horror").await, vec!["Berserk"]);
+        assert_eq!(list_names(&state, "GENRE", "Action").await.len(), 2);
+        assert_eq!(list_names(&state, "STATUS", "hiatus").await, vec!["Berserk"]);
+        assert_eq!(list_names(&state, "YEAR", "2018").await, vec!["Solo Leveling"]);
+        assert!(list_names(&state, "AUTHOR", "O'Brien").await.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        response::Html,
+        Extension,
+        Router,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    const MANGA_PAGE: &str = r#"
+        <html><head><meta property="og:image" content="/missing.png" /></head><body>
+            <div class="post-title"><h1>Solo Leveling</h1></div>
+            <div class="post-content_item">
+                <div class="summary-heading"><h5>Alternative</h5></div>
+                <div class="summary-content">Only I Level Up</div>
+            </div>
+            <div class="author-content"><a>Chugong</a></div>
+            <div class="genres-content"><a>Action</a>, <a>Fantasy</a></div>
+            <div class="post-status"><div class="post-content_item">
+                <div class="summary-heading"><h5>Status</h5></div>
+                <div class="summary-content">Completed</div>
+            </div></div>
+            <div class="summary__content"><p>E-rank hunter.</p></div>
+            <ul><li class="wp-manga-chapter"><a href="/manga/solo-leveling/chapter-1/">Chapter 1</a></li></ul>
+        </body></html>
+    "#;
+
+    /// Starts a local Madara-like website and returns its base URL
+    async fn start_website() -> String {
+        let app = Router::new()
+            .route("/manga/solo-leveling/", get(|| async { Html(MANGA_PAGE) }))
+            .route("/manga/solo-leveling", get(|| async { Html(MANGA_PAGE) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    async fn setup_app_no_auth(base_url: &str) -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO website (domain, base_url, chapter_selector) VALUES ('127.0.0.1', ?, 'li.wp-manga-chapter > a')")
+            .bind(base_url)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let key_path = "test_key_metadata.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/from-url", post(handlers::manga::create_manga_from_url))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn create_from_url(app: &Router, url: &str) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/manga/from-url")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::json!({"url": url}).to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_create_manga_from_url() {
+        let base_url = start_website().await;
+        let (app, pool) = setup_app_no_auth(&base_url).await;
+
+        let (status, json) = create_from_url(&app, &format!("{}/manga/solo-leveling/", base_url)).await;
+        assert_eq!(status, StatusCode::OK);
+        let created = &json["data"];
+        assert_eq!(created["name"], "Solo Leveling");
+        assert_eq!(created["website_domain"], "127.0.0.1");
+        assert_eq!(created["source_path"], "/manga/solo-leveling");
+        let metadata = &created["metadata"];
+        assert_eq!(metadata["alt_titles"][0], "Only I Level Up");
+        assert_eq!(metadata["authors"][0], "Chugong");
+        assert_eq!(metadata["genres"].as_array().unwrap().len(), 2);
+        assert_eq!(metadata["status"], "completed");
+        assert_eq!(metadata["cover"], format!("{}/missing.png", base_url));
+
+        let (name, cover, path): (String, String, String) = sqlx::query_as(
+            "SELECT m.name, m.cover, s.path FROM manga m JOIN source s ON s.manga_id = m.id"
+        )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "Solo Leveling");
+        assert_eq!(cover, format!("{}/missing.png", base_url));
+        assert_eq!(path, "/manga/solo-leveling");
+        let status: String = sqlx::query_scalar("SELECT status FROM manga").fetch_one(&pool).await.unwrap();
+        assert_eq!(status, "completed");
+        let genres: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM manga_genre").fetch_one(&pool).await.unwrap();
+        assert_eq!(genres, 2);
+
+        let (status, _) = create_from_url(&app, &format!("{}/manga/solo-leveling", base_url)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_create_manga_from_url_errors() {
+        let base_url = start_website().await;
+        let (app, _pool) = setup_app_no_auth(&base_url).await;
+
+        let (status, json) = create_from_url(&app, "https://unknown.example.com/manga/test").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["message"].as_str().unwrap().contains("unknown.example.com"));
+
+        let (status, _) = create_from_url(&app, "not a url").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = create_from_url(&app, &format!("{}/manga/does-not-exist", base_url)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
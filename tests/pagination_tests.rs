@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    /// One manga with sources on 3 websites and 5 chapters read a day apart
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Berserk', 'c', 'c')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for id in 1..=3 {
+            sqlx::query("INSERT INTO website (id, domain) VALUES (?, ?)")
+                .bind(id)
+                .bind(format!("site{}.com", id))
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO source (manga_id, website_id, path) VALUES (1, ?, '/berserk')")
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for number in 1..=5 {
+            sqlx::query("INSERT INTO chapter (manga_id, number, updated_at) VALUES (1, ?, ?)")
+                .bind(number.to_string())
+                .bind(format!("2026-01-0{} 00:00:00", number))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let key_path = "test_key_pagination.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/source", get(handlers::source::list_sources))
+            .route("/website", get(handlers::website::list_websites))
+            .route("/manga/{id}/history", get(handlers::manga::get_manga_history))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn get_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    fn values(page: &serde_json::Value, key: &str) -> Vec<serde_json::Value> {
+        page["items"].as_array().unwrap().iter().map(|item| item[key].clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_history_cursor_is_stable_while_reading() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let (status, json) = get_json(&app, "/manga/1/history?size=2").await;
+        assert_eq!(status, StatusCode::OK);
+        let page = &json["data"];
+        assert_eq!(values(page, "number"), vec!["5", "4"]);
+        assert_eq!(page["total"], 5);
+        assert_eq!(page["page"], 1);
+        let cursor = page["next_cursor"].as_str().unwrap().to_string();
+
+        // A chapter read meanwhile would shift an offset by one
+        sqlx::query("INSERT INTO chapter (manga_id, number, updated_at) VALUES (1, '6', '2026-01-06 00:00:00')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (_, json) = get_json(&app, &format!("/manga/1/history?size=2&cursor={}", cursor)).await;
+        let page = &json["data"];
+        assert_eq!(values(page, "number"), vec!["3", "2"]);
+        assert_eq!(page["total"], 6);
+        assert!(page["page"].is_null());
+        let cursor = page["next_cursor"].as_str().unwrap().to_string();
+
+        let (_, json) = get_json(&app, &format!("/manga/1/history?size=2&cursor={}", cursor)).await;
+        assert_eq!(values(&json["data"], "number"), vec!["1"]);
+        assert!(json["data"]["next_cursor"].is_null());
+
+        let (status, _) = get_json(&app, &format!("/manga/1/history?page=2&cursor={}", cursor)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json(&app, "/manga/1/history?cursor=nothex").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_source_cursor_and_offset_pages() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let (_, json) = get_json(&app, "/source?size=2").await;
+        assert_eq!(values(&json["data"], "website_id"), vec![1, 2]);
+        let cursor = json["data"]["next_cursor"].as_str().unwrap().to_string();
+
+        sqlx::query("INSERT INTO source (manga_id, website_id, path) VALUES (1, 1, '/berserk-deluxe')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (_, json) = get_json(&app, &format!("/source?size=2&cursor={}", cursor)).await;
+        assert_eq!(values(&json["data"], "website_id"), vec![3, 1]);
+        assert_eq!(json["data"]["total"], 4);
+
+        // Lists without a stable key only take page numbers
+        let (status, json) = get_json(&app, "/website?size=2&page=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(values(&json["data"], "domain"), vec!["site3.com"]);
+        assert_eq!(json["data"]["total"], 3);
+        assert!(json["data"]["next_cursor"].is_null());
+
+        let (status, _) = get_json(&app, &format!("/website?cursor={}", cursor)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json(&app, "/source?size=101").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
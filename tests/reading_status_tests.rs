@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::{Query, State},
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::handlers::manga::Pagination;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    /// Two manga, the first one with a source listing chapters 1 to 3
+    async fn setup_app_no_auth() -> (Router, AppState) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for name in ["Solo Leveling", "Berserk"] {
+            sqlx::query("INSERT INTO manga (name, cover, cover_small) VALUES (?, 'cover.jpg', 'cover.jpg')")
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO website (domain) VALUES ('example.com')").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO source (manga_id, website_id, path) VALUES (1, 1, '/manga/solo-leveling')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for number in 1..=3 {
+            sqlx::query("INSERT INTO remote_chapter (website_id, path, href, number, position) VALUES (1, '/manga/solo-leveling', ?, ?, ?)")
+                .bind(format!("/manga/solo-leveling/chapter-{}", number))
+                .bind(number as f64)
+                .bind(3 - number)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let key_path = "test_key_reading_status.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool,
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state.clone());
+
+        (app, state)
+    }
+
+    async fn request(app: &Router, method: &str, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    async fn list_names(state: &AppState, key: &str, value: &str) -> Vec<String> {
+        let pagination = Pagination {
+            size: None,
+            page: None,
+            filter: Some(vec![HashMap::from([(key.to_string(), value.to_string())])]),
+        };
+        let response = handlers::manga::list_manga(State(state.clone()), Extension(AuthContext::legacy()), Query(pagination))
+            .await
+            .unwrap_or_else(|_| panic!("listing with {} failed", key));
+        response.0.data.unwrap().items.into_iter().map(|m| m.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_reading_status_transitions() {
+        let (app, state) = setup_app_no_auth().await;
+
+        let (_, json) = request(&app, "GET", "/manga/2", None).await;
+        assert_eq!(json["data"]["reading_status"], "plan_to_read");
+        assert!(json["data"]["started_at"].is_null());
+
+        // Reading a chapter starts the manga
+        request(&app, "PATCH", "/manga/2", Some(serde_json::json!({"chapter_number": "1"}))).await;
+        let (_, json) = request(&app, "GET", "/manga/2", None).await;
+        assert_eq!(json["data"]["reading_status"], "reading");
+        let started_at = json["data"]["started_at"].clone();
+        assert!(!started_at.is_null());
+
+        let (status, _) = request(&app, "PATCH", "/manga/2", Some(serde_json::json!({"reading_status": "completed"}))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, json) = request(&app, "GET", "/manga/2", None).await;
+        assert_eq!(json["data"]["reading_status"], "completed");
+        assert!(!json["data"]["completed_at"].is_null());
+        assert_eq!(json["data"]["started_at"], started_at);
+
+        request(&app, "PATCH", "/manga/2", Some(serde_json::json!({"reading_status": "dropped"}))).await;
+        let (_, json) = request(&app, "GET", "/manga/2", None).await;
+        assert_eq!(json["data"]["reading_status"], "dropped");
+        assert!(json["data"]["completed_at"].is_null());
+
+        let (status, _) = request(&app, "PATCH", "/manga/2", Some(serde_json::json!({"reading_status": "finished"}))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        assert_eq!(list_names(&state, "READING_STATUS", "dropped").await, vec!["Berserk"]);
+        assert_eq!(list_names(&state, "READING_STATUS", "plan_to_read").await, vec!["Solo Leveling"]);
+        assert_eq!(list_names(&state, "STARTED_AT", "ASC").await, vec!["Berserk", "Solo Leveling"]);
+        assert!(handlers::manga::list_manga(
+            State(state.clone()),
+            Extension(AuthContext::legacy()),
+            Query(Pagination {
+                size: None,
+                page: None,
+                filter: Some(vec![HashMap::from([("READING_STATUS".to_string(), "done".to_string())])]),
+            }),
+        ).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_completed_when_caught_up_on_finished_series() {
+        let (app, _) = setup_app_no_auth().await;
+
+        // The series is still ongoing, reading the latest chapter is not enough
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"chapter_number": "3"}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["reading_status"], "reading");
+
+        // Once it is finished, it completes only on its last chapter
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"chapter_number": "2", "status": "completed"}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["reading_status"], "reading");
+
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"chapter_number": "Chapter 3"}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["reading_status"], "completed");
+        assert!(!json["data"]["completed_at"].is_null());
+
+        // Dropped manga stay dropped
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"reading_status": "dropped", "chapter_number": "2"}))).await;
+        request(&app, "PATCH", "/manga/1", Some(serde_json::json!({"chapter_number": "3"}))).await;
+        let (_, json) = request(&app, "GET", "/manga/1", None).await;
+        assert_eq!(json["data"]["reading_status"], "dropped");
+    }
+}
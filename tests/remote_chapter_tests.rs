@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::{get},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::sync::remote_chapters::store_chapters;
+    use manga_sync::sync::strategy::ChapterLink;
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_remote_chapter.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga))
+            .route("/manga/{id}/source", get(handlers::manga::get_manga_sources))
+            .route("/manga/{id}/source/{domain}/chapters", get(handlers::manga::get_manga_source_chapters))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', 'cover.jpg', 'cover_small.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO website (id, domain) VALUES (1, 'example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (1, 1, 1, '/manga/test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        (app, pool)
+    }
+
+    fn chapter(href: &str) -> ChapterLink {
+        ChapterLink {
+            href: href.to_string(),
+            title: Some(format!("Title of {}", href)),
+        }
+    }
+
+    async fn body_json(response: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_store_chapters_upserts_list() {
+        let (_app, pool) = setup_app_no_auth().await;
+
+        store_chapters(&pool, 1, &[chapter("/manga/test/chapter-2"), chapter("/manga/test/chapter-1")])
+            .await
+            .unwrap();
+
+        // Mark the existing rows as seen long ago to check first_seen_at is kept
+        sqlx::query("UPDATE remote_chapter SET first_seen_at = '2020-01-01 00:00:00'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // chapter-3 is published and chapter-1 is removed by the site
+        store_chapters(&pool, 1, &[chapter("/manga/test/chapter-3"), chapter("/manga/test/chapter-2")])
+            .await
+            .unwrap();
+
+        let rows: Vec<(String, i64, Option<f64>, String)> = sqlx::query_as(
+            "SELECT href, position, number, CAST(first_seen_at AS TEXT) FROM remote_chapter WHERE website_id = 1 AND path = '/manga/test' ORDER BY position"
+        )
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, "/manga/test/chapter-3");
+        assert_eq!(rows[0].1, 0);
+        assert_eq!(rows[0].2, Some(3.0));
+        assert_ne!(rows[0].3, "2020-01-01 00:00:00");
+        assert_eq!(rows[1].0, "/manga/test/chapter-2");
+        assert_eq!(rows[1].1, 1);
+        assert_eq!(rows[1].3, "2020-01-01 00:00:00");
+    }
+
+    #[tokio::test]
+    async fn test_chapter_list_shared_by_sources_of_a_page() {
+        let (_app, pool) = setup_app_no_auth().await;
+
+        // Another user follows the same page
+        for query in [
+            "INSERT INTO user (id, name) VALUES (2, 'other')",
+            "INSERT INTO manga (id, user_id, name, cover, cover_small) VALUES (2, 2, 'Test Manga', 'c', 'c')",
+            "INSERT INTO source (id, manga_id, website_id, path) VALUES (2, 2, 1, '/manga/test')",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        store_chapters(&pool, 1, &[chapter("/manga/test/chapter-1")]).await.unwrap();
+        store_chapters(&pool, 2, &[chapter("/manga/test/chapter-2"), chapter("/manga/test/chapter-1")])
+            .await
+            .unwrap();
+
+        let count = || sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM remote_chapter").fetch_one(&pool);
+        assert_eq!(count().await.unwrap(), 2);
+
+        // The list is kept while a source still follows the page
+        sqlx::query("DELETE FROM manga WHERE id = 1").execute(&pool).await.unwrap();
+        assert_eq!(count().await.unwrap(), 2);
+        sqlx::query("DELETE FROM source WHERE id = 2").execute(&pool).await.unwrap();
+        assert_eq!(count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_manga_source_chapters() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        store_chapters(&pool, 1, &[chapter("/manga/test/chapter-2"), chapter("/manga/test/chapter-1")])
+            .await
+            .unwrap();
+
+        let response = app.clone()
+            .oneshot(Request::builder().uri("/manga/1/source/example.com/chapters").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        let chapters = body["data"]["items"].as_array().unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(body["data"]["total"], 2);
+        assert_eq!(chapters[0]["href"], "/manga/test/chapter-2");
+        assert_eq!(chapters[0]["title"], "Title of /manga/test/chapter-2");
+        assert_eq!(chapters[1]["number"], 1.0);
+
+        let response = app
+            .oneshot(Request::builder().uri("/manga/1/source/unknown.com/chapters").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_reading_position_reconciled_across_sources() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        sqlx::query("INSERT INTO website (id, domain) VALUES (2, 'other.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (2, 1, 2, '/read/test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        store_chapters(&pool, 1, &[chapter("/manga/test/chapter-121"), chapter("/manga/test/chapter-120")])
+            .await
+            .unwrap();
+        // The other site uses different slugs and is two chapters ahead
+        store_chapters(&pool, 2, &[
+            chapter("/read/test/ch-123"),
+            chapter("/read/test/ch-122"),
+            chapter("/read/test/ch-121"),
+            chapter("/read/test/ch-120"),
+        ])
+            .await
+            .unwrap();
+
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("PATCH")
+                    .uri("/manga/1")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"chapter_number": "chapter-120"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let unread: Vec<(i64, Option<i64>)> = sqlx::query_as("SELECT id, number_unread_chapter FROM source ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(unread, vec![(1, Some(1)), (2, Some(3))]);
+
+        let response = app.clone()
+            .oneshot(Request::builder().uri("/manga/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["furthest_source"], "other.com");
+        assert_eq!(body["data"]["number_unread_chapter"], 3);
+
+        let response = app
+            .oneshot(Request::builder().uri("/manga/1/source").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        let sources = body["data"]["items"].as_array().unwrap();
+        assert_eq!(sources[0]["latest_chapter"], 121.0);
+        assert_eq!(sources[0]["is_furthest_ahead"], false);
+        assert_eq!(sources[1]["latest_chapter"], 123.0);
+        assert_eq!(sources[1]["is_furthest_ahead"], true);
+    }
+}
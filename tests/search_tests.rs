@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Extension,
+        Router,
+        routing::get,
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for query in [
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Na Honjaman', 'c', 'c')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (2, 'Leveling Up Alone', 'c', 'c')",
+            "INSERT INTO manga (id, name, cover, cover_small) VALUES (3, 'Pokémon Adventures', 'c', 'c')",
+            "INSERT INTO manga_alt_title (manga_id, title) VALUES (1, 'I Alone Level-Up')",
+            "INSERT INTO manga_alt_title (manga_id, title) VALUES (1, 'Solo Leveling')",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        let key_path = "test_key_search.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/search", get(handlers::search::search_manga))
+            .route("/manga", get(handlers::manga::list_manga))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn get_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    fn ids(json: &serde_json::Value) -> Vec<i64> {
+        json["data"]["items"].as_array().unwrap().iter().map(|m| m["id"].as_i64().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_and_highlights() {
+        let (app, _) = setup_app_no_auth().await;
+
+        // Names weigh more than alternative titles
+        let (status, json) = get_json(&app, "/search?q=leveling").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&json), vec![2, 1]);
+        assert_eq!(json["data"]["total"], 2);
+        let items = &json["data"]["items"];
+        assert_eq!(items[0]["name_highlight"], "<mark>Leveling</mark> Up Alone");
+        assert!(items[0]["alt_title"].is_null());
+        assert_eq!(items[1]["name_highlight"], "Na Honjaman");
+        assert_eq!(items[1]["alt_title"], "Solo <mark>Leveling</mark>");
+        assert!(items[0]["score"].as_f64().unwrap() > items[1]["score"].as_f64().unwrap());
+
+        // Words match by prefix, in any order, ignoring case and diacritics
+        let (_, json) = get_json(&app, "/search?q=ALONE%20lev").await;
+        assert_eq!(ids(&json), vec![2, 1]);
+        let (_, json) = get_json(&app, "/search?q=pokemon").await;
+        assert_eq!(ids(&json), vec![3]);
+        assert_eq!(json["data"]["items"][0]["name_highlight"], "<mark>Pokémon</mark> Adventures");
+
+        let (status, _) = get_json(&app, "/search?q=%22*%20-").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json(&app, "/search").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_index_follows_manga_and_text_filter() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        sqlx::query("UPDATE manga SET name = 'Pocket Monsters' WHERE id = 3").execute(&pool).await.unwrap();
+        sqlx::query("DELETE FROM manga_alt_title WHERE title = 'Solo Leveling'").execute(&pool).await.unwrap();
+        sqlx::query("DELETE FROM manga WHERE id = 2").execute(&pool).await.unwrap();
+
+        let (_, json) = get_json(&app, "/search?q=leveling").await;
+        assert!(ids(&json).is_empty());
+        let (_, json) = get_json(&app, "/search?q=pokemon").await;
+        assert!(ids(&json).is_empty());
+        let (_, json) = get_json(&app, "/search?q=monster").await;
+        assert_eq!(ids(&json), vec![3]);
+
+        let (_, json) = get_json(&app, "/manga?filter[TEXT]=level").await;
+        assert_eq!(ids(&json), vec![1]);
+        let (_, json) = get_json(&app, "/manga?filter[TEXT]=pock%20mon").await;
+        assert_eq!(ids(&json), vec![3]);
+    }
+}
@@ -3,6 +3,7 @@ mod tests {
     use axum::{
         body::Body,
         http::{Request, StatusCode},
+        Extension,
         Router,
         routing::{get},
     };
@@ -12,6 +13,9 @@ mod tests {
     use manga_sync::handlers;
     use manga_sync::cache::ChapterCache;
     use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
     use manga_sync::auth::key_manager::KeyManager;
 
     async fn setup_app_no_auth() -> (Router, SqlitePool) {
@@ -26,10 +30,13 @@ mod tests {
             pool: pool.clone(),
             cache: Arc::new(ChapterCache::new()),
             key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
         };
 
         let app = Router::new()
             .route("/source", get(handlers::source::list_sources))
+            .layer(Extension(AuthContext::legacy()))
             .with_state(state);
 
         (app, pool)
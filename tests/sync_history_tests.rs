@@ -0,0 +1,348 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        response::Html,
+        Extension,
+        Router,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+    use sqlx::{Row, SqlitePool};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::sync::history::{self, SyncScope, SyncTrigger};
+    use manga_sync::sync::service::{SyncResult, SyncService};
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_sync_history.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/sync", post(handlers::sync::sync_all))
+            .route("/manga/{id}/sync", post(handlers::sync::sync_manga))
+            .route("/source/{id}/sync", post(handlers::sync::sync_source))
+            .route("/sync/runs", get(handlers::sync::list_sync_runs))
+            .route("/sync/runs/{id}", get(handlers::sync::get_sync_run))
+            .route("/manga/refresh-unread", post(handlers::manga::refresh_all_unread))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    async fn body_json(response: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_is_recorded() {
+        let (_app, pool) = setup_app_no_auth().await;
+
+        // No source belongs to a supported domain, so nothing is fetched
+        let service = SyncService::new(pool.clone(), Arc::new(ChapterCache::new()), EventBus::new(), Arc::new(DomainRateLimiter::default())).await;
+        let (run_id, results) = service.run(SyncTrigger::Manual, SyncScope::All, None).await.unwrap();
+        assert!(results.is_empty());
+
+        let row = sqlx::query("SELECT triggered_by, finished_at IS NOT NULL as finished, total_sources FROM sync_run WHERE id = ?")
+            .bind(run_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("triggered_by"), "manual");
+        assert!(row.get::<bool, _>("finished"));
+        assert_eq!(row.get::<i64, _>("total_sources"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_reports_unsupported_sources() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', 'cover.jpg', 'cover_small.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO website (id, domain) VALUES (1, 'example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (1, 1, 1, '/manga/test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let response = app
+            .oneshot(Request::builder().method("POST").uri("/manga/refresh-unread").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["total"], 1);
+        assert_eq!(body["data"]["errors"], 1);
+        assert_eq!(body["data"]["results"][0]["domain"], "example.com");
+        assert_eq!(body["data"]["results"][0]["error"], "No strategy for this domain");
+    }
+
+    #[tokio::test]
+    async fn test_get_sync_run_with_results() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        sqlx::query("INSERT INTO user (id, name) VALUES (2, 'other')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', 'cover.jpg', 'cover_small.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO website (id, domain) VALUES (1, 'example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (1, 1, 1, '/manga/test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let users = BTreeMap::from([(1, 2), (2, 1)]);
+        let run_id = history::start_run(&pool, SyncTrigger::Cron, SyncScope::All, &users).await.unwrap();
+        for (user_id, new_chapters, error) in [(1, 3, None), (1, 0, Some("Failed to fetch chapters".to_string())), (2, 5, None)] {
+            let result = SyncResult {
+                source_id: 1,
+                manga_id: 1,
+                user_id,
+                manga_name: "Test Manga".to_string(),
+                domain: "example.com".to_string(),
+                new_chapters,
+                error,
+                duration_ms: 120,
+            };
+            history::record_result(&pool, run_id, &result).await.unwrap();
+        }
+        history::finish_run(&pool, run_id).await.unwrap();
+
+        let response = app.clone()
+            .oneshot(Request::builder().uri(format!("/sync/runs/{}", run_id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        // Only the results of the user's own sources are shown
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["triggered_by"], "cron");
+        assert_eq!(body["data"]["status"], "finished");
+        assert_eq!(body["data"]["total_sources"], 2);
+        assert_eq!(body["data"]["completed_sources"], 2);
+        assert_eq!(body["data"]["success_count"], 1);
+        assert_eq!(body["data"]["error_count"], 1);
+        assert_eq!(body["data"]["new_chapters"], 3);
+        assert_eq!(body["data"]["results"].as_array().unwrap().len(), 2);
+
+        let response = app.clone()
+            .oneshot(Request::builder().uri("/sync/runs").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["items"].as_array().unwrap().len(), 1);
+
+        for uri in ["/sync/runs?size=0", "/sync/runs?size=101", "/sync/runs?page=0"] {
+            let response = app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", uri);
+        }
+
+        let response = app
+            .oneshot(Request::builder().uri("/sync/runs/999").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_manual_sync_returns_job() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (1, 'Test Manga', 'cover.jpg', 'cover_small.jpg')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO website (id, domain) VALUES (1, 'example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (1, 1, 1, '/manga/test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // A manga and source of another user
+        for query in [
+            "INSERT INTO user (id, name) VALUES (2, 'other')",
+            "INSERT INTO manga (id, user_id, name, cover, cover_small) VALUES (2, 2, 'Other Manga', 'c', 'c')",
+            "INSERT INTO source (id, manga_id, website_id, path) VALUES (2, 2, 1, '/manga/other')",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        for (uri, expected_scope) in [("/sync", "user"), ("/manga/1/sync", "manga"), ("/source/1/sync", "source")] {
+            let response = app.clone()
+                .oneshot(Request::builder().uri(uri).method("POST").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+            let body = body_json(response).await;
+            // example.com has no strategy, so the run has nothing to sync
+            assert_eq!(body["data"]["total_sources"], 0);
+
+            let run_id = body["data"]["run_id"].as_i64().unwrap();
+            let scope: String = sqlx::query_scalar("SELECT scope FROM sync_run WHERE id = ?")
+                .bind(run_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert_eq!(scope, expected_scope);
+        }
+
+        for uri in ["/manga/999/sync", "/source/999/sync", "/manga/2/sync", "/source/2/sync"] {
+            let response = app.clone()
+                .oneshot(Request::builder().uri(uri).method("POST").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manual_sync_returns_running_run() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let running = history::start_run(&pool, SyncTrigger::Manual, SyncScope::User(1), &BTreeMap::from([(1, 3)])).await.unwrap();
+
+        let response = app.clone()
+            .oneshot(Request::builder().uri("/sync").method("POST").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["run_id"], running);
+        assert_eq!(body["data"]["total_sources"], 3);
+
+        let runs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sync_run")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(runs, 1);
+
+        // Once finished, a new run is started
+        history::finish_run(&pool, running).await.unwrap();
+        let response = app
+            .oneshot(Request::builder().uri("/sync").method("POST").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        assert_ne!(body["data"]["run_id"], running);
+    }
+
+    #[tokio::test]
+    async fn test_close_interrupted_runs() {
+        let (_app, pool) = setup_app_no_auth().await;
+
+        let run_id = history::start_run(&pool, SyncTrigger::Manual, SyncScope::All, &BTreeMap::from([(1, 5)])).await.unwrap();
+        assert_eq!(history::close_interrupted_runs(&pool).await.unwrap(), 1);
+
+        let finished: bool = sqlx::query_scalar("SELECT finished_at IS NOT NULL FROM sync_run WHERE id = ?")
+            .bind(run_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(finished);
+        assert_eq!(history::close_interrupted_runs(&pool).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_runs_share_domain_limit() {
+        let (_app, pool) = setup_app_no_auth().await;
+
+        // A website answering slowly, recording how many requests it serves at once
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let site = Router::new().route("/manga/{slug}", get({
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            move || async move {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Html(r#"<li class="wp-manga-chapter"><a href="/manga/test/chapter-2">Chapter 2</a></li>"#)
+            }
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, site).await.unwrap() });
+
+        sqlx::query("INSERT INTO website (id, domain, base_url, chapter_selector) VALUES (1, '127.0.0.1', ?, 'li.wp-manga-chapter > a')")
+            .bind(format!("http://{}", addr))
+            .execute(&pool)
+            .await
+            .unwrap();
+        for id in [1, 2] {
+            sqlx::query("INSERT INTO manga (id, name, cover, cover_small) VALUES (?, ?, 'c', 'c')")
+                .bind(id)
+                .bind(format!("Test Manga {}", id))
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO source (id, manga_id, website_id, path) VALUES (?, ?, 1, ?)")
+                .bind(id)
+                .bind(id)
+                .bind(format!("/manga/test-{}", id))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        // Each run alone would be allowed one request at a time
+        let limiter = Arc::new(DomainRateLimiter::new(1, Duration::ZERO));
+        let first = SyncService::new(pool.clone(), Arc::new(ChapterCache::new()), EventBus::new(), limiter.clone()).await;
+        let second = SyncService::new(pool.clone(), Arc::new(ChapterCache::new()), EventBus::new(), limiter).await;
+
+        let (first, second) = tokio::join!(
+            first.run(SyncTrigger::Manual, SyncScope::Source(1), None),
+            second.run(SyncTrigger::Manual, SyncScope::Source(2), None),
+        );
+        assert_eq!(first.unwrap().1.len(), 1);
+        assert_eq!(second.unwrap().1.len(), 1);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+    }
+}
@@ -11,18 +11,23 @@ fn test_mangaread_count_new_chapters_basic() {
     let chapters = vec![
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-282/".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-281/".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-280/".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-1/".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-0/".to_string(),
+            title: None,
         },
     ];
 
@@ -47,9 +52,11 @@ fn test_mangaread_count_new_chapters_not_found() {
     let chapters = vec![
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-10/".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "https://www.mangaread.org/manga/test/chapter-9/".to_string(),
+            title: None,
         },
     ];
 
@@ -64,18 +71,23 @@ fn test_mangabuddy_count_new_chapters_basic() {
     let chapters = vec![
         ChapterLink {
             href: "/solo-leveling/chapter-227".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "/solo-leveling/chapter-226".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "/solo-leveling/chapter-225".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "/solo-leveling/chapter-2".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "/solo-leveling/chapter-1".to_string(),
+            title: None,
         },
     ];
 
@@ -100,9 +112,11 @@ fn test_mangabuddy_count_new_chapters_not_found() {
     let chapters = vec![
         ChapterLink {
             href: "/solo-leveling/chapter-10".to_string(),
+            title: None,
         },
         ChapterLink {
             href: "/solo-leveling/chapter-9".to_string(),
+            title: None,
         },
     ];
 
@@ -110,6 +124,49 @@ fn test_mangabuddy_count_new_chapters_not_found() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_count_new_chapters_numeric() {
+    let strategy = WebsiteMangabuddyCom::new();
+
+    let chapters: Vec<ChapterLink> = [
+        "/test/chapter-110",
+        "/test/chapter-12-5",
+        "/test/chapter-12",
+        "/test/chapter-11",
+        "/test/chapter-10",
+    ]
+    .iter()
+    .map(|href| ChapterLink {
+        href: href.to_string(),
+        title: None,
+    })
+    .collect();
+
+    // "chapter-10" must not match "chapter-110" and the decimal chapter counts
+    assert_eq!(strategy.count_new_chapters(&chapters, "chapter-10").unwrap(), 4);
+    assert_eq!(strategy.count_new_chapters(&chapters, "10").unwrap(), 4);
+    assert_eq!(strategy.count_new_chapters(&chapters, "12.5").unwrap(), 1);
+    assert_eq!(strategy.count_new_chapters(&chapters, "vol-1-chapter-12").unwrap(), 2);
+}
+
+#[test]
+fn test_count_new_chapters_unparsable_fallback() {
+    let strategy = WebsiteMangabuddyCom::new();
+
+    let chapters = vec![
+        ChapterLink {
+            href: "/test/epilogue".to_string(),
+            title: None,
+        },
+        ChapterLink {
+            href: "/test/prologue".to_string(),
+            title: None,
+        },
+    ];
+
+    assert_eq!(strategy.count_new_chapters(&chapters, "prologue").unwrap(), 1);
+}
+
 #[test]
 fn test_strategy_domains() {
     let mangaread = WebsiteMangareadOrg::new();
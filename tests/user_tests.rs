@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        middleware,
+        Router,
+        routing::{delete, get},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::auth::middleware::auth_middleware;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+
+    async fn setup_app() -> (Router, SqlitePool, String) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_user.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+        let legacy_key = km.refresh_key().unwrap();
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/manga", get(handlers::manga::list_manga).post(handlers::manga::create_manga))
+            .route("/manga/{id}", get(handlers::manga::get_manga).patch(handlers::manga::update_manga).delete(handlers::manga::delete_manga))
+            .route("/manga/{id}/history", get(handlers::manga::get_manga_history))
+            .route("/source", get(handlers::source::list_sources))
+            .route("/user", get(handlers::user::list_users).post(handlers::user::create_user))
+            .route("/user/{id}", delete(handlers::user::delete_user))
+            .route("/key/api", get(handlers::key::list_api_keys).post(handlers::key::create_api_key))
+            .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
+            .with_state(state);
+
+        (app, pool, legacy_key)
+    }
+
+    async fn send(app: &Router, method: &str, uri: &str, token: &str, body: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            builder = builder.header("content-type", "application/json");
+        }
+        let request = builder
+            .body(body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty))
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Creates a user and a write key bound to them
+    async fn create_user(app: &Router, admin: &str, name: &str) -> (i64, String) {
+        let (status, json) = send(app, "POST", "/user", admin, Some(&format!(r#"{{"name": "{}"}}"#, name))).await;
+        assert_eq!(status, StatusCode::OK);
+        let user_id = json["data"]["id"].as_i64().unwrap();
+
+        let body = format!(r#"{{"name": "{}", "scope": "write", "user_id": {}}}"#, name, user_id);
+        let (status, json) = send(app, "POST", "/key/api", admin, Some(&body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["user_id"], user_id);
+
+        (user_id, json["data"]["key"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_libraries_are_separate() {
+        let (app, pool, legacy_key) = setup_app().await;
+        sqlx::query("INSERT INTO website (domain) VALUES ('example.com')").execute(&pool).await.unwrap();
+
+        let (_, alice) = create_user(&app, &legacy_key, "alice").await;
+        let (_, bob) = create_user(&app, &legacy_key, "bob").await;
+
+        // Both can add the same manga from the same source
+        let manga = Some(r#"{"name": "Test Manga", "cover": "c.jpg", "cover_small": "cs.jpg", "source_path": "/manga/test", "website_domain": "example.com"}"#);
+        assert_eq!(send(&app, "POST", "/manga", &alice, manga).await.0, StatusCode::OK);
+        assert_eq!(send(&app, "POST", "/manga", &bob, manga).await.0, StatusCode::OK);
+
+        let (_, json) = send(&app, "GET", "/manga", &alice, None).await;
+        let list = json["data"]["items"].as_array().unwrap();
+        assert_eq!(list.len(), 1);
+        let alice_manga = list[0]["id"].as_i64().unwrap();
+
+        let (_, json) = send(&app, "GET", "/manga", &bob, None).await;
+        let bob_manga = json["data"]["items"][0]["id"].as_i64().unwrap();
+        assert_ne!(alice_manga, bob_manga);
+
+        let (_, json) = send(&app, "GET", "/source", &alice, None).await;
+        assert_eq!(json["data"]["items"].as_array().unwrap().len(), 1);
+        assert_eq!(json["data"]["items"][0]["manga_id"], alice_manga);
+
+        // Reading progress is per user
+        let uri = format!("/manga/{}", alice_manga);
+        let chapter = Some(r#"{"chapter_number": "chapter-5"}"#);
+        assert_eq!(send(&app, "PATCH", &uri, &alice, chapter).await.0, StatusCode::OK);
+
+        let (_, json) = send(&app, "GET", &uri, &alice, None).await;
+        assert_eq!(json["data"]["current_chapter"], "chapter-5");
+        let (_, json) = send(&app, "GET", &format!("/manga/{}", bob_manga), &bob, None).await;
+        assert!(json["data"]["current_chapter"].is_null());
+
+        // Other users' manga are invisible
+        assert_eq!(send(&app, "GET", &uri, &bob, None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(send(&app, "PATCH", &uri, &bob, chapter).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(send(&app, "DELETE", &uri, &bob, None).await.0, StatusCode::NOT_FOUND);
+        let (_, json) = send(&app, "GET", &format!("{}/history", uri), &bob, None).await;
+        assert!(json["data"]["items"].as_array().unwrap().is_empty());
+
+        // The legacy key belongs to the default user, whose library is empty
+        let (_, json) = send(&app, "GET", "/manga", &legacy_key, None).await;
+        assert!(json["data"]["items"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_user() {
+        let (app, pool, legacy_key) = setup_app().await;
+
+        let (user_id, key) = create_user(&app, &legacy_key, "alice").await;
+        let manga = Some(r#"{"name": "Test Manga", "cover": "c.jpg", "cover_small": "cs.jpg"}"#);
+        assert_eq!(send(&app, "POST", "/manga", &key, manga).await.0, StatusCode::OK);
+
+        // Write keys cannot manage users
+        assert_eq!(send(&app, "GET", "/user", &key, None).await.0, StatusCode::FORBIDDEN);
+
+        assert_eq!(send(&app, "DELETE", "/user/1", &legacy_key, None).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(send(&app, "DELETE", &format!("/user/{}", user_id), &legacy_key, None).await.0, StatusCode::OK);
+        assert_eq!(send(&app, "DELETE", &format!("/user/{}", user_id), &legacy_key, None).await.0, StatusCode::NOT_FOUND);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM manga").fetch_one(&pool).await.unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(send(&app, "GET", "/manga", &key, None).await.0, StatusCode::FORBIDDEN);
+
+        let (_, json) = send(&app, "GET", "/user", &legacy_key, None).await;
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["items"][0]["name"], "default");
+    }
+}
@@ -0,0 +1,364 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, Bytes},
+        extract::State,
+        http::{HeaderMap, Request, StatusCode},
+        Extension,
+        Router,
+        routing::{get, post, put},
+    };
+    use tower::ServiceExt;
+    use sqlx::SqlitePool;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::api_key::AuthContext;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::webhook::{self, NewChaptersEvent, WebhookDispatcher};
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        statuses: Arc<Mutex<VecDeque<StatusCode>>>,
+        received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    }
+
+    async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: Bytes) -> StatusCode {
+        receiver.received.lock().unwrap().push((headers, String::from_utf8(body.to_vec()).unwrap()));
+        receiver.statuses.lock().unwrap().pop_front().unwrap_or(StatusCode::OK)
+    }
+
+    /// Starts a local webhook target answering with `statuses` in order, then 200
+    async fn start_receiver(statuses: &[StatusCode]) -> (String, Receiver) {
+        let receiver = Receiver::default();
+        receiver.statuses.lock().unwrap().extend(statuses.iter().copied());
+
+        let app = Router::new().route("/hook", post(receive)).with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}/hook", addr), receiver)
+    }
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // Retry immediately to keep the tests fast
+        sqlx::query("UPDATE setting SET value = '0' WHERE key = 'WEBHOOK_RETRY_DELAY_MS'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // The receivers listen on the loopback address
+        sqlx::query("UPDATE setting SET value = 'true' WHERE key = 'WEBHOOK_ALLOW_PRIVATE_HOSTS'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let key_path = "test_key_webhook.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/webhook", get(handlers::webhook::list_webhooks).post(handlers::webhook::create_webhook))
+            .route("/webhook/{id}", put(handlers::webhook::update_webhook).delete(handlers::webhook::delete_webhook))
+            .route("/webhook/{id}/deliveries", get(handlers::webhook::list_webhook_deliveries))
+            .route("/webhook/{id}/test", post(handlers::webhook::test_webhook))
+            .layer(Extension(AuthContext::legacy()))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    fn json_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_json(response: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn new_chapters_event() -> NewChaptersEvent {
+        NewChaptersEvent {
+            manga_id: 1,
+            manga_name: "Test Manga".to_string(),
+            source_id: 1,
+            domain: "example.com".to_string(),
+            previous_unread: 0,
+            number_unread_chapter: 2,
+        }
+    }
+
+    /// Deliveries run in the background: waits until `count` have finished
+    async fn wait_for_deliveries(pool: &SqlitePool, count: i64) {
+        let mut finished = 0;
+        for _ in 0..50 {
+            finished = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM webhook_delivery WHERE finished_at IS NOT NULL")
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            if finished >= count {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(finished, count);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_crud() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        // Webhooks of other users cannot be seen or changed
+        sqlx::query("INSERT INTO user (id, name) VALUES (2, 'other')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let other_id = sqlx::query("INSERT INTO webhook (user_id, url) VALUES (2, 'https://example.com/other')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        let response = app.clone()
+            .oneshot(json_request("PUT", &format!("/webhook/{}", other_id), serde_json::json!({ "url": "http://127.0.0.1/stolen" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app.clone()
+            .oneshot(Request::builder().method("DELETE").uri(format!("/webhook/{}", other_id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app.clone()
+            .oneshot(json_request("POST", "/webhook", serde_json::json!({ "url": "ftp://127.0.0.1" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app.clone()
+            .oneshot(json_request("POST", "/webhook", serde_json::json!({ "url": "http://127.0.0.1/hook", "events": "unknown" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app.clone()
+            .oneshot(json_request("POST", "/webhook", serde_json::json!({ "url": "http://127.0.0.1/hook", "secret": "s3cret" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["events"], "new_chapters");
+        assert_eq!(body["data"]["has_secret"], true);
+        assert!(body["data"].get("secret").is_none());
+        let id = body["data"]["id"].as_i64().unwrap();
+
+        let response = app.clone()
+            .oneshot(json_request(
+                "PUT",
+                &format!("/webhook/{}", id),
+                serde_json::json!({ "url": "http://127.0.0.1/hook", "events": "new_chapters, sync_finished", "enabled": false }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["events"], "new_chapters,sync_finished");
+        assert_eq!(body["data"]["has_secret"], false);
+        assert_eq!(body["data"]["enabled"], false);
+
+        let response = app.clone()
+            .oneshot(Request::builder().method("DELETE").uri(format!("/webhook/{}", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(Request::builder().uri("/webhook").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_ping_is_signed() {
+        let (app, _pool) = setup_app_no_auth().await;
+        let (url, receiver) = start_receiver(&[]).await;
+
+        let response = app.clone()
+            .oneshot(json_request("POST", "/webhook", serde_json::json!({ "url": url, "secret": "s3cret" })))
+            .await
+            .unwrap();
+        let id = body_json(response).await["data"]["id"].as_i64().unwrap();
+
+        let response = app.clone()
+            .oneshot(Request::builder().method("POST").uri(format!("/webhook/{}/test", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["success"], true);
+        assert_eq!(body["data"]["attempts"], 1);
+        assert_eq!(body["data"]["status_code"], 200);
+
+        let received = receiver.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        let (headers, payload) = &received[0];
+        assert_eq!(headers[webhook::EVENT_HEADER], "ping");
+        assert_eq!(headers[webhook::SIGNATURE_HEADER], webhook::sign("s3cret", payload.as_bytes()).as_str());
+        let payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(payload["event"], "ping");
+        assert_eq!(payload["data"]["webhook_id"], id);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_delivery_retries() {
+        let (app, pool) = setup_app_no_auth().await;
+        let (url, receiver) = start_receiver(&[
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::OK,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::BAD_GATEWAY,
+        ])
+        .await;
+
+        let response = app.clone()
+            .oneshot(json_request("POST", "/webhook", serde_json::json!({ "url": url })))
+            .await
+            .unwrap();
+        let id = body_json(response).await["data"]["id"].as_i64().unwrap();
+
+        // Pings are not retried
+        let response = app.clone()
+            .oneshot(Request::builder().method("POST").uri(format!("/webhook/{}/test", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["success"], false);
+        assert_eq!(body["data"]["attempts"], 1);
+        assert_eq!(body["data"]["status_code"], 500);
+
+        // Fails once, then succeeds
+        let dispatcher = WebhookDispatcher::new(pool.clone()).await;
+        dispatcher.dispatch(1, webhook::EVENT_NEW_CHAPTERS, &new_chapters_event()).await;
+        wait_for_deliveries(&pool, 2).await;
+
+        // Fails every attempt
+        dispatcher.dispatch(1, webhook::EVENT_NEW_CHAPTERS, &new_chapters_event()).await;
+        wait_for_deliveries(&pool, 3).await;
+
+        assert_eq!(receiver.received.lock().unwrap().len(), 6);
+
+        let response = app
+            .oneshot(Request::builder().uri(format!("/webhook/{}/deliveries", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        let deliveries = body["data"]["items"].as_array().unwrap();
+        assert_eq!(deliveries.len(), 3);
+        assert_eq!(deliveries[0]["success"], false);
+        assert_eq!(deliveries[0]["attempts"], 3);
+        assert_eq!(deliveries[0]["status_code"], 502);
+        assert!(deliveries[0]["error"].as_str().unwrap().contains("502"));
+        assert_eq!(deliveries[1]["success"], true);
+        assert_eq!(deliveries[1]["attempts"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_only_to_subscribed_webhooks() {
+        let (_app, pool) = setup_app_no_auth().await;
+        let (url, receiver) = start_receiver(&[]).await;
+
+        sqlx::query("INSERT INTO user (id, name) VALUES (2, 'other')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO webhook (user_id, url, events) VALUES (1, ?, 'new_chapters'), (1, ?, 'sync_finished')")
+            .bind(&url)
+            .bind(&url)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO webhook (user_id, url, events, enabled) VALUES (1, ?, 'new_chapters', 0)")
+            .bind(&url)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Webhooks of other users are not notified of the user's manga
+        sqlx::query("INSERT INTO webhook (user_id, url, events) VALUES (2, ?, 'new_chapters')")
+            .bind(&url)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dispatcher = WebhookDispatcher::new(pool.clone()).await;
+        dispatcher.dispatch(1, webhook::EVENT_NEW_CHAPTERS, &new_chapters_event()).await;
+        wait_for_deliveries(&pool, 1).await;
+
+        let received = receiver.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&received[0].1).unwrap();
+        assert_eq!(payload["event"], "new_chapters");
+        assert_eq!(payload["data"]["manga_name"], "Test Manga");
+        assert_eq!(payload["data"]["number_unread_chapter"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_on_private_host_is_rejected() {
+        let (app, pool) = setup_app_no_auth().await;
+        let (url, receiver) = start_receiver(&[]).await;
+
+        sqlx::query("UPDATE setting SET value = 'false' WHERE key = 'WEBHOOK_ALLOW_PRIVATE_HOSTS'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for target in [url.as_str(), "http://169.254.169.254/latest/meta-data", "http://[::1]/hook"] {
+            let response = app.clone()
+                .oneshot(json_request("POST", "/webhook", serde_json::json!({ "url": target })))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", target);
+        }
+
+        // A host that resolved to a public address when registered is checked
+        // again on delivery
+        let id = sqlx::query("INSERT INTO webhook (user_id, url) VALUES (1, ?)")
+            .bind(&url)
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let response = app
+            .oneshot(Request::builder().method("POST").uri(format!("/webhook/{}/test", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = body_json(response).await;
+        assert_eq!(body["data"]["success"], false);
+        assert!(body["data"]["error"].as_str().unwrap().contains("non-public"));
+        assert!(receiver.received.lock().unwrap().is_empty());
+    }
+}
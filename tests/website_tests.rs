@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Router,
+        routing::{get},
+    };
+    use tower::ServiceExt;
+    use sqlx::{Row, SqlitePool};
+    use std::sync::Arc;
+    use manga_sync::handlers;
+    use manga_sync::cache::ChapterCache;
+    use manga_sync::state::AppState;
+    use manga_sync::events::EventBus;
+    use manga_sync::sync::rate_limit::DomainRateLimiter;
+    use manga_sync::auth::key_manager::KeyManager;
+    use manga_sync::sync::strategies::StrategyRegistry;
+
+    async fn setup_app_no_auth() -> (Router, SqlitePool) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let key_path = "test_key_website.pub";
+        let _ = std::fs::remove_file(key_path);
+        let km = Arc::new(KeyManager::new(key_path, 90, 365).unwrap());
+
+        let state = AppState {
+            pool: pool.clone(),
+            cache: Arc::new(ChapterCache::new()),
+            key_manager: km,
+            events: EventBus::new(),
+            limiter: Arc::new(DomainRateLimiter::default()),
+        };
+
+        let app = Router::new()
+            .route("/website", get(handlers::website::list_websites))
+            .route("/website/{domain}", get(handlers::website::check_website).post(handlers::website::create_website).put(handlers::website::update_website_config))
+            .with_state(state);
+
+        (app, pool)
+    }
+
+    fn json_request(method: &str, uri: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_website_without_config() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/website/example.com")
+                    .method("POST")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let registry = StrategyRegistry::load(&pool).await.unwrap();
+        assert!(registry.get("example.com").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_website_with_config() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/website/madara.example.org",
+                r#"{"chapter_selector": "li.wp-manga-chapter > a"}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let row = sqlx::query("SELECT chapter_selector FROM website WHERE domain = ?")
+            .bind("madara.example.org")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("chapter_selector"), "li.wp-manga-chapter > a");
+
+        let registry = StrategyRegistry::load(&pool).await.unwrap();
+        let strategy = registry.get("madara.example.org").expect("Configured strategy should be registered");
+        assert_eq!(strategy.domain(), "madara.example.org");
+        assert!(registry.get("www.mangaread.org").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_create_website_invalid_config() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/website/example.com",
+                r#"{"chapter_selector": "li.chapter > a", "external_id_regex": "(unclosed"}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let check = sqlx::query("SELECT id FROM website WHERE domain = ?")
+            .bind("example.com")
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+        assert!(check.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_website_config() {
+        let (app, pool) = setup_app_no_auth().await;
+
+        sqlx::query("INSERT INTO website (domain) VALUES (?)")
+            .bind("example.com")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let response = app.clone()
+            .oneshot(json_request(
+                "PUT",
+                "/website/example.com",
+                r#"{"base_url": "https://example.com", "chapter_selector": "a.chapter"}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let registry = StrategyRegistry::load(&pool).await.unwrap();
+        assert!(registry.get("example.com").is_some());
+
+        // Replacing with an empty config removes the strategy
+        let response = app.clone()
+            .oneshot(json_request("PUT", "/website/example.com", "{}"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let registry = StrategyRegistry::load(&pool).await.unwrap();
+        assert!(registry.get("example.com").is_none());
+
+        let response = app
+            .oneshot(json_request("PUT", "/website/unknown.com", "{}"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}